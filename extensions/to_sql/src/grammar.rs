@@ -452,6 +452,11 @@ impl IntoSqlBuilder for LiteralsAndKeywords {
             LiteralsAndKeywords::IntegerLit(val) => format!("{}", val),
             LiteralsAndKeywords::UnsignedLit(val) => format!("{}", val),
             LiteralsAndKeywords::FloatingLit(val) => format!("{}", val),
+            LiteralsAndKeywords::QuantityLit(_, _) => {
+                return Ok(Box::new(UnsupportedBuilder {
+                    message: "QuantityLit not implemented yet".to_string(),
+                }))
+            }
             LiteralsAndKeywords::FStringList(_) => {
                 return Ok(Box::new(UnsupportedBuilder {
                     message: "FStringList not implemented yet".to_string(),
@@ -3,7 +3,7 @@ use std::fmt;
 
 use pyo3::{types::PyBytes, IntoPyObject, Python};
 use pyo3::{Bound, IntoPyObjectExt, PyAny, PyErr};
-use rscel::CelValue;
+use rscel::{CelOptional, CelQuantity, CelValue, CelZonedTimestamp};
 
 use crate::cel_py_object::CelPyObject;
 
@@ -108,6 +108,31 @@ impl<'py, 'a> IntoPyObject<'py> for PyCelValueRef<'a> {
             Duration(d) => d.into_pyobject_or_pyerr(py).map(|o| o.into_any()),
             Null => Ok(py.None().bind(py).to_owned()),
             Dyn(d) => {
+                if let Some(opt) = d.any_ref().downcast_ref::<CelOptional>() {
+                    // Optionals unwrap to their value, or None when empty
+                    return match opt.value() {
+                        Some(v) => PyCelValueRef(v).into_pyobject(py),
+                        None => Ok(py.None().bind(py).to_owned()),
+                    };
+                }
+
+                if let Some(zoned) = d.any_ref().downcast_ref::<CelZonedTimestamp>() {
+                    // Zoned timestamps become aware datetimes at the zone's offset
+                    return zoned
+                        .local()
+                        .fixed_offset()
+                        .into_pyobject_or_pyerr(py)
+                        .map(|o| o.into_any());
+                }
+
+                if let Some(quantity) = d.any_ref().downcast_ref::<CelQuantity>() {
+                    // Quantities become their display string, like "5 kg"
+                    return quantity
+                        .to_string()
+                        .into_pyobject_or_pyerr(py)
+                        .map(|o| o.into_any());
+                }
+
                 match d.any_ref().downcast_ref::<CelPyObject>() {
                     Some(obj) => Ok(obj.as_inner().clone().bind(py).to_owned()),
                     // This *should* never happen. If this downcase were to fail that would
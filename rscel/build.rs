@@ -3,6 +3,21 @@ use std::env;
 fn main() {
     println!("cargo::rustc-check-cfg=cfg(test_protos)");
     println!("cargo:rerun-if-env-changed=RSCEL_TEST_PROTO");
+    println!("cargo:rerun-if-changed=protos");
+
+    if env::var("CARGO_FEATURE_PROTOBUF").is_ok() {
+        protobuf_codegen::Codegen::new()
+            .protoc()
+            .protoc_path(&protoc_bin_vendored::protoc_bin_path().unwrap())
+            .include("protos")
+            .include(protoc_bin_vendored::include_path().unwrap())
+            .inputs([
+                "protos/google/api/expr/v1alpha1/syntax.proto",
+                "protos/google/api/expr/v1alpha1/checked.proto",
+            ])
+            .cargo_out_dir("cel_protos")
+            .run_from_script();
+    }
 
     if let Ok(_) = env::var("RSCEL_TEST_PROTO") {
         println!("cargo:rustc-cfg=test_protos");
@@ -33,6 +33,10 @@ impl<T> AstNode<T> {
     pub fn node(&self) -> &T {
         &self.node
     }
+
+    pub fn node_mut(&mut self) -> &mut T {
+        &mut self.node
+    }
 }
 
 #[cfg(test)]
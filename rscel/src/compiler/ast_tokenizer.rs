@@ -0,0 +1,480 @@
+use super::{
+    ast_node::AstNode,
+    grammar::*,
+    source_location::SourceLocation,
+    source_range::SourceRange,
+    syntax_error::SyntaxError,
+    tokenizer::{TokenWithLoc, Tokenizer},
+    tokens::{FStringSegment, Token},
+};
+
+/// Tokenizer that replays an already constructed AST.
+///
+/// The tokens are generated by walking the AST and a matching source string is
+/// synthesized along the way so that any errors raised during compilation (or
+/// evaluation) can still point at a meaningful location. Because the grammar
+/// types encode operator precedence, replaying the tokens through the compiler
+/// yields an identical tree.
+pub struct AstTokenizer {
+    source: String,
+    tokens: Vec<TokenWithLoc>,
+    pos: usize,
+}
+
+impl AstTokenizer {
+    pub fn from_ast(ast: &AstNode<Expr>) -> AstTokenizer {
+        let mut emitter = Emitter::new();
+        emitter.expr(ast.node());
+
+        AstTokenizer {
+            source: emitter.source,
+            tokens: emitter.tokens,
+            pos: 0,
+        }
+    }
+}
+
+impl Tokenizer for AstTokenizer {
+    fn peek(&mut self) -> Result<Option<&TokenWithLoc>, SyntaxError> {
+        Ok(self.tokens.get(self.pos))
+    }
+
+    fn next(&mut self) -> Result<Option<TokenWithLoc>, SyntaxError> {
+        let token = self.tokens.get(self.pos).cloned();
+
+        if token.is_some() {
+            self.pos += 1;
+        }
+
+        Ok(token)
+    }
+
+    fn source<'a>(&'a self) -> &'a str {
+        &self.source
+    }
+
+    fn location(&self) -> SourceLocation {
+        match self.tokens.get(self.pos) {
+            Some(t) => t.loc.start(),
+            None => SourceLocation::new(0, self.source.chars().count()),
+        }
+    }
+}
+
+struct Emitter {
+    source: String,
+    tokens: Vec<TokenWithLoc>,
+    col: usize,
+}
+
+impl Emitter {
+    fn new() -> Emitter {
+        Emitter {
+            source: String::new(),
+            tokens: Vec::new(),
+            col: 0,
+        }
+    }
+
+    fn token(&mut self, token: Token, text: &str) {
+        let start = SourceLocation::new(0, self.col);
+        self.text(text);
+        let end = SourceLocation::new(0, self.col);
+
+        self.tokens
+            .push(TokenWithLoc::new(token, SourceRange::new(start, end)));
+    }
+
+    fn text(&mut self, text: &str) {
+        self.source.push_str(text);
+        self.col += text.chars().count();
+    }
+
+    fn space(&mut self) {
+        self.text(" ");
+    }
+
+    fn binary_op(&mut self, token: Token, text: &str) {
+        self.space();
+        self.token(token, text);
+        self.space();
+    }
+
+    fn expr(&mut self, expr: &Expr) {
+        match expr {
+            Expr::Ternary {
+                condition,
+                true_clause,
+                false_clause,
+            } => {
+                self.conditional_or(condition.node());
+                self.binary_op(Token::Question, "?");
+                self.conditional_or(true_clause.node());
+                self.binary_op(Token::Colon, ":");
+                self.expr(false_clause.node());
+            }
+            Expr::Match { condition, cases } => {
+                self.token(Token::Match, "match");
+                self.space();
+                self.expr(condition.node());
+                self.space();
+                self.token(Token::LBrace, "{");
+
+                for (i, case) in cases.iter().enumerate() {
+                    if i > 0 {
+                        self.token(Token::Comma, ",");
+                    }
+                    self.space();
+                    self.match_case(case.node());
+                }
+
+                self.space();
+                self.token(Token::RBrace, "}");
+            }
+            Expr::Unary(or) => self.conditional_or(or.node()),
+        }
+    }
+
+    fn match_case(&mut self, case: &MatchCase) {
+        self.token(Token::Case, "case");
+        self.space();
+
+        match case.pattern.node() {
+            MatchPattern::Cmp { op, or } => {
+                let (token, text) = match op.node() {
+                    MatchCmpOp::Eq => (Token::EqualEqual, "=="),
+                    MatchCmpOp::Neq => (Token::NotEqual, "!="),
+                    MatchCmpOp::Gt => (Token::GreaterThan, ">"),
+                    MatchCmpOp::Ge => (Token::GreaterEqual, ">="),
+                    MatchCmpOp::Lt => (Token::LessThan, "<"),
+                    MatchCmpOp::Le => (Token::LessEqual, "<="),
+                };
+                self.token(token, text);
+                self.conditional_or(or.node());
+            }
+            MatchPattern::Type(t) => self.keyword_ident(t.node().as_type_str()),
+            MatchPattern::Any(_) => self.token(Token::Ident("_".to_owned()), "_"),
+        }
+
+        self.token(Token::Colon, ":");
+        self.space();
+        self.expr(case.expr.node());
+    }
+
+    fn conditional_or(&mut self, or: &ConditionalOr) {
+        match or {
+            ConditionalOr::Binary { lhs, rhs } => {
+                self.conditional_or(lhs.node());
+                self.binary_op(Token::OrOr, "||");
+                self.conditional_and(rhs.node());
+            }
+            ConditionalOr::Unary(and) => self.conditional_and(and.node()),
+        }
+    }
+
+    fn conditional_and(&mut self, and: &ConditionalAnd) {
+        match and {
+            ConditionalAnd::Binary { lhs, rhs } => {
+                self.conditional_and(lhs.node());
+                self.binary_op(Token::AndAnd, "&&");
+                self.relation(rhs.node());
+            }
+            ConditionalAnd::Unary(relation) => self.relation(relation.node()),
+        }
+    }
+
+    fn relation(&mut self, relation: &Relation) {
+        match relation {
+            Relation::Binary { lhs, op, rhs } => {
+                self.relation(lhs.node());
+                let (token, text) = match op {
+                    Relop::Le => (Token::LessEqual, "<="),
+                    Relop::Lt => (Token::LessThan, "<"),
+                    Relop::Ge => (Token::GreaterEqual, ">="),
+                    Relop::Gt => (Token::GreaterThan, ">"),
+                    Relop::Eq => (Token::EqualEqual, "=="),
+                    Relop::Ne => (Token::NotEqual, "!="),
+                    Relop::In => (Token::In, "in"),
+                };
+                self.binary_op(token, text);
+                self.addition(rhs.node());
+            }
+            Relation::Unary(addition) => self.addition(addition.node()),
+        }
+    }
+
+    fn addition(&mut self, addition: &Addition) {
+        match addition {
+            Addition::Binary { lhs, op, rhs } => {
+                self.addition(lhs.node());
+                match op {
+                    AddOp::Add => self.binary_op(Token::Add, "+"),
+                    AddOp::Sub => self.binary_op(Token::Minus, "-"),
+                }
+                self.multiplication(rhs.node());
+            }
+            Addition::Unary(mult) => self.multiplication(mult.node()),
+        }
+    }
+
+    fn multiplication(&mut self, mult: &Multiplication) {
+        match mult {
+            Multiplication::Binary { lhs, op, rhs } => {
+                self.multiplication(lhs.node());
+                match op {
+                    MultOp::Mult => self.binary_op(Token::Multiply, "*"),
+                    MultOp::Div => self.binary_op(Token::Divide, "/"),
+                    MultOp::Mod => self.binary_op(Token::Mod, "%"),
+                }
+                self.unary(rhs.node());
+            }
+            Multiplication::Unary(unary) => self.unary(unary.node()),
+        }
+    }
+
+    fn unary(&mut self, unary: &Unary) {
+        match unary {
+            Unary::Member(member) => self.member(member.node()),
+            Unary::NotMember { nots, member } => {
+                let mut curr = nots.node();
+                while let NotList::List { tail } = curr {
+                    self.token(Token::Not, "!");
+                    curr = tail.node();
+                }
+                self.member(member.node());
+            }
+            Unary::NegMember { negs, member } => {
+                let mut curr = negs.node();
+                while let NegList::List { tail } = curr {
+                    self.token(Token::Minus, "-");
+                    curr = tail.node();
+                }
+                self.member(member.node());
+            }
+        }
+    }
+
+    fn member(&mut self, member: &Member) {
+        self.primary(member.primary.node());
+
+        for prime in member.member.iter() {
+            match prime.node() {
+                MemberPrime::MemberAccess { ident } => {
+                    self.token(Token::Dot, ".");
+                    self.token(Token::Ident(ident.node().0.clone()), &ident.node().0);
+                }
+                MemberPrime::Call { call } => {
+                    self.token(Token::LParen, "(");
+                    // The compiler stores call arguments in reverse order
+                    self.expr_list(call.node().exprs.iter().rev());
+                    self.token(Token::RParen, ")");
+                }
+                MemberPrime::ArrayAccess { access } => {
+                    self.token(Token::LBracket, "[");
+                    self.expr(access.node());
+                    self.token(Token::RBracket, "]");
+                }
+                MemberPrime::Empty => {}
+            }
+        }
+    }
+
+    fn expr_list<'a>(&mut self, exprs: impl Iterator<Item = &'a AstNode<Expr>>) {
+        for (i, expr) in exprs.enumerate() {
+            if i > 0 {
+                self.token(Token::Comma, ",");
+                self.space();
+            }
+            self.expr(expr.node());
+        }
+    }
+
+    fn primary(&mut self, primary: &Primary) {
+        match primary {
+            Primary::Type => self.token(Token::Ident("type".to_owned()), "type"),
+            Primary::Ident(ident) => self.token(Token::Ident(ident.0.clone()), &ident.0),
+            Primary::Parens(expr) => {
+                self.token(Token::LParen, "(");
+                self.expr(expr.node());
+                self.token(Token::RParen, ")");
+            }
+            Primary::ListConstruction(list) => {
+                self.token(Token::LBracket, "[");
+                self.expr_list(list.node().exprs.iter());
+                self.token(Token::RBracket, "]");
+            }
+            Primary::ObjectInit(inits) => {
+                self.token(Token::LBrace, "{");
+                for (i, init) in inits.node().inits.iter().enumerate() {
+                    if i > 0 {
+                        self.token(Token::Comma, ",");
+                        self.space();
+                    }
+                    self.expr(init.node().key.node());
+                    self.token(Token::Colon, ":");
+                    self.space();
+                    self.expr(init.node().value.node());
+                }
+                self.token(Token::RBrace, "}");
+            }
+            Primary::Literal(literal) => self.literal(literal),
+        }
+    }
+
+    fn literal(&mut self, literal: &LiteralsAndKeywords) {
+        match literal {
+            LiteralsAndKeywords::Type => self.keyword_ident("type"),
+            LiteralsAndKeywords::NullType => self.keyword_ident("null_type"),
+            LiteralsAndKeywords::Int => self.keyword_ident("int"),
+            LiteralsAndKeywords::Uint => self.keyword_ident("uint"),
+            LiteralsAndKeywords::Float => self.keyword_ident("double"),
+            LiteralsAndKeywords::Bool => self.keyword_ident("bool"),
+            LiteralsAndKeywords::String => self.keyword_ident("string"),
+            LiteralsAndKeywords::Bytes => self.keyword_ident("bytes"),
+            LiteralsAndKeywords::Timestamp => self.keyword_ident("timestamp"),
+            LiteralsAndKeywords::Duration => self.keyword_ident("duration"),
+            LiteralsAndKeywords::NullLit => self.token(Token::Null, "null"),
+            // Int tokens are unsigned, negative values are spelled `(-n)`
+            LiteralsAndKeywords::IntegerLit(i) if *i < 0 => {
+                self.token(Token::LParen, "(");
+                self.token(Token::Minus, "-");
+                self.token(
+                    Token::IntLit(i.unsigned_abs()),
+                    &i.unsigned_abs().to_string(),
+                );
+                self.token(Token::RParen, ")");
+            }
+            LiteralsAndKeywords::IntegerLit(i) => {
+                self.token(Token::IntLit(*i as u64), &i.to_string())
+            }
+            LiteralsAndKeywords::UnsignedLit(u) => {
+                self.token(Token::UIntLit(*u), &format!("{}u", u))
+            }
+            // Non-finite floats have no literal form, spelled `double('inf')`
+            LiteralsAndKeywords::FloatingLit(f) if !f.is_finite() => {
+                let text = if f.is_nan() {
+                    "NaN"
+                } else if *f > 0.0 {
+                    "inf"
+                } else {
+                    "-inf"
+                };
+
+                self.keyword_ident("double");
+                self.token(Token::LParen, "(");
+                self.token(Token::StringLit(text.to_owned()), &format!("'{}'", text));
+                self.token(Token::RParen, ")");
+            }
+            LiteralsAndKeywords::FloatingLit(f) => {
+                self.token(Token::FloatLit(*f), &format!("{:?}", f))
+            }
+            LiteralsAndKeywords::QuantityLit(f, unit) => self.token(
+                Token::QuantityLit(*f, unit.clone()),
+                &format!("{}{}", f, unit),
+            ),
+            LiteralsAndKeywords::FStringList(segments) => {
+                let mut text = String::from("f\"");
+                for segment in segments.iter() {
+                    match segment {
+                        FStringSegment::Lit(s) => {
+                            text.push_str(&escape_str(s).replace('{', "{{").replace('}', "}}"))
+                        }
+                        FStringSegment::Expr(e) => {
+                            text.push('{');
+                            text.push_str(e);
+                            text.push('}');
+                        }
+                        FStringSegment::FmtExpr(e, spec) => {
+                            text.push('{');
+                            text.push_str(e);
+                            text.push(':');
+                            text.push_str(spec);
+                            text.push('}');
+                        }
+                    }
+                }
+                text.push('"');
+
+                self.token(Token::FStringLit(segments.clone()), &text)
+            }
+            LiteralsAndKeywords::StringLit(s) => self.token(
+                Token::StringLit(s.clone()),
+                &format!("\"{}\"", escape_str(s)),
+            ),
+            LiteralsAndKeywords::ByteStringLit(b) => {
+                let mut text = String::from("b\"");
+                for byte in b.iter() {
+                    text.extend(std::ascii::escape_default(*byte).map(|c| c as char));
+                }
+                text.push('"');
+
+                self.token(Token::ByteStringLit(b.clone().into()), &text)
+            }
+            LiteralsAndKeywords::BooleanLit(b) => self.token(Token::BoolLit(*b), &b.to_string()),
+        }
+    }
+
+    fn keyword_ident(&mut self, name: &str) {
+        self.token(Token::Ident(name.to_owned()), name)
+    }
+}
+
+fn escape_str(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+#[cfg(test)]
+mod test {
+    use test_case::test_case;
+
+    use super::AstTokenizer;
+    use crate::{BindContext, CelContext, CelValue, ExprBuilder, Program, Tokenizer};
+
+    #[test_case("3 + 4 * foo"; "arithmetic")]
+    #[test_case("!a.b(1, 2)[3] && -x < 4u || y in [1, 2.5, 'foo']"; "members and relations")]
+    #[test_case("a ? {'k': b\"\\x00\"} : (c - 1) % 2"; "ternary")]
+    #[test_case("f'{a} is {{not}} \"quoted\"'"; "format string")]
+    #[test_case("f'{a:>5} {b ? 1 : 2:.2f}'"; "format string spec")]
+    #[test_case("match x { case int: 1, case >3: 2, case _: 3 }"; "match")]
+    #[test_case("match x { case null_type: 1, case double: 2, case _: 3 }"; "match type names")]
+    #[test_case("5kg + 2.5lb * x"; "quantity")]
+    fn round_trip(source: &str) {
+        let prog = Program::from_source(source).unwrap();
+        let ast = prog.ast().unwrap();
+
+        let tokenizer = AstTokenizer::from_ast(ast);
+        let synthesized = Program::from_source(tokenizer.source()).unwrap();
+
+        assert_eq!(prog.bytecode(), synthesized.bytecode());
+    }
+
+    #[test_case(f64::INFINITY; "infinity")]
+    #[test_case(f64::NEG_INFINITY; "negative infinity")]
+    #[test_case(f64::NAN; "nan")]
+    fn non_finite_float(val: f64) {
+        let prog = ExprBuilder::float(val).compile().unwrap();
+
+        let mut ctx = CelContext::new();
+        ctx.add_program("main", prog);
+
+        match ctx.exec("main", &BindContext::new()).unwrap() {
+            CelValue::Float(f) if val.is_nan() => assert!(f.is_nan()),
+            CelValue::Float(f) => assert_eq!(f, val),
+            other => panic!("expected a double, got {:?}", other),
+        }
+    }
+}
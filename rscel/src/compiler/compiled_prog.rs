@@ -228,7 +228,7 @@ impl CompiledProg {
         r
     }
 
-    #[inline]
+    #[cfg(test)]
     pub fn bytecode_len(&self) -> usize {
         match self.inner {
             NodeValue::Bytecode(ref b) => b.len(),
@@ -7,6 +7,7 @@ pub enum PreResolvedCodePoint {
     Bytecode(ByteCode),
     Jmp { label: u32 },
     JmpCond { when: JmpWhen, label: u32 },
+    CompNext { label: u32 },
     Label(u32),
 }
 
@@ -106,6 +107,12 @@ impl PreResolvedByteCode {
                         dist: offset as i32,
                     });
                 }
+                PreResolvedCodePoint::CompNext { label } => {
+                    curr_loc += 1;
+                    let jmp_loc = locations[&label];
+                    let offset = (jmp_loc as isize) - (curr_loc as isize);
+                    ret.push(ByteCode::CompNext(offset as i32));
+                }
                 PreResolvedCodePoint::Label(_) => {}
             }
         }
@@ -1,43 +1,50 @@
-use std::collections::HashMap;
-
+mod codegen;
+mod comprehension;
 mod pattern_utils;
 
+use codegen::CodeGen;
 use pattern_utils::PrefixPattern;
 
 use super::{
     ast_node::AstNode,
-    compiled_prog::{CompiledProg, NodeValue, PreResolvedCodePoint},
+    ast_tokenizer::AstTokenizer,
     grammar::*,
     source_range::SourceRange,
     syntax_error::SyntaxError,
     tokenizer::{TokenWithLoc, Tokenizer},
-    tokens::{AsToken, FStringSegment, IntoToken, Token},
-};
-use crate::{
-    interp::{Interpreter, JmpWhen},
-    BindContext, ByteCode, CelError, CelResult, CelValue, CelValueDyn, Program, StringTokenizer,
+    tokens::{AsToken, IntoToken, Token},
 };
+use crate::{CelError, CelResult, Dialect, Program};
 
-use crate::compile;
-
+/// Parses the tokens of an expression into its AST, which `CodeGen` compiles.
 pub struct CelCompiler<'l> {
     tokenizer: &'l mut dyn Tokenizer,
-    bindings: BindContext<'l>,
-
-    next_label: u32,
+    codegen: CodeGen,
 }
 
 impl<'l> CelCompiler<'l> {
     pub fn with_tokenizer(tokenizer: &'l mut dyn Tokenizer) -> Self {
         CelCompiler {
             tokenizer,
-            bindings: BindContext::for_compile(),
-            next_label: 0,
+            codegen: CodeGen::new(),
         }
     }
 
+    /// Resolve names within `container`, such that `Msg` may refer to `pkg.sub.Msg`,
+    /// `pkg.Msg` or `Msg` when compiled with a container of `pkg.sub`.
+    pub fn with_container(mut self, container: &str) -> Self {
+        self.codegen.container = Some(container.to_owned());
+        self
+    }
+
+    /// Compile for `dialect`, which the program records and is later evaluated in.
+    pub fn with_dialect(mut self, dialect: Dialect) -> Self {
+        self.codegen.dialect = dialect;
+        self
+    }
+
     pub fn compile(mut self) -> CelResult<Program> {
-        let (cprog, ast) = self.parse_expression()?;
+        let ast = self.parse_expression()?;
 
         if !self.tokenizer.peek()?.is_none() {
             return Err(SyntaxError::from_location(self.tokenizer.location())
@@ -45,36 +52,45 @@ impl<'l> CelCompiler<'l> {
                 .into());
         }
 
-        let mut prog = cprog.into_program(self.tokenizer.source().to_owned());
-        prog.details_mut().add_ast(ast);
+        let mut prog = self
+            .codegen
+            .compile(ast, self.tokenizer.source().to_owned())?;
+
+        for comment in self.tokenizer.comments() {
+            if let Token::Comment(text) = comment.token() {
+                prog.details_mut()
+                    .add_comment(AstNode::new(text.clone(), comment.loc));
+            }
+        }
 
         Ok(prog)
     }
 
-    fn new_label(&mut self) -> u32 {
-        let n = self.next_label;
-        self.next_label += 1;
-        n
+    /// Compiles an already constructed AST, such as one from `ExprBuilder`.
+    ///
+    /// The AST keeps its own source ranges, the source recorded in the program details
+    /// is synthesized from it.
+    pub fn compile_ast(ast: AstNode<Expr>) -> CelResult<Program> {
+        let source = AstTokenizer::from_ast(&ast).source().to_owned();
+
+        CodeGen::new().compile(ast, source)
     }
 
-    fn parse_expression(&mut self) -> CelResult<(CompiledProg, AstNode<Expr>)> {
+    fn parse_expression(&mut self) -> CelResult<AstNode<Expr>> {
         if let Some(Token::Match) = self.tokenizer.peek()?.as_token() {
             self.tokenizer.next()?;
             self.parse_match_expression()
         } else {
-            let (lhs_node, lhs_ast) = self.parse_conditional_or()?;
+            let lhs_ast = self.parse_conditional_or()?;
 
             match self.tokenizer.peek()?.as_token() {
                 Some(Token::Question) => {
                     self.tokenizer.next()?;
-                    self.parse_turnary_expression(lhs_node, lhs_ast)
+                    self.parse_turnary_expression(lhs_ast)
                 }
                 _ => {
                     let range = lhs_ast.range();
-                    Ok((
-                        CompiledProg::from_node(lhs_node),
-                        AstNode::new(Expr::Unary(Box::new(lhs_ast)), range),
-                    ))
+                    Ok(AstNode::new(Expr::Unary(Box::new(lhs_ast)), range))
                 }
             }
         }
@@ -82,13 +98,9 @@ impl<'l> CelCompiler<'l> {
 
     fn parse_turnary_expression(
         &mut self,
-        or_prog: CompiledProg,
         or_ast: AstNode<ConditionalOr>,
-    ) -> CelResult<(CompiledProg, AstNode<Expr>)> {
-        let (expr_node, mut details) = or_prog.into_parts();
-
-        let (true_clause_node, true_clause_ast) = self.parse_conditional_or()?;
-        let (true_clause_node, true_clause_details) = true_clause_node.into_parts();
+    ) -> CelResult<AstNode<Expr>> {
+        let true_clause_ast = self.parse_conditional_or()?;
 
         let next = self.tokenizer.next()?;
         if next.as_token() != Some(&Token::Colon) {
@@ -97,112 +109,25 @@ impl<'l> CelCompiler<'l> {
                 .into());
         }
 
-        let (false_clause_node, false_clause_ast) = self.parse_expression()?;
-        let (false_clause_node, false_clause_details) = false_clause_node.into_parts();
+        let false_clause_ast = self.parse_expression()?;
 
         let range = or_ast.range().surrounding(false_clause_ast.range());
 
-        details.union_from(true_clause_details);
-        details.union_from(false_clause_details);
-
-        let turnary_node = if let NodeValue::ConstExpr(i) = expr_node {
-            if i.is_err() {
-                CompiledProg {
-                    inner: NodeValue::ConstExpr(i),
-                    details,
-                }
-            } else {
-                if cfg!(feature = "type_prop") {
-                    if i.is_truthy() {
-                        CompiledProg {
-                            inner: true_clause_node,
-                            details,
-                        }
-                    } else {
-                        CompiledProg {
-                            inner: false_clause_node,
-                            details,
-                        }
-                    }
-                } else {
-                    if let CelValue::Bool(b) = i {
-                        if b {
-                            CompiledProg {
-                                inner: true_clause_node,
-                                details,
-                            }
-                        } else {
-                            CompiledProg {
-                                inner: false_clause_node,
-                                details,
-                            }
-                        }
-                    } else {
-                        CompiledProg {
-                            inner: NodeValue::ConstExpr(CelValue::from_err(CelError::Value(
-                                format!("{} cannot be converted to bool", i.as_type()),
-                            ))),
-                            details,
-                        }
-                    }
-                }
-            }
-        } else {
-            let true_clause_bytecode = true_clause_node.into_bytecode();
-            let false_clause_bytecode = false_clause_node.into_bytecode();
-
-            let after_true_clause = self.new_label();
-            let end_label = self.new_label();
-
-            CompiledProg {
-                inner: NodeValue::Bytecode(
-                    expr_node
-                        .into_bytecode()
-                        .into_iter()
-                        .chain(
-                            [PreResolvedCodePoint::JmpCond {
-                                when: JmpWhen::False,
-                                label: after_true_clause,
-                            }]
-                            .into_iter(),
-                        )
-                        .chain(true_clause_bytecode.into_iter())
-                        .chain(
-                            [
-                                PreResolvedCodePoint::Jmp { label: end_label },
-                                PreResolvedCodePoint::Label(after_true_clause),
-                            ]
-                            .into_iter(),
-                        )
-                        .chain(false_clause_bytecode.into_iter())
-                        .chain([PreResolvedCodePoint::Label(end_label)].into_iter())
-                        .collect(),
-                ),
-                details,
-            }
-        };
-
-        Ok((
-            turnary_node,
-            AstNode::new(
-                Expr::Ternary {
-                    condition: Box::new(or_ast),
-                    true_clause: Box::new(true_clause_ast),
-                    false_clause: Box::new(false_clause_ast),
-                },
-                range,
-            ),
+        Ok(AstNode::new(
+            Expr::Ternary {
+                condition: Box::new(or_ast),
+                true_clause: Box::new(true_clause_ast),
+                false_clause: Box::new(false_clause_ast),
+            },
+            range,
         ))
     }
 
-    fn parse_match_expression(&mut self) -> CelResult<(CompiledProg, AstNode<Expr>)> {
-        let (condition_node, condition_ast) = self.parse_expression()?;
+    fn parse_match_expression(&mut self) -> CelResult<AstNode<Expr>> {
+        let condition_ast = self.parse_expression()?;
 
         let mut range = condition_ast.range();
 
-        let (node_value, mut node_details) = condition_node.into_parts();
-        let mut node_bytecode = node_value.into_bytecode();
-
         let next = self.tokenizer.next()?;
         if next.as_token() != Some(&Token::LBrace) {
             return Err(SyntaxError::from_location(self.tokenizer.location())
@@ -212,8 +137,6 @@ impl<'l> CelCompiler<'l> {
 
         let mut expressions: Vec<AstNode<MatchCase>> = Vec::new();
 
-        let mut all_parts = Vec::new();
-
         let mut comma_seen = true;
 
         loop {
@@ -239,12 +162,7 @@ impl<'l> CelCompiler<'l> {
                     .into());
             }
             //pattern
-            let (pattern_prog, pattern_ast) = self.parse_match_pattern()?;
-            let (pattern_bytecode, pattern_details) = pattern_prog.into_parts();
-            let pattern_bytecode = pattern_bytecode.into_bytecode();
-
-            node_details.union_from(pattern_details);
-
+            let pattern_ast = self.parse_match_pattern()?;
             let pattern_range = pattern_ast.range();
 
             // colon after pattern
@@ -256,18 +174,10 @@ impl<'l> CelCompiler<'l> {
             }
 
             // eval expression
-            let (expr_prog, expr_ast) = self.parse_expression()?;
-            let (expr_bytecode, expr_details) = expr_prog.into_parts();
-            let expr_bytecode: Vec<_> = [ByteCode::Pop.into()]
-                .into_iter()
-                .chain(expr_bytecode.into_bytecode().into_iter())
-                .collect();
-
-            node_details.union_from(expr_details);
+            let expr_ast = self.parse_expression()?;
 
             let case_range = pattern_range.surrounding(expr_ast.range());
 
-            all_parts.push((pattern_bytecode, expr_bytecode));
             expressions.push(AstNode::new(
                 MatchCase {
                     pattern: pattern_ast,
@@ -287,45 +197,16 @@ impl<'l> CelCompiler<'l> {
         // consume the RBRACE
         self.tokenizer.next()?;
 
-        // After match expression label
-        let after_match_s_l = self.new_label();
-
-        for (pattern_bytecode, expr_bytecode) in all_parts.into_iter() {
-            let after_case_l = self.new_label();
-
-            node_bytecode.push(ByteCode::Dup);
-            node_bytecode.extend(pattern_bytecode.into_iter());
-            node_bytecode.push(PreResolvedCodePoint::JmpCond {
-                when: JmpWhen::False,
-                label: after_case_l,
-            });
-
-            node_bytecode.extend(expr_bytecode);
-            node_bytecode.push(PreResolvedCodePoint::Jmp {
-                label: after_match_s_l,
-            });
-            node_bytecode.push(PreResolvedCodePoint::Label(after_case_l));
-        }
-
-        node_bytecode.extend([
-            ByteCode::Pop.into(),
-            ByteCode::Push(CelValue::from_null()).into(),
-            PreResolvedCodePoint::Label(after_match_s_l),
-        ]);
-
-        Ok((
-            CompiledProg::new(NodeValue::Bytecode(node_bytecode), node_details),
-            AstNode::new(
-                Expr::Match {
-                    condition: Box::new(condition_ast),
-                    cases: expressions,
-                },
-                range,
-            ),
+        Ok(AstNode::new(
+            Expr::Match {
+                condition: Box::new(condition_ast),
+                cases: expressions,
+            },
+            range,
         ))
     }
 
-    fn parse_match_pattern(&mut self) -> CelResult<(CompiledProg, AstNode<MatchPattern>)> {
+    fn parse_match_pattern(&mut self) -> CelResult<AstNode<MatchPattern>> {
         let start = self.tokenizer.location();
         let mut prefix_pattern = PrefixPattern::Eq;
 
@@ -336,40 +217,20 @@ impl<'l> CelCompiler<'l> {
                     self.tokenizer.next()?;
                     let range = SourceRange::new(start, self.tokenizer.location());
 
-                    return Ok((
-                        CompiledProg::with_bytecode(
-                            [
-                                ByteCode::Pop,                     // pop off the pattern value
-                                ByteCode::Push(CelValue::true_()), // push true
-                            ]
-                            .into_iter()
-                            .collect(),
-                        ),
-                        AstNode::new(
-                            MatchPattern::Any(AstNode::new(MatchAnyPattern {}, range)),
-                            range,
-                        ),
+                    return Ok(AstNode::new(
+                        MatchPattern::Any(AstNode::new(MatchAnyPattern {}, range)),
+                        range,
                     ));
-                } else if self.bindings.get_type(&i).is_some() {
+                } else if self.codegen.is_type(&i) {
                     self.tokenizer.next()?;
-                    return Ok((
-                        CompiledProg::with_bytecode(
-                            [
-                                ByteCode::Push(CelValue::Ident("type".to_owned())),
-                                ByteCode::Call(1),
-                                ByteCode::Push(CelValue::Ident(i.clone())),
-                                ByteCode::Eq,
-                            ]
-                            .into_iter()
-                            .collect(),
-                        ),
-                        AstNode::new(
-                            MatchPattern::Type(AstNode::new(
-                                MatchTypePattern::from_type_str(&i),
-                                SourceRange::new(start, self.tokenizer.location()),
-                            )),
-                            SourceRange::new(start, self.tokenizer.location()),
-                        ),
+                    let range = SourceRange::new(start, self.tokenizer.location());
+
+                    return Ok(AstNode::new(
+                        MatchPattern::Type(AstNode::new(
+                            MatchTypePattern::from_type_str(&i),
+                            range,
+                        )),
+                        range,
                     ));
                 }
             }
@@ -382,446 +243,199 @@ impl<'l> CelCompiler<'l> {
 
         let op_range = SourceRange::new(start, self.tokenizer.location());
 
-        let (or_prod, or_ast) = self.parse_conditional_or()?;
-        let or_details = or_prod.details().clone();
-        let mut or_bc = or_prod.into_unresolved_bytecode();
+        let or_ast = self.parse_conditional_or()?;
 
-        or_bc.push(prefix_pattern.as_bytecode());
-
-        Ok((
-            CompiledProg::new(NodeValue::Bytecode(or_bc), or_details),
-            AstNode::new(
-                MatchPattern::Cmp {
-                    op: AstNode::new(prefix_pattern.as_ast(), op_range),
-                    or: or_ast,
-                },
-                SourceRange::new(start, self.tokenizer.location()),
-            ),
+        Ok(AstNode::new(
+            MatchPattern::Cmp {
+                op: AstNode::new(prefix_pattern.as_ast(), op_range),
+                or: or_ast,
+            },
+            SourceRange::new(start, self.tokenizer.location()),
         ))
     }
 
-    fn parse_conditional_or(&mut self) -> CelResult<(CompiledProg, AstNode<ConditionalOr>)> {
-        let (mut current_node, mut current_ast) = into_unary(self.parse_conditional_and()?);
+    fn parse_conditional_or(&mut self) -> CelResult<AstNode<ConditionalOr>> {
+        let mut current_ast = into_unary(self.parse_conditional_and()?);
 
-        let label = self.new_label();
-
-        loop {
-            if let Some(Token::OrOr) = self.tokenizer.peek()?.as_token() {
-                self.tokenizer.next()?;
-                let (rhs_node, rhs_ast) = self.parse_conditional_and()?;
-
-                let jmp_node = CompiledProg::with_code_points(vec![
-                    PreResolvedCodePoint::Bytecode(ByteCode::Test),
-                    PreResolvedCodePoint::Bytecode(ByteCode::Dup),
-                    PreResolvedCodePoint::JmpCond {
-                        when: JmpWhen::True,
-                        label,
-                    },
-                ]);
-
-                let range = current_ast.range().surrounding(rhs_ast.range());
+        while let Some(Token::OrOr) = self.tokenizer.peek()?.as_token() {
+            self.tokenizer.next()?;
+            let rhs_ast = self.parse_conditional_and()?;
+            let range = current_ast.range().surrounding(rhs_ast.range());
 
-                current_ast = AstNode::new(
-                    ConditionalOr::Binary {
-                        lhs: Box::new(current_ast),
-                        rhs: rhs_ast,
-                    },
-                    range,
-                );
-                current_node = compile!(
-                    [ByteCode::Or.into()],
-                    current_node.or(&rhs_node),
-                    current_node,
-                    jmp_node,
-                    rhs_node
-                );
-            } else {
-                break;
-            }
+            current_ast = AstNode::new(
+                ConditionalOr::Binary {
+                    lhs: Box::new(current_ast),
+                    rhs: rhs_ast,
+                },
+                range,
+            );
         }
 
-        current_node.append_if_bytecode([PreResolvedCodePoint::Label(label)]);
-
-        Ok((current_node, current_ast))
+        Ok(current_ast)
     }
 
-    fn parse_conditional_and(&mut self) -> CelResult<(CompiledProg, AstNode<ConditionalAnd>)> {
-        let (mut current_node, mut current_ast) = into_unary(self.parse_relation()?);
-
-        let label = self.new_label();
-
-        loop {
-            if let Some(Token::AndAnd) = self.tokenizer.peek()?.as_token() {
-                self.tokenizer.next()?;
-                let (rhs_node, rhs_ast) = self.parse_relation()?;
-
-                let jmp_node = CompiledProg::with_code_points(vec![
-                    PreResolvedCodePoint::Bytecode(ByteCode::Test),
-                    PreResolvedCodePoint::Bytecode(ByteCode::Dup),
-                    PreResolvedCodePoint::JmpCond {
-                        when: JmpWhen::False,
-                        label: label,
-                    },
-                ]);
+    fn parse_conditional_and(&mut self) -> CelResult<AstNode<ConditionalAnd>> {
+        let mut current_ast = into_unary(self.parse_relation()?);
 
-                let range = current_ast.range().surrounding(rhs_ast.range());
+        while let Some(Token::AndAnd) = self.tokenizer.peek()?.as_token() {
+            self.tokenizer.next()?;
+            let rhs_ast = self.parse_relation()?;
+            let range = current_ast.range().surrounding(rhs_ast.range());
 
-                current_ast = AstNode::new(
-                    ConditionalAnd::Binary {
-                        lhs: Box::new(current_ast),
-                        rhs: rhs_ast,
-                    },
-                    range,
-                );
-                current_node = compile!(
-                    [ByteCode::And.into()],
-                    current_node.and(rhs_node),
-                    current_node,
-                    jmp_node,
-                    rhs_node
-                );
-            } else {
-                break;
-            }
+            current_ast = AstNode::new(
+                ConditionalAnd::Binary {
+                    lhs: Box::new(current_ast),
+                    rhs: rhs_ast,
+                },
+                range,
+            );
         }
-        current_node.append_if_bytecode([PreResolvedCodePoint::Label(label)]);
 
-        Ok((current_node, current_ast))
+        Ok(current_ast)
     }
 
-    fn parse_relation(&mut self) -> CelResult<(CompiledProg, AstNode<Relation>)> {
-        let (mut current_node, mut current_ast) = into_unary(self.parse_addition()?);
+    fn parse_relation(&mut self) -> CelResult<AstNode<Relation>> {
+        let mut current_ast = into_unary(self.parse_addition()?);
 
         loop {
-            match self.tokenizer.peek()?.as_token() {
-                Some(Token::LessThan) => {
-                    self.tokenizer.next()?;
+            let op = match self.tokenizer.peek()?.as_token() {
+                Some(Token::LessThan) => Relop::Lt,
+                Some(Token::LessEqual) => Relop::Le,
+                Some(Token::EqualEqual) => Relop::Eq,
+                Some(Token::NotEqual) => Relop::Ne,
+                Some(Token::GreaterEqual) => Relop::Ge,
+                Some(Token::GreaterThan) => Relop::Gt,
+                Some(Token::In) => Relop::In,
+                _ => break,
+            };
+            self.tokenizer.next()?;
 
-                    let (rhs_node, rhs_ast) = self.parse_addition()?;
-                    let range = current_ast.range().surrounding(rhs_ast.range());
+            let rhs_ast = self.parse_addition()?;
+            let range = current_ast.range().surrounding(rhs_ast.range());
 
-                    current_ast = AstNode::new(
-                        Relation::Binary {
-                            lhs: Box::new(current_ast),
-                            op: Relop::Lt,
-                            rhs: rhs_ast,
-                        },
-                        range,
-                    );
-
-                    current_node = compile!(
-                        [ByteCode::Lt.into()],
-                        current_node.lt(rhs_node),
-                        current_node,
-                        rhs_node
-                    );
-                }
-                Some(Token::LessEqual) => {
-                    self.tokenizer.next()?;
-                    let (rhs_node, rhs_ast) = self.parse_addition()?;
-                    let range = current_ast.range().surrounding(rhs_ast.range());
-
-                    current_ast = AstNode::new(
-                        Relation::Binary {
-                            lhs: Box::new(current_ast),
-                            op: Relop::Le,
-                            rhs: rhs_ast,
-                        },
-                        range,
-                    );
-
-                    current_node = compile!(
-                        [ByteCode::Le.into()],
-                        current_node.le(rhs_node),
-                        current_node,
-                        rhs_node
-                    );
-                }
-                Some(Token::EqualEqual) => {
-                    self.tokenizer.next()?;
-                    let (rhs_node, rhs_ast) = self.parse_addition()?;
-                    let range = current_ast.range().surrounding(rhs_ast.range());
-
-                    current_ast = AstNode::new(
-                        Relation::Binary {
-                            lhs: Box::new(current_ast),
-                            op: Relop::Eq,
-                            rhs: rhs_ast,
-                        },
-                        range,
-                    );
-
-                    current_node = compile!(
-                        [ByteCode::Eq.into()],
-                        CelValueDyn::eq(&current_node, &rhs_node),
-                        current_node,
-                        rhs_node
-                    );
-                }
-                Some(Token::NotEqual) => {
-                    self.tokenizer.next()?;
-                    let (rhs_node, rhs_ast) = self.parse_addition()?;
-                    let range = current_ast.range().surrounding(rhs_ast.range());
-
-                    current_ast = AstNode::new(
-                        Relation::Binary {
-                            lhs: Box::new(current_ast),
-                            op: Relop::Ne,
-                            rhs: rhs_ast,
-                        },
-                        range,
-                    );
-
-                    current_node = compile!(
-                        [ByteCode::Ne.into()],
-                        current_node.neq(rhs_node),
-                        current_node,
-                        rhs_node
-                    );
-                }
-                Some(Token::GreaterEqual) => {
-                    self.tokenizer.next()?;
-                    let (rhs_node, rhs_ast) = self.parse_addition()?;
-                    let range = current_ast.range().surrounding(rhs_ast.range());
-
-                    current_ast = AstNode::new(
-                        Relation::Binary {
-                            lhs: Box::new(current_ast),
-                            op: Relop::Ge,
-                            rhs: rhs_ast,
-                        },
-                        range,
-                    );
-
-                    current_node = compile!(
-                        [ByteCode::Ge.into()],
-                        current_node.ge(rhs_node),
-                        current_node,
-                        rhs_node
-                    );
-                }
-                Some(Token::GreaterThan) => {
-                    self.tokenizer.next()?;
-                    let (rhs_node, rhs_ast) = self.parse_addition()?;
-                    let range = current_ast.range().surrounding(rhs_ast.range());
-
-                    current_ast = AstNode::new(
-                        Relation::Binary {
-                            lhs: Box::new(current_ast),
-                            op: Relop::Gt,
-                            rhs: rhs_ast,
-                        },
-                        range,
-                    );
-
-                    current_node = compile!(
-                        [ByteCode::Gt.into()],
-                        current_node.gt(rhs_node),
-                        current_node,
-                        rhs_node
-                    );
-                }
-                Some(Token::In) => {
-                    self.tokenizer.next()?;
-                    let (rhs_node, rhs_ast) = self.parse_addition()?;
-                    let range = current_ast.range().surrounding(rhs_ast.range());
-
-                    current_ast = AstNode::new(
-                        Relation::Binary {
-                            lhs: Box::new(current_ast),
-                            op: Relop::In,
-                            rhs: rhs_ast,
-                        },
-                        range,
-                    );
-                    current_node = compile!(
-                        [ByteCode::In.into()],
-                        current_node.in_(rhs_node),
-                        current_node,
-                        rhs_node
-                    )
-                }
-                _ => break,
-            }
+            current_ast = AstNode::new(
+                Relation::Binary {
+                    lhs: Box::new(current_ast),
+                    op,
+                    rhs: rhs_ast,
+                },
+                range,
+            );
         }
 
-        Ok((current_node, current_ast))
+        Ok(current_ast)
     }
 
-    fn parse_addition(&mut self) -> CelResult<(CompiledProg, AstNode<Addition>)> {
-        let (mut current_node, mut current_ast) = into_unary(self.parse_multiplication()?);
+    fn parse_addition(&mut self) -> CelResult<AstNode<Addition>> {
+        let mut current_ast = into_unary(self.parse_multiplication()?);
 
         loop {
-            match self.tokenizer.peek()?.as_token() {
-                Some(Token::Add) => {
-                    self.tokenizer.next()?;
-
-                    let (rhs_node, rhs_ast) = self.parse_multiplication()?;
-                    let range = current_ast.range().surrounding(rhs_ast.range());
-
-                    current_ast = AstNode::new(
-                        Addition::Binary {
-                            lhs: Box::new(current_ast),
-                            op: AddOp::Add,
-                            rhs: rhs_ast,
-                        },
-                        range,
-                    );
-
-                    current_node = compile!(
-                        [ByteCode::Add.into()],
-                        current_node + rhs_node,
-                        current_node,
-                        rhs_node
-                    );
-                }
-                Some(Token::Minus) => {
-                    self.tokenizer.next()?;
+            let op = match self.tokenizer.peek()?.as_token() {
+                Some(Token::Add) => AddOp::Add,
+                Some(Token::Minus) => AddOp::Sub,
+                _ => break,
+            };
+            self.tokenizer.next()?;
 
-                    let (rhs_node, rhs_ast) = self.parse_multiplication()?;
-                    let range = current_ast.range().surrounding(rhs_ast.range());
+            let rhs_ast = self.parse_multiplication()?;
+            let range = current_ast.range().surrounding(rhs_ast.range());
 
-                    current_ast = AstNode::new(
-                        Addition::Binary {
-                            lhs: Box::new(current_ast),
-                            op: AddOp::Sub,
-                            rhs: rhs_ast,
-                        },
-                        range,
-                    );
-
-                    current_node = compile!(
-                        [ByteCode::Sub.into()],
-                        current_node - rhs_node,
-                        current_node,
-                        rhs_node
-                    );
-                }
-                _ => break,
-            }
+            current_ast = AstNode::new(
+                Addition::Binary {
+                    lhs: Box::new(current_ast),
+                    op,
+                    rhs: rhs_ast,
+                },
+                range,
+            );
         }
 
-        Ok((current_node, current_ast))
+        Ok(current_ast)
     }
 
-    fn parse_multiplication(&mut self) -> CelResult<(CompiledProg, AstNode<Multiplication>)> {
-        let (mut current_node, mut current_ast) = into_unary(self.parse_unary()?);
+    fn parse_multiplication(&mut self) -> CelResult<AstNode<Multiplication>> {
+        let mut current_ast = into_unary(self.parse_unary()?);
 
         loop {
-            match self.tokenizer.peek()?.as_token() {
-                Some(Token::Multiply) => {
-                    self.tokenizer.next()?;
-
-                    let (rhs_node, rhs_ast) = self.parse_unary()?;
-                    let range = current_ast.range().surrounding(rhs_ast.range());
-
-                    current_ast = AstNode::new(
-                        Multiplication::Binary {
-                            lhs: Box::new(current_ast),
-                            op: MultOp::Mult,
-                            rhs: rhs_ast,
-                        },
-                        range,
-                    );
-                    current_node = compile!(
-                        [ByteCode::Mul.into()],
-                        current_node * rhs_node,
-                        current_node,
-                        rhs_node
-                    );
-                }
-                Some(Token::Divide) => {
-                    self.tokenizer.next()?;
-
-                    let (rhs_node, rhs_ast) = self.parse_unary()?;
-                    let range = current_ast.range().surrounding(rhs_ast.range());
-
-                    current_ast = AstNode::new(
-                        Multiplication::Binary {
-                            lhs: Box::new(current_ast),
-                            op: MultOp::Div,
-                            rhs: rhs_ast,
-                        },
-                        range,
-                    );
-
-                    current_node = compile!(
-                        [ByteCode::Div.into()],
-                        current_node / rhs_node,
-                        current_node,
-                        rhs_node
-                    );
-                }
-                Some(Token::Mod) => {
-                    self.tokenizer.next()?;
+            let op = match self.tokenizer.peek()?.as_token() {
+                Some(Token::Multiply) => MultOp::Mult,
+                Some(Token::Divide) => MultOp::Div,
+                Some(Token::Mod) => MultOp::Mod,
+                _ => break,
+            };
+            self.tokenizer.next()?;
 
-                    let (rhs_node, rhs_ast) = self.parse_unary()?;
-                    let range = current_ast.range().surrounding(rhs_ast.range());
+            let rhs_ast = self.parse_unary()?;
+            let range = current_ast.range().surrounding(rhs_ast.range());
 
-                    current_ast = AstNode::new(
-                        Multiplication::Binary {
-                            lhs: Box::new(current_ast),
-                            op: MultOp::Mod,
-                            rhs: rhs_ast,
-                        },
-                        range,
-                    );
-
-                    current_node = compile!(
-                        [ByteCode::Mod.into()],
-                        current_node % rhs_node,
-                        current_node,
-                        rhs_node
-                    );
-                }
-                _ => break,
-            }
+            current_ast = AstNode::new(
+                Multiplication::Binary {
+                    lhs: Box::new(current_ast),
+                    op,
+                    rhs: rhs_ast,
+                },
+                range,
+            );
         }
 
-        Ok((current_node, current_ast))
+        Ok(current_ast)
     }
 
-    fn parse_unary(&mut self) -> CelResult<(CompiledProg, AstNode<Unary>)> {
+    fn parse_unary(&mut self) -> CelResult<AstNode<Unary>> {
         match self.tokenizer.peek()?.as_token() {
             Some(Token::Not) => {
-                let (not, not_ast) = self.parse_not_list()?;
-                let (member, member_ast) = self.parse_member()?;
+                let not_ast = self.parse_not_list()?;
+                let member_ast = self.parse_member()?;
 
                 let range = not_ast.range().surrounding(member_ast.range());
 
-                Ok((
-                    member.append_result(not),
-                    AstNode::new(
-                        Unary::NotMember {
-                            nots: not_ast,
-                            member: member_ast,
-                        },
-                        range,
-                    ),
+                Ok(AstNode::new(
+                    Unary::NotMember {
+                        nots: not_ast,
+                        member: member_ast,
+                    },
+                    range,
                 ))
             }
             Some(Token::Minus) => {
-                let (neg, neg_ast) = self.parse_neg_list()?;
-                let (member, member_ast) = self.parse_member()?;
+                let mut neg_ast = self.parse_neg_list()?;
+
+                // -9223372036854775808 is in range even though 9223372036854775808 isn't, so
+                // the innermost negation is folded into the literal
+                let member_ast = match self.tokenizer.peek()? {
+                    Some(&TokenWithLoc {
+                        token: Token::IntLit(val),
+                        loc,
+                    }) if val == i64::MIN.unsigned_abs() => {
+                        self.tokenizer.next()?;
+
+                        if let NegList::List { tail } = neg_ast.node() {
+                            neg_ast = tail.as_ref().clone();
+                        }
+
+                        self.parse_member_prime(AstNode::new(
+                            Primary::Literal(LiteralsAndKeywords::IntegerLit(i64::MIN)),
+                            loc,
+                        ))?
+                    }
+                    _ => self.parse_member()?,
+                };
 
                 let range = member_ast.range().surrounding(neg_ast.range());
 
-                Ok((
-                    member.append_result(neg),
-                    AstNode::new(
-                        Unary::NegMember {
-                            negs: neg_ast,
-                            member: member_ast,
-                        },
-                        range,
-                    ),
+                Ok(AstNode::new(
+                    Unary::NegMember {
+                        negs: neg_ast,
+                        member: member_ast,
+                    },
+                    range,
                 ))
             }
             _ => Ok(into_unary(self.parse_member()?)),
         }
     }
 
-    fn parse_not_list(&mut self) -> CelResult<(CompiledProg, AstNode<NotList>)> {
+    fn parse_not_list(&mut self) -> CelResult<AstNode<NotList>> {
         match self.tokenizer.peek()? {
             Some(&TokenWithLoc {
                 token: Token::Not,
@@ -829,32 +443,27 @@ impl<'l> CelCompiler<'l> {
             }) => {
                 self.tokenizer.next()?;
 
-                let (not_list, ast) = self.parse_not_list()?;
-                let node = compile!([ByteCode::Not.into()], not_list, not_list);
-
+                let ast = self.parse_not_list()?;
                 let range = ast.range().surrounding(loc);
 
-                Ok((
-                    node,
-                    AstNode::new(
-                        NotList::List {
-                            tail: Box::new(ast),
-                        },
-                        range,
-                    ),
+                Ok(AstNode::new(
+                    NotList::List {
+                        tail: Box::new(ast),
+                    },
+                    range,
                 ))
             }
             _ => {
                 let start_loc = self.tokenizer.location();
-                Ok((
-                    CompiledProg::empty(),
-                    AstNode::new(NotList::EmptyList, SourceRange::new(start_loc, start_loc)),
+                Ok(AstNode::new(
+                    NotList::EmptyList,
+                    SourceRange::new(start_loc, start_loc),
                 ))
             }
         }
     }
 
-    fn parse_neg_list(&mut self) -> CelResult<(CompiledProg, AstNode<NegList>)> {
+    fn parse_neg_list(&mut self) -> CelResult<AstNode<NegList>> {
         match self.tokenizer.peek()? {
             Some(&TokenWithLoc {
                 token: Token::Minus,
@@ -862,35 +471,33 @@ impl<'l> CelCompiler<'l> {
             }) => {
                 self.tokenizer.next()?;
 
-                let (neg_list, ast) = self.parse_neg_list()?;
-                let node = compile!([ByteCode::Neg.into()], neg_list, neg_list);
-
+                let ast = self.parse_neg_list()?;
                 let range = ast.range().surrounding(loc);
 
-                Ok((
-                    node,
-                    AstNode::new(
-                        NegList::List {
-                            tail: Box::new(ast),
-                        },
-                        range,
-                    ),
+                Ok(AstNode::new(
+                    NegList::List {
+                        tail: Box::new(ast),
+                    },
+                    range,
                 ))
             }
             _ => {
                 let start_loc = self.tokenizer.location();
-                Ok((
-                    CompiledProg::empty(),
-                    AstNode::new(NegList::EmptyList, SourceRange::new(start_loc, start_loc)),
+                Ok(AstNode::new(
+                    NegList::EmptyList,
+                    SourceRange::new(start_loc, start_loc),
                 ))
             }
         }
     }
 
-    fn parse_member(&mut self) -> CelResult<(CompiledProg, AstNode<Member>)> {
-        let (primary_node, primary_ast) = self.parse_primary()?;
+    fn parse_member(&mut self) -> CelResult<AstNode<Member>> {
+        let primary = self.parse_primary()?;
+        self.parse_member_prime(primary)
+    }
 
-        let mut member_prime_node = CompiledProg::from_node(primary_node);
+    /// Parses the member accesses, indexes and calls following `primary`.
+    fn parse_member_prime(&mut self, primary_ast: AstNode<Primary>) -> CelResult<AstNode<Member>> {
         let mut member_prime_ast: Vec<AstNode<MemberPrime>> = Vec::new();
 
         loop {
@@ -905,39 +512,9 @@ impl<'l> CelCompiler<'l> {
                             token: Token::Ident(ident),
                             loc,
                         }) => {
-                            let res = CompiledProg::with_const(CelValue::from_ident(&ident));
-
-                            member_prime_node = CompiledProg::from_children2_w_bytecode_cannone(
-                                member_prime_node,
-                                res,
-                                vec![ByteCode::Access],
-                                |o, c| {
-                                    if let CelValue::Ident(s) = c {
-                                        // Allow for const eval for obj members in the
-                                        // off chance a user does somthing like this
-                                        // `{'a': 3}.a`. Its const value will be 3.
-                                        if o.is_obj() {
-                                            // So if this fails we should break the const
-                                            // status and let the compiler generate some
-                                            // bytecode for function discovery and such.
-                                            match o.access(&s) {
-                                                CelValue::Err(_) => None,
-                                                o => Some(o),
-                                            }
-                                        } else {
-                                            None
-                                        }
-                                    } else {
-                                        Some(CelValue::from_err(CelError::value(
-                                            "Accessor must be ident",
-                                        )))
-                                    }
-                                },
-                            );
-
                             member_prime_ast.push(AstNode::new(
                                 MemberPrime::MemberAccess {
-                                    ident: AstNode::new(Ident(ident.clone()), loc),
+                                    ident: AstNode::new(Ident(ident), loc),
                                 },
                                 dot_loc.surrounding(loc),
                             ));
@@ -960,7 +537,7 @@ impl<'l> CelCompiler<'l> {
                 }) => {
                     self.tokenizer.next()?;
 
-                    let args = self.parse_expression_list(Token::RParen)?;
+                    let mut args_ast = self.parse_expression_list(Token::RParen)?;
 
                     let token = self.tokenizer.next()?;
                     if let Some(TokenWithLoc {
@@ -968,28 +545,8 @@ impl<'l> CelCompiler<'l> {
                         loc: rparen_loc,
                     }) = token
                     {
-                        let args_len = args.len();
-
-                        let mut args_ast = Vec::new();
-                        let mut args_node = CompiledProg::empty();
-                        // Arguments are evaluated backwards so they get popped off the stack in order
-                        for (a, ast) in args.into_iter().rev() {
-                            args_ast.push(ast);
-                            args_node =
-                                args_node.append_result(CompiledProg::with_code_points(vec![
-                                    ByteCode::Push(a.into_unresolved_bytecode().resolve().into())
-                                        .into(),
-                                ]))
-                        }
-
-                        member_prime_node = args_node
-                            .consume_child(member_prime_node)
-                            .consume_child(CompiledProg::with_code_points(vec![ByteCode::Call(
-                                args_len as u32,
-                            )
-                            .into()]));
-
-                        member_prime_node = self.check_for_const(member_prime_node);
+                        // Arguments are kept last first, the order they are pushed in
+                        args_ast.reverse();
 
                         member_prime_ast.push(AstNode::new(
                             MemberPrime::Call {
@@ -1015,20 +572,13 @@ impl<'l> CelCompiler<'l> {
                 }) => {
                     self.tokenizer.next()?;
 
-                    let (index_node, index_ast) = self.parse_expression()?;
+                    let index_ast = self.parse_expression()?;
 
                     match self.tokenizer.next()? {
                         Some(TokenWithLoc {
                             token: Token::RBracket,
                             loc: rbracket_loc,
                         }) => {
-                            member_prime_node = compile!(
-                                [ByteCode::Index.into()],
-                                member_prime_node.index(index_node),
-                                member_prime_node,
-                                index_node
-                            );
-
                             member_prime_ast.push(AstNode::new(
                                 MemberPrime::ArrayAccess { access: index_ast },
                                 loc.surrounding(rbracket_loc),
@@ -1054,35 +604,26 @@ impl<'l> CelCompiler<'l> {
             range = range.surrounding(m.range());
         }
 
-        Ok((
-            member_prime_node,
-            AstNode::new(
-                Member {
-                    primary: primary_ast,
-                    member: member_prime_ast,
-                },
-                range,
-            ),
+        Ok(AstNode::new(
+            Member {
+                primary: primary_ast,
+                member: member_prime_ast,
+            },
+            range,
         ))
     }
 
-    fn parse_primary(&mut self) -> CelResult<(CompiledProg, AstNode<Primary>)> {
+    fn parse_primary(&mut self) -> CelResult<AstNode<Primary>> {
         match self.tokenizer.next()? {
             Some(TokenWithLoc {
                 token: Token::Ident(val),
                 loc,
-            }) => Ok((
-                CompiledProg::with_code_points(vec![
-                    ByteCode::Push(CelValue::from_ident(&val)).into()
-                ])
-                .add_ident(&val),
-                AstNode::new(Primary::Ident(Ident(val.clone())), loc),
-            )),
+            }) => Ok(AstNode::new(Primary::Ident(Ident(val)), loc)),
             Some(TokenWithLoc {
                 token: Token::LParen,
                 loc,
             }) => {
-                let (expr, expr_ast) = self.parse_expression()?;
+                let expr_ast = self.parse_expression()?;
 
                 let next_token = self.tokenizer.next();
                 let rparen_loc = match next_token? {
@@ -1104,9 +645,9 @@ impl<'l> CelCompiler<'l> {
                     }
                 };
 
-                Ok((
-                    CompiledProg::from_node(expr),
-                    AstNode::new(Primary::Parens(expr_ast), loc.surrounding(rparen_loc)),
+                Ok(AstNode::new(
+                    Primary::Parens(expr_ast),
+                    loc.surrounding(rparen_loc),
                 ))
             }
             Some(TokenWithLoc {
@@ -1114,10 +655,7 @@ impl<'l> CelCompiler<'l> {
                 loc,
             }) => {
                 // list construction
-                let expr_node_list = self.parse_expression_list(Token::RBracket)?;
-                let expr_list_len = expr_node_list.len();
-                let (expr_list, expr_list_ast): (Vec<_>, Vec<_>) =
-                    expr_node_list.into_iter().unzip();
+                let expr_list_ast = self.parse_expression_list(Token::RBracket)?;
 
                 let range = if let Some(TokenWithLoc {
                     token: Token::RBracket,
@@ -1133,21 +671,14 @@ impl<'l> CelCompiler<'l> {
 
                 self.tokenizer.next()?;
 
-                Ok((
-                    CompiledProg::from_children_w_bytecode(
-                        expr_list,
-                        vec![ByteCode::MkList(expr_list_len as u32)],
-                        |c| c.into(),
-                    ),
-                    AstNode::new(
-                        Primary::ListConstruction(AstNode::new(
-                            ExprList {
-                                exprs: expr_list_ast,
-                            },
-                            range,
-                        )),
+                Ok(AstNode::new(
+                    Primary::ListConstruction(AstNode::new(
+                        ExprList {
+                            exprs: expr_list_ast,
+                        },
                         range,
-                    ),
+                    )),
+                    range,
                 ))
             }
             Some(TokenWithLoc {
@@ -1155,7 +686,7 @@ impl<'l> CelCompiler<'l> {
                 loc,
             }) => {
                 // Dictionary construction
-                let obj_init = self.parse_obj_inits()?;
+                let init_asts = self.parse_obj_inits()?;
 
                 let range = if let Some(&TokenWithLoc {
                     token: Token::RBrace,
@@ -1171,156 +702,78 @@ impl<'l> CelCompiler<'l> {
                         .into());
                 };
 
-                let obj_init_len = obj_init.len();
-                debug_assert!(obj_init_len % 2 == 0);
-
-                let mut init_asts = Vec::new();
-
-                let (compiled_children, children_ast): (Vec<_>, Vec<_>) =
-                    obj_init.into_iter().unzip();
-
-                let mut children_ast_iter = children_ast.into_iter();
-                // init is created as value then key for mkdict stack
-                while let Some(val_ast) = children_ast_iter.next() {
-                    let key_ast = children_ast_iter.next().unwrap();
-
-                    let range = key_ast.range().surrounding(val_ast.range());
-
-                    init_asts.push(AstNode::new(
-                        ObjInit {
-                            key: key_ast,
-                            value: val_ast,
-                        },
-                        range,
-                    ));
-                }
-
-                let new_ast = AstNode::new(
+                Ok(AstNode::new(
                     Primary::ObjectInit(AstNode::new(ObjInits { inits: init_asts }, range)),
                     range,
-                );
-
-                Ok((
-                    CompiledProg::from_children_w_bytecode(
-                        compiled_children,
-                        vec![ByteCode::MkDict(obj_init_len as u32 / 2)],
-                        |vals| {
-                            let mut obj_map = HashMap::new();
-                            for i in (0..vals.len()).step_by(2) {
-                                let key = if let CelValue::String(ref k) = vals[i + 1] {
-                                    k
-                                } else {
-                                    return CelValue::from_err(CelError::value(
-                                        "Only strings can be object keys",
-                                    ));
-                                };
-
-                                obj_map.insert(key.clone(), vals[i].clone());
-                            }
-
-                            obj_map.into()
-                        },
-                    ),
-                    new_ast,
                 ))
             }
             Some(TokenWithLoc {
                 token: Token::UIntLit(val),
                 loc,
-            }) => Ok((
-                CompiledProg::with_const(val.into()),
-                AstNode::new(Primary::Literal(LiteralsAndKeywords::UnsignedLit(val)), loc),
+            }) => Ok(AstNode::new(
+                Primary::Literal(LiteralsAndKeywords::UnsignedLit(val)),
+                loc,
             )),
             Some(TokenWithLoc {
                 token: Token::IntLit(val),
                 loc,
-            }) => Ok((
-                CompiledProg::with_const((val as i64).into()),
-                AstNode::new(
-                    Primary::Literal(LiteralsAndKeywords::IntegerLit(val as i64)),
+            }) => match i64::try_from(val) {
+                Ok(val) => Ok(AstNode::new(
+                    Primary::Literal(LiteralsAndKeywords::IntegerLit(val)),
                     loc,
-                ),
-            )),
+                )),
+                Err(_) => Err(SyntaxError::from_location(loc.start())
+                    .with_message(format!("Int literal {} out of range", val))
+                    .into()),
+            },
             Some(TokenWithLoc {
                 token: Token::FloatLit(val),
                 loc,
-            }) => Ok((
-                CompiledProg::with_const((val).into()),
-                AstNode::new(Primary::Literal(LiteralsAndKeywords::FloatingLit(val)), loc),
+            }) => Ok(AstNode::new(
+                Primary::Literal(LiteralsAndKeywords::FloatingLit(val)),
+                loc,
+            )),
+            Some(TokenWithLoc {
+                token: Token::QuantityLit(val, unit),
+                loc,
+            }) => Ok(AstNode::new(
+                Primary::Literal(LiteralsAndKeywords::QuantityLit(val, unit)),
+                loc,
             )),
             Some(TokenWithLoc {
                 token: Token::StringLit(val),
                 loc,
-            }) => Ok((
-                CompiledProg::with_const(val.clone().into()),
-                AstNode::new(
-                    Primary::Literal(LiteralsAndKeywords::StringLit(val.clone())),
-                    loc,
-                ),
+            }) => Ok(AstNode::new(
+                Primary::Literal(LiteralsAndKeywords::StringLit(val)),
+                loc,
             )),
             Some(TokenWithLoc {
                 token: Token::ByteStringLit(val),
                 loc,
-            }) => Ok((
-                CompiledProg::with_const(val.clone().into()),
-                AstNode::new(
-                    Primary::Literal(LiteralsAndKeywords::ByteStringLit(val.into())),
-                    loc,
-                ),
+            }) => Ok(AstNode::new(
+                Primary::Literal(LiteralsAndKeywords::ByteStringLit(val.into())),
+                loc,
             )),
             Some(TokenWithLoc {
                 token: Token::FStringLit(segments),
                 loc,
-            }) => {
-                let mut bytecode = Vec::<PreResolvedCodePoint>::new();
-
-                for segment in segments.iter() {
-                    match segment {
-                        FStringSegment::Lit(c) => {
-                            bytecode.push(ByteCode::Push(CelValue::String(c.clone())).into())
-                        }
-                        FStringSegment::Expr(e) => {
-                            let mut tok = StringTokenizer::with_input(&e);
-                            let mut comp = CelCompiler::with_tokenizer(&mut tok);
-
-                            let (e, _) = comp.parse_expression()?;
-
-                            bytecode.push(
-                                ByteCode::Push(CelValue::ByteCode(
-                                    e.into_unresolved_bytecode().resolve(),
-                                ))
-                                .into(),
-                            );
-                        }
-                    }
-                    bytecode.push(ByteCode::Push(CelValue::Ident("string".to_string())).into());
-                    bytecode.push(ByteCode::Call(1).into());
-                }
-
-                // Reverse it so its evaluated in order on the stack
-                bytecode.push(ByteCode::FmtString(segments.len() as u32).into());
-
-                Ok((
-                    CompiledProg::with_code_points(bytecode),
-                    AstNode::new(
-                        Primary::Literal(LiteralsAndKeywords::FStringList(segments.clone())),
-                        loc,
-                    ),
-                ))
-            }
+            }) => Ok(AstNode::new(
+                Primary::Literal(LiteralsAndKeywords::FStringList(segments)),
+                loc,
+            )),
             Some(TokenWithLoc {
                 token: Token::BoolLit(val),
                 loc,
-            }) => Ok((
-                CompiledProg::with_const(val.into()),
-                AstNode::new(Primary::Literal(LiteralsAndKeywords::BooleanLit(val)), loc),
+            }) => Ok(AstNode::new(
+                Primary::Literal(LiteralsAndKeywords::BooleanLit(val)),
+                loc,
             )),
             Some(TokenWithLoc {
                 token: Token::Null,
                 loc,
-            }) => Ok((
-                CompiledProg::with_const(CelValue::from_null()),
-                AstNode::new(Primary::Literal(LiteralsAndKeywords::NullLit), loc),
+            }) => Ok(AstNode::new(
+                Primary::Literal(LiteralsAndKeywords::NullLit),
+                loc,
             )),
             _ => Err(SyntaxError::from_location(self.tokenizer.location())
                 .with_message(format!(
@@ -1331,10 +784,7 @@ impl<'l> CelCompiler<'l> {
         }
     }
 
-    fn parse_expression_list(
-        &mut self,
-        ending: Token,
-    ) -> CelResult<Vec<(CompiledProg, AstNode<Expr>)>> {
+    fn parse_expression_list(&mut self, ending: Token) -> CelResult<Vec<AstNode<Expr>>> {
         let mut exprs = Vec::new();
 
         'outer: loop {
@@ -1347,8 +797,7 @@ impl<'l> CelCompiler<'l> {
                 None => {}
             }
 
-            let compiled = self.parse_expression()?;
-            exprs.push(compiled);
+            exprs.push(self.parse_expression()?);
 
             match self.tokenizer.peek()?.as_token() {
                 Some(Token::Comma) => {
@@ -1362,7 +811,7 @@ impl<'l> CelCompiler<'l> {
         Ok(exprs)
     }
 
-    fn parse_obj_inits(&mut self) -> CelResult<Vec<(CompiledProg, AstNode<Expr>)>> {
+    fn parse_obj_inits(&mut self) -> CelResult<Vec<AstNode<ObjInit>>> {
         let mut inits = Vec::new();
 
         'outer: loop {
@@ -1370,7 +819,7 @@ impl<'l> CelCompiler<'l> {
                 break 'outer;
             }
 
-            let compiled_key = self.parse_expression()?;
+            let key_ast = self.parse_expression()?;
 
             let next_token = self.tokenizer.next()?.into_token();
             if next_token != Some(Token::Colon) {
@@ -1378,11 +827,17 @@ impl<'l> CelCompiler<'l> {
                     .with_message(format!("Invalid token: expected ':' got {:?}", next_token))
                     .into());
             }
-            // MkDict expects value then key
-            let compiled_value = self.parse_expression()?;
 
-            inits.push(compiled_value);
-            inits.push(compiled_key);
+            let value_ast = self.parse_expression()?;
+            let range = key_ast.range().surrounding(value_ast.range());
+
+            inits.push(AstNode::new(
+                ObjInit {
+                    key: key_ast,
+                    value: value_ast,
+                },
+                range,
+            ));
 
             match self.tokenizer.peek()?.as_token() {
                 Some(Token::Comma) => {
@@ -1395,26 +850,13 @@ impl<'l> CelCompiler<'l> {
 
         Ok(inits)
     }
-
-    #[inline]
-    fn check_for_const(&self, member_prime_node: CompiledProg) -> CompiledProg {
-        let mut i = Interpreter::empty();
-        i.add_bindings(&self.bindings);
-        let bc = member_prime_node.into_unresolved_bytecode().resolve();
-        let r = i.run_raw(&bc, true);
-
-        match r {
-            Ok(v) => CompiledProg::with_const(v),
-            Err(_) => CompiledProg::with_bytecode(bc),
-        }
-    }
 }
 
 #[cfg(test)]
 mod test {
     use test_case::test_case;
 
-    use crate::compiler::string_tokenizer::StringTokenizer;
+    use crate::{compiler::string_tokenizer::StringTokenizer, ByteCode};
 
     use super::CelCompiler;
 
@@ -1450,4 +892,24 @@ mod test {
         assert!(e.is_err());
         let _ = format!("{}", e.unwrap_err());
     }
+
+    #[test_case("l.all(x, x > 0)"; "all")]
+    #[test_case("l.map(x, x > 0, x * 2)"; "map with filter")]
+    #[test_case("l.reduce(acc, x, acc + x, 0)"; "reduce")]
+    #[test_case("m.transformMap(k, v, v)"; "transformMap")]
+    fn comprehension_bytecode(input: &str) {
+        let mut tokenizer = StringTokenizer::with_input(input);
+        let prog = CelCompiler::with_tokenizer(&mut tokenizer)
+            .compile()
+            .unwrap();
+
+        assert!(prog
+            .bytecode()
+            .iter()
+            .any(|b| matches!(b, ByteCode::CompBegin { .. })));
+        assert!(!prog
+            .bytecode()
+            .iter()
+            .any(|b| matches!(b, ByteCode::Call(_))));
+    }
 }
@@ -0,0 +1,878 @@
+use std::collections::HashMap;
+
+use super::{comprehension::ComprehensionMacro, pattern_utils::PrefixPattern, CelCompiler};
+use crate::{
+    compile,
+    compiler::{
+        ast_node::AstNode,
+        compiled_prog::{CompiledProg, NodeValue, PreResolvedCodePoint},
+        grammar::*,
+        source_location::SourceLocation,
+        syntax_error::SyntaxError,
+        tokens::FStringSegment,
+    },
+    interp::{Interpreter, JmpWhen, QualifiedIdent},
+    types::CelBytes,
+    utils::{qualified_names, FormatSpec},
+    BindContext, ByteCode, CelError, CelResult, CelValue, CelValueDyn, Dialect, Program,
+    StringTokenizer,
+};
+
+/// Generates the bytecode of an AST, whether parsed from source or built directly.
+pub struct CodeGen {
+    bindings: BindContext<'static>,
+    pub(super) container: Option<String>,
+    pub(super) dialect: Dialect,
+
+    next_label: u32,
+}
+
+impl CodeGen {
+    pub fn new() -> Self {
+        CodeGen {
+            bindings: BindContext::for_compile(),
+            container: None,
+            dialect: Dialect::default(),
+            next_label: 0,
+        }
+    }
+
+    /// Compiles `ast` into a program recording `source` as the text it came from.
+    pub fn compile(mut self, ast: AstNode<Expr>, source: String) -> CelResult<Program> {
+        let cprog = self.compile_expr(&ast)?;
+
+        let mut prog = cprog.into_program(source);
+        prog.details_mut().add_ast(ast);
+        prog.details_mut().set_container(self.container.take());
+        prog.details_mut().set_dialect(self.dialect);
+
+        Ok(prog)
+    }
+
+    /// Whether `name` is a type, which a match pattern tests values against.
+    pub fn is_type(&self, name: &str) -> bool {
+        self.bindings.get_type(name).is_some()
+    }
+
+    pub(super) fn new_label(&mut self) -> u32 {
+        let n = self.next_label;
+        self.next_label += 1;
+        n
+    }
+
+    fn compile_expr(&mut self, ast: &AstNode<Expr>) -> CelResult<CompiledProg> {
+        match ast.node() {
+            Expr::Unary(or_ast) => Ok(CompiledProg::from_node(
+                self.compile_conditional_or(or_ast)?,
+            )),
+            Expr::Ternary {
+                condition,
+                true_clause,
+                false_clause,
+            } => self.compile_turnary_expression(condition, true_clause, false_clause),
+            Expr::Match { condition, cases } => {
+                if self.dialect.is_strict() {
+                    return Err(syntax_error(
+                        ast.start(),
+                        "match expressions are not part of strict CEL",
+                    ));
+                }
+
+                self.compile_match_expression(condition, cases)
+            }
+        }
+    }
+
+    fn compile_turnary_expression(
+        &mut self,
+        condition: &AstNode<ConditionalOr>,
+        true_clause: &AstNode<ConditionalOr>,
+        false_clause: &AstNode<Expr>,
+    ) -> CelResult<CompiledProg> {
+        let (expr_node, mut details) = self.compile_conditional_or(condition)?.into_parts();
+        let (true_clause_node, true_clause_details) =
+            self.compile_conditional_or(true_clause)?.into_parts();
+        let (false_clause_node, false_clause_details) =
+            self.compile_expr(false_clause)?.into_parts();
+
+        details.union_from(true_clause_details);
+        details.union_from(false_clause_details);
+
+        let turnary_node = if let NodeValue::ConstExpr(i) = expr_node {
+            let inner = if i.is_err() {
+                NodeValue::ConstExpr(i)
+            } else if cfg!(feature = "type_prop") && !self.dialect.is_strict() {
+                if i.is_truthy() {
+                    true_clause_node
+                } else {
+                    false_clause_node
+                }
+            } else if let CelValue::Bool(b) = i {
+                if b {
+                    true_clause_node
+                } else {
+                    false_clause_node
+                }
+            } else {
+                NodeValue::ConstExpr(CelValue::from_err(CelError::Value(format!(
+                    "{} cannot be converted to bool",
+                    i.as_type()
+                ))))
+            };
+
+            CompiledProg { inner, details }
+        } else {
+            let after_true_clause = self.new_label();
+            let end_label = self.new_label();
+
+            CompiledProg {
+                inner: NodeValue::Bytecode(
+                    expr_node
+                        .into_bytecode()
+                        .into_iter()
+                        .chain([PreResolvedCodePoint::JmpCond {
+                            when: JmpWhen::False,
+                            label: after_true_clause,
+                        }])
+                        .chain(true_clause_node.into_bytecode().into_iter())
+                        .chain([
+                            PreResolvedCodePoint::Jmp { label: end_label },
+                            PreResolvedCodePoint::Label(after_true_clause),
+                        ])
+                        .chain(false_clause_node.into_bytecode().into_iter())
+                        .chain([PreResolvedCodePoint::Label(end_label)])
+                        .collect(),
+                ),
+                details,
+            }
+        };
+
+        Ok(turnary_node)
+    }
+
+    fn compile_match_expression(
+        &mut self,
+        condition: &AstNode<Expr>,
+        cases: &[AstNode<MatchCase>],
+    ) -> CelResult<CompiledProg> {
+        let (node_value, mut node_details) = self.compile_expr(condition)?.into_parts();
+        let mut node_bytecode = node_value.into_bytecode();
+
+        let mut all_parts = Vec::new();
+
+        for case in cases.iter() {
+            let (pattern_bytecode, pattern_details) = self
+                .compile_match_pattern(&case.node().pattern)?
+                .into_parts();
+            let pattern_bytecode = pattern_bytecode.into_bytecode();
+
+            node_details.union_from(pattern_details);
+
+            let (expr_bytecode, expr_details) = self.compile_expr(&case.node().expr)?.into_parts();
+            let expr_bytecode: Vec<_> = [ByteCode::Pop.into()]
+                .into_iter()
+                .chain(expr_bytecode.into_bytecode().into_iter())
+                .collect();
+
+            node_details.union_from(expr_details);
+
+            all_parts.push((pattern_bytecode, expr_bytecode));
+        }
+
+        // After match expression label
+        let after_match_s_l = self.new_label();
+
+        for (pattern_bytecode, expr_bytecode) in all_parts.into_iter() {
+            let after_case_l = self.new_label();
+
+            node_bytecode.push(ByteCode::Dup);
+            node_bytecode.extend(pattern_bytecode.into_iter());
+            node_bytecode.push(PreResolvedCodePoint::JmpCond {
+                when: JmpWhen::False,
+                label: after_case_l,
+            });
+
+            node_bytecode.extend(expr_bytecode);
+            node_bytecode.push(PreResolvedCodePoint::Jmp {
+                label: after_match_s_l,
+            });
+            node_bytecode.push(PreResolvedCodePoint::Label(after_case_l));
+        }
+
+        node_bytecode.extend([
+            ByteCode::Pop.into(),
+            ByteCode::Push(CelValue::from_null()).into(),
+            PreResolvedCodePoint::Label(after_match_s_l),
+        ]);
+
+        Ok(CompiledProg::new(
+            NodeValue::Bytecode(node_bytecode),
+            node_details,
+        ))
+    }
+
+    fn compile_match_pattern(&mut self, ast: &AstNode<MatchPattern>) -> CelResult<CompiledProg> {
+        match ast.node() {
+            MatchPattern::Any(_) => Ok(CompiledProg::with_bytecode(
+                [
+                    ByteCode::Pop,                     // pop off the pattern value
+                    ByteCode::Push(CelValue::true_()), // push true
+                ]
+                .into_iter()
+                .collect(),
+            )),
+            MatchPattern::Type(type_pattern) => Ok(CompiledProg::with_bytecode(
+                [
+                    ByteCode::Push(CelValue::Ident("type".to_owned())),
+                    ByteCode::Call(1),
+                    ByteCode::Push(CelValue::Ident(
+                        type_pattern.node().as_type_str().to_owned(),
+                    )),
+                    ByteCode::Eq,
+                ]
+                .into_iter()
+                .collect(),
+            )),
+            MatchPattern::Cmp { op, or } => {
+                let or_prod = self.compile_conditional_or(or)?;
+                let or_details = or_prod.details().clone();
+                let mut or_bc = or_prod.into_unresolved_bytecode();
+
+                or_bc.push(PrefixPattern::from_ast(op.node()).as_bytecode());
+
+                Ok(CompiledProg::new(NodeValue::Bytecode(or_bc), or_details))
+            }
+        }
+    }
+
+    fn compile_conditional_or(&mut self, ast: &AstNode<ConditionalOr>) -> CelResult<CompiledProg> {
+        // `a || b || c` nests to the left, all of its operands jump to the same label
+        let mut rhs_asts = Vec::new();
+        let mut lhs_ast = ast;
+        let first = loop {
+            match lhs_ast.node() {
+                ConditionalOr::Binary { lhs, rhs } => {
+                    rhs_asts.push(rhs);
+                    lhs_ast = lhs;
+                }
+                ConditionalOr::Unary(first) => break first,
+            }
+        };
+
+        let mut current_node = self.compile_conditional_and(first)?;
+
+        let label = self.new_label();
+
+        for rhs_ast in rhs_asts.into_iter().rev() {
+            let rhs_node = self.compile_conditional_and(rhs_ast)?;
+
+            let jmp_node = CompiledProg::with_code_points(vec![
+                PreResolvedCodePoint::Bytecode(ByteCode::Test),
+                PreResolvedCodePoint::Bytecode(ByteCode::Dup),
+                PreResolvedCodePoint::JmpCond {
+                    when: JmpWhen::True,
+                    label,
+                },
+            ]);
+
+            current_node = compile!(
+                [ByteCode::Or.into()],
+                self.dialect.binary(&ByteCode::Or, current_node, rhs_node),
+                current_node,
+                jmp_node,
+                rhs_node
+            );
+        }
+
+        current_node.append_if_bytecode([PreResolvedCodePoint::Label(label)]);
+
+        Ok(current_node)
+    }
+
+    fn compile_conditional_and(
+        &mut self,
+        ast: &AstNode<ConditionalAnd>,
+    ) -> CelResult<CompiledProg> {
+        let mut rhs_asts = Vec::new();
+        let mut lhs_ast = ast;
+        let first = loop {
+            match lhs_ast.node() {
+                ConditionalAnd::Binary { lhs, rhs } => {
+                    rhs_asts.push(rhs);
+                    lhs_ast = lhs;
+                }
+                ConditionalAnd::Unary(first) => break first,
+            }
+        };
+
+        let mut current_node = self.compile_relation(first)?;
+
+        let label = self.new_label();
+
+        for rhs_ast in rhs_asts.into_iter().rev() {
+            let rhs_node = self.compile_relation(rhs_ast)?;
+
+            let jmp_node = CompiledProg::with_code_points(vec![
+                PreResolvedCodePoint::Bytecode(ByteCode::Test),
+                PreResolvedCodePoint::Bytecode(ByteCode::Dup),
+                PreResolvedCodePoint::JmpCond {
+                    when: JmpWhen::False,
+                    label,
+                },
+            ]);
+
+            current_node = compile!(
+                [ByteCode::And.into()],
+                self.dialect.binary(&ByteCode::And, current_node, rhs_node),
+                current_node,
+                jmp_node,
+                rhs_node
+            );
+        }
+
+        current_node.append_if_bytecode([PreResolvedCodePoint::Label(label)]);
+
+        Ok(current_node)
+    }
+
+    fn compile_relation(&mut self, ast: &AstNode<Relation>) -> CelResult<CompiledProg> {
+        let mut rhs_asts = Vec::new();
+        let mut lhs_ast = ast;
+        let first = loop {
+            match lhs_ast.node() {
+                Relation::Binary { lhs, op, rhs } => {
+                    rhs_asts.push((op, rhs));
+                    lhs_ast = lhs;
+                }
+                Relation::Unary(first) => break first,
+            }
+        };
+
+        let mut current_node = self.compile_addition(first)?;
+
+        for (op, rhs_ast) in rhs_asts.into_iter().rev() {
+            let rhs_node = self.compile_addition(rhs_ast)?;
+
+            current_node = match op {
+                Relop::In => compile!(
+                    [ByteCode::In.into()],
+                    self.dialect.in_(current_node, rhs_node),
+                    current_node,
+                    rhs_node
+                ),
+                _ => {
+                    let bytecode = match op {
+                        Relop::Lt => ByteCode::Lt,
+                        Relop::Le => ByteCode::Le,
+                        Relop::Eq => ByteCode::Eq,
+                        Relop::Ne => ByteCode::Ne,
+                        Relop::Ge => ByteCode::Ge,
+                        Relop::Gt => ByteCode::Gt,
+                        Relop::In => unreachable!(),
+                    };
+
+                    compile!(
+                        [bytecode.clone().into()],
+                        self.dialect.binary(&bytecode, current_node, rhs_node),
+                        current_node,
+                        rhs_node
+                    )
+                }
+            };
+        }
+
+        Ok(current_node)
+    }
+
+    fn compile_addition(&mut self, ast: &AstNode<Addition>) -> CelResult<CompiledProg> {
+        let mut rhs_asts = Vec::new();
+        let mut lhs_ast = ast;
+        let first = loop {
+            match lhs_ast.node() {
+                Addition::Binary { lhs, op, rhs } => {
+                    rhs_asts.push((op, rhs));
+                    lhs_ast = lhs;
+                }
+                Addition::Unary(first) => break first,
+            }
+        };
+
+        let mut current_node = self.compile_multiplication(first)?;
+
+        for (op, rhs_ast) in rhs_asts.into_iter().rev() {
+            let rhs_node = self.compile_multiplication(rhs_ast)?;
+            let bytecode = match op {
+                AddOp::Add => ByteCode::Add,
+                AddOp::Sub => ByteCode::Sub,
+            };
+
+            current_node = compile!(
+                [bytecode.clone().into()],
+                self.dialect.binary(&bytecode, current_node, rhs_node),
+                current_node,
+                rhs_node
+            );
+        }
+
+        Ok(current_node)
+    }
+
+    fn compile_multiplication(&mut self, ast: &AstNode<Multiplication>) -> CelResult<CompiledProg> {
+        let mut rhs_asts = Vec::new();
+        let mut lhs_ast = ast;
+        let first = loop {
+            match lhs_ast.node() {
+                Multiplication::Binary { lhs, op, rhs } => {
+                    rhs_asts.push((op, rhs));
+                    lhs_ast = lhs;
+                }
+                Multiplication::Unary(first) => break first,
+            }
+        };
+
+        let mut current_node = self.compile_unary(first)?;
+
+        for (op, rhs_ast) in rhs_asts.into_iter().rev() {
+            let rhs_node = self.compile_unary(rhs_ast)?;
+            let bytecode = match op {
+                MultOp::Mult => ByteCode::Mul,
+                MultOp::Div => ByteCode::Div,
+                MultOp::Mod => ByteCode::Mod,
+            };
+
+            current_node = compile!(
+                [bytecode.clone().into()],
+                self.dialect.binary(&bytecode, current_node, rhs_node),
+                current_node,
+                rhs_node
+            );
+        }
+
+        Ok(current_node)
+    }
+
+    fn compile_unary(&mut self, ast: &AstNode<Unary>) -> CelResult<CompiledProg> {
+        match ast.node() {
+            Unary::Member(member) => self.compile_member(member),
+            Unary::NotMember { nots, member } => {
+                let not = Self::compile_not_list(nots);
+                Ok(self.compile_member(member)?.append_result(not))
+            }
+            Unary::NegMember { negs, member } => {
+                let neg = Self::compile_neg_list(negs);
+                Ok(self.compile_member(member)?.append_result(neg))
+            }
+        }
+    }
+
+    fn compile_not_list(ast: &AstNode<NotList>) -> CompiledProg {
+        match ast.node() {
+            NotList::List { tail } => {
+                let not_list = Self::compile_not_list(tail);
+                compile!([ByteCode::Not.into()], not_list, not_list)
+            }
+            NotList::EmptyList => CompiledProg::empty(),
+        }
+    }
+
+    fn compile_neg_list(ast: &AstNode<NegList>) -> CompiledProg {
+        match ast.node() {
+            NegList::List { tail } => {
+                let neg_list = Self::compile_neg_list(tail);
+                compile!([ByteCode::Neg.into()], neg_list, neg_list)
+            }
+            NegList::EmptyList => CompiledProg::empty(),
+        }
+    }
+
+    /// Compiles the member accesses, indexes and calls following the primary.
+    fn compile_member(&mut self, ast: &AstNode<Member>) -> CelResult<CompiledProg> {
+        let Member { primary, member } = ast.node();
+
+        // The dotted name spelled out so far, `a.b.c`, while the member chain is only
+        // idents. It is compiled as one qualified name once the chain ends.
+        let mut qualified = match primary.node() {
+            Primary::Ident(Ident(name)) => Some(name.clone()),
+            _ => None,
+        };
+        let mut member_prime_node = match qualified {
+            Some(_) => CompiledProg::empty(),
+            None => CompiledProg::from_node(self.compile_primary(primary)?),
+        };
+        // A comprehension macro being called on the member chain so far
+        let mut comprehension: Option<(ComprehensionMacro, CompiledProg)> = None;
+
+        for (i, member_prime) in member.iter().enumerate() {
+            let is_call = matches!(
+                member.get(i + 1).map(|m| m.node()),
+                Some(MemberPrime::Call { .. })
+            );
+
+            match member_prime.node() {
+                MemberPrime::MemberAccess { ident } => {
+                    let Ident(ident) = ident.node();
+
+                    if let Some(q) = qualified.as_mut() {
+                        if is_call {
+                            comprehension = ComprehensionMacro::from_name(ident)
+                                .map(|m| (m, self.compile_name(q)));
+                        }
+
+                        q.push('.');
+                        q.push_str(ident);
+
+                        if is_call && q == "cel.bind" {
+                            comprehension = Some((ComprehensionMacro::Bind, CompiledProg::empty()));
+                        } else if is_call && self.resolve_callable(q).is_some() {
+                            // A qualified function shadows the macro
+                            comprehension = None;
+                        } else if let Some(type_) = self.resolve_type(q).filter(|_| !is_call) {
+                            member_prime_node = CompiledProg::with_const(type_);
+                            qualified = None;
+                        }
+
+                        continue;
+                    }
+
+                    comprehension = ComprehensionMacro::from_name(ident)
+                        .filter(|_| is_call)
+                        .map(|m| (m, member_prime_node.clone()));
+
+                    let res = CompiledProg::with_const(CelValue::from_ident(ident));
+
+                    member_prime_node = CompiledProg::from_children2_w_bytecode_cannone(
+                        member_prime_node,
+                        res,
+                        vec![if is_call {
+                            ByteCode::AccessMethod
+                        } else {
+                            ByteCode::Access
+                        }],
+                        |o, c| {
+                            if let CelValue::Ident(s) = c {
+                                // Allow for const eval for obj members in the
+                                // off chance a user does somthing like this
+                                // `{'a': 3}.a`. Its const value will be 3.
+                                if o.is_obj() {
+                                    // So if this fails we should break the const
+                                    // status and let the compiler generate some
+                                    // bytecode for function discovery and such.
+                                    match o.access(s) {
+                                        CelValue::Err(_) => None,
+                                        o => Some(o),
+                                    }
+                                } else {
+                                    None
+                                }
+                            } else {
+                                Some(CelValue::from_err(CelError::value(
+                                    "Accessor must be ident",
+                                )))
+                            }
+                        },
+                    );
+                }
+                MemberPrime::Call { call } => {
+                    let args_len = call.node().exprs.len();
+
+                    // The arguments are kept last first, so they get popped off the
+                    // stack in order
+                    let mut args = call
+                        .node()
+                        .exprs
+                        .iter()
+                        .map(|a| self.compile_expr(a))
+                        .collect::<CelResult<Vec<_>>>()?;
+
+                    let mut compiled = None;
+                    if let Some((macro_, range)) = comprehension.take() {
+                        args.reverse();
+                        match self.compile_comprehension(macro_, range, args) {
+                            Ok(node) => {
+                                compiled = Some(node);
+                                args = Vec::new();
+                            }
+                            Err(unused) => {
+                                args = unused;
+                                args.reverse();
+                            }
+                        }
+                    }
+
+                    member_prime_node =
+                        match compiled {
+                            Some(node) => {
+                                qualified = None;
+                                node
+                            }
+                            None => {
+                                let mut args_node = CompiledProg::empty();
+                                for a in args.into_iter() {
+                                    args_node = args_node.append_result(
+                                        CompiledProg::with_code_points(vec![ByteCode::Push(
+                                            a.into_unresolved_bytecode().resolve().into(),
+                                        )
+                                        .into()]),
+                                    )
+                                }
+
+                                if let Some(q) = qualified.take() {
+                                    member_prime_node = match self.resolve_callable(&q) {
+                                        Some(name) => {
+                                            CompiledProg::with_code_points(vec![ByteCode::Push(
+                                                CelValue::from_ident(&name),
+                                            )
+                                            .into()])
+                                        }
+                                        None => {
+                                            let ident =
+                                                QualifiedIdent::new(self.container.as_deref(), &q);
+                                            let root = ident.root().to_owned();
+                                            CompiledProg::with_code_points(vec![
+                                                ByteCode::ResolveCall(ident).into(),
+                                            ])
+                                            .add_ident(&root)
+                                        }
+                                    };
+                                }
+
+                                args_node.consume_child(member_prime_node).consume_child(
+                                    CompiledProg::with_code_points(vec![ByteCode::Call(
+                                        args_len as u32,
+                                    )
+                                    .into()]),
+                                )
+                            }
+                        };
+
+                    member_prime_node = self.check_for_const(member_prime_node);
+                }
+                MemberPrime::ArrayAccess { access } => {
+                    if let Some(q) = qualified.take() {
+                        member_prime_node = self.compile_name(&q);
+                    }
+
+                    let index_node = self.compile_expr(access)?;
+
+                    member_prime_node = compile!(
+                        [ByteCode::Index.into()],
+                        self.dialect.index(member_prime_node, index_node),
+                        member_prime_node,
+                        index_node
+                    );
+                }
+                MemberPrime::Empty => {}
+            }
+        }
+
+        if let Some(q) = qualified {
+            member_prime_node = self.compile_name(&q);
+        }
+
+        Ok(member_prime_node)
+    }
+
+    fn compile_primary(&mut self, ast: &AstNode<Primary>) -> CelResult<CompiledProg> {
+        match ast.node() {
+            Primary::Type => Ok(Self::compile_ident("type")),
+            Primary::Ident(Ident(val)) => Ok(self.compile_name(val)),
+            Primary::Parens(expr) => Ok(CompiledProg::from_node(self.compile_expr(expr)?)),
+            Primary::ListConstruction(list) => {
+                let exprs = &list.node().exprs;
+                let expr_list = exprs
+                    .iter()
+                    .map(|e| self.compile_expr(e))
+                    .collect::<CelResult<Vec<_>>>()?;
+
+                Ok(CompiledProg::from_children_w_bytecode(
+                    expr_list,
+                    vec![ByteCode::MkList(exprs.len() as u32)],
+                    |c| c.into(),
+                ))
+            }
+            Primary::ObjectInit(inits) => {
+                let inits = &inits.node().inits;
+
+                // init is created as value then key for mkdict stack
+                let mut compiled_children = Vec::new();
+                for init in inits.iter() {
+                    compiled_children.push(self.compile_expr(&init.node().value)?);
+                    compiled_children.push(self.compile_expr(&init.node().key)?);
+                }
+
+                Ok(CompiledProg::from_children_w_bytecode(
+                    compiled_children,
+                    vec![ByteCode::MkDict(inits.len() as u32)],
+                    |vals| {
+                        let mut obj_map = HashMap::new();
+                        for i in (0..vals.len()).step_by(2) {
+                            let key = if let CelValue::String(ref k) = vals[i + 1] {
+                                k
+                            } else {
+                                return CelValue::from_err(CelError::value(
+                                    "Only strings can be object keys",
+                                ));
+                            };
+
+                            obj_map.insert(key.clone(), vals[i].clone());
+                        }
+
+                        obj_map.into()
+                    },
+                ))
+            }
+            Primary::Literal(literal) => self.compile_literal(literal, ast.start()),
+        }
+    }
+
+    fn compile_literal(
+        &mut self,
+        literal: &LiteralsAndKeywords,
+        loc: SourceLocation,
+    ) -> CelResult<CompiledProg> {
+        match literal {
+            LiteralsAndKeywords::Type => Ok(Self::compile_ident("type")),
+            LiteralsAndKeywords::NullType => Ok(Self::compile_ident("null_type")),
+            LiteralsAndKeywords::Int => Ok(Self::compile_ident("int")),
+            LiteralsAndKeywords::Uint => Ok(Self::compile_ident("uint")),
+            LiteralsAndKeywords::Float => Ok(Self::compile_ident("double")),
+            LiteralsAndKeywords::Bool => Ok(Self::compile_ident("bool")),
+            LiteralsAndKeywords::String => Ok(Self::compile_ident("string")),
+            LiteralsAndKeywords::Bytes => Ok(Self::compile_ident("bytes")),
+            LiteralsAndKeywords::Timestamp => Ok(Self::compile_ident("timestamp")),
+            LiteralsAndKeywords::Duration => Ok(Self::compile_ident("duration")),
+            LiteralsAndKeywords::NullLit => Ok(CompiledProg::with_const(CelValue::from_null())),
+            LiteralsAndKeywords::IntegerLit(val) => Ok(CompiledProg::with_const((*val).into())),
+            LiteralsAndKeywords::UnsignedLit(val) => Ok(CompiledProg::with_const((*val).into())),
+            LiteralsAndKeywords::FloatingLit(val) => Ok(CompiledProg::with_const((*val).into())),
+            LiteralsAndKeywords::QuantityLit(val, unit) => {
+                if self.dialect.is_strict() {
+                    return Err(syntax_error(
+                        loc,
+                        "unit literals are not part of strict CEL",
+                    ));
+                }
+
+                // `5kg` is `quantity(5.0, 'kg')`, arguments pushed last first
+                Ok(CompiledProg::with_bytecode(
+                    [
+                        ByteCode::Push(CelValue::String(unit.clone())),
+                        ByteCode::Push((*val).into()),
+                        ByteCode::Push(CelValue::from_ident("quantity")),
+                        ByteCode::Call(2),
+                    ]
+                    .into_iter()
+                    .collect(),
+                ))
+            }
+            LiteralsAndKeywords::StringLit(val) => Ok(CompiledProg::with_const(val.clone().into())),
+            LiteralsAndKeywords::ByteStringLit(val) => Ok(CompiledProg::with_const(
+                CelBytes::from_vec(val.clone()).into(),
+            )),
+            LiteralsAndKeywords::FStringList(segments) => {
+                if self.dialect.is_strict() {
+                    return Err(syntax_error(loc, "f-strings are not part of strict CEL"));
+                }
+
+                let mut bytecode = Vec::<PreResolvedCodePoint>::new();
+
+                for segment in segments.iter() {
+                    match segment {
+                        FStringSegment::Lit(c) => {
+                            bytecode.push(ByteCode::Push(CelValue::String(c.clone())).into())
+                        }
+                        FStringSegment::Expr(e) => {
+                            bytecode.push(self.fstring_expr(e)?);
+                            bytecode.push(ByteCode::FmtValue(FormatSpec::default()).into());
+                        }
+                        FStringSegment::FmtExpr(e, spec) => {
+                            let spec = FormatSpec::parse(spec)
+                                .map_err(|err| syntax_error(loc, &err.to_string()))?;
+
+                            bytecode.push(self.fstring_expr(e)?);
+                            bytecode.push(ByteCode::FmtValue(spec).into());
+                        }
+                    }
+                }
+
+                // Reverse it so its evaluated in order on the stack
+                bytecode.push(ByteCode::FmtString(segments.len() as u32).into());
+
+                Ok(CompiledProg::with_code_points(bytecode))
+            }
+            LiteralsAndKeywords::BooleanLit(val) => Ok(CompiledProg::with_const((*val).into())),
+        }
+    }
+
+    /// Compiles a variable name, `a.b.c`, resolved within the container.
+    fn compile_name(&self, name: &str) -> CompiledProg {
+        let ident = QualifiedIdent::new(self.container.as_deref(), name);
+        let root = ident.root().to_owned();
+
+        CompiledProg::with_code_points(vec![ByteCode::Resolve(ident).into()]).add_ident(&root)
+    }
+
+    fn compile_ident(name: &str) -> CompiledProg {
+        CompiledProg::with_code_points(vec![ByteCode::Push(CelValue::from_ident(name)).into()])
+            .add_ident(name)
+    }
+
+    /// Compiles a format string placeholder into a bytecode value that is
+    /// resolved when the placeholder is formatted.
+    fn fstring_expr(&mut self, source: &str) -> CelResult<PreResolvedCodePoint> {
+        let mut tok = StringTokenizer::with_input(source);
+        let ast = CelCompiler::with_tokenizer(&mut tok).parse_expression()?;
+
+        let e = self.compile_expr(&ast)?;
+
+        Ok(ByteCode::Push(CelValue::ByteCode(e.into_unresolved_bytecode().resolve())).into())
+    }
+
+    /// Finds the function, macro or type `name` refers to from within the container,
+    /// only when doing so changes what the call would otherwise compile to.
+    fn resolve_callable(&self, name: &str) -> Option<String> {
+        let resolved = qualified_names(self.container.as_deref(), name)
+            .into_iter()
+            .find(|n| {
+                self.bindings.get_func(n).is_some()
+                    || self.bindings.get_macro(n).is_some()
+                    || self.bindings.get_type(n).is_some()
+            })?;
+
+        if resolved.contains('.') {
+            Some(resolved)
+        } else {
+            None
+        }
+    }
+
+    fn resolve_type(&self, name: &str) -> Option<CelValue> {
+        qualified_names(self.container.as_deref(), name)
+            .iter()
+            .find_map(|n| self.bindings.get_type(n))
+            .cloned()
+    }
+
+    #[inline]
+    fn check_for_const(&self, member_prime_node: CompiledProg) -> CompiledProg {
+        let mut i = Interpreter::empty().with_dialect(self.dialect);
+        i.add_bindings(&self.bindings);
+        let (node, details) = member_prime_node.into_parts();
+        let bc = node.into_bytecode().resolve();
+        let r = i.run_raw(&bc, true);
+
+        match r {
+            // Dyn values can't be serialized so they are rebuilt when the program runs
+            Ok(CelValue::Dyn(_)) | Err(_) => {
+                CompiledProg::new(NodeValue::Bytecode(bc.into()), details)
+            }
+            Ok(v) => CompiledProg::with_const(v),
+        }
+    }
+}
+
+fn syntax_error(loc: SourceLocation, message: &str) -> CelError {
+    SyntaxError::from_location(loc)
+        .with_message(message.to_owned())
+        .into()
+}
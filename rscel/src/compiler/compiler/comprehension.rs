@@ -0,0 +1,356 @@
+use std::collections::HashMap;
+
+use crate::{
+    compiler::compiled_prog::{CompiledProg, NodeValue, PreResolvedCodePoint},
+    interp::JmpWhen,
+    ByteCode, CelValue, ProgramDetails,
+};
+
+use super::CodeGen;
+
+/// Name of the accumulator of comprehensions that don't name their own, it can't be
+/// spelled in an expression so it never shadows a user variable.
+const RESULT: &str = "@result";
+
+/// The macros compiled to comprehension bytecode instead of a macro call.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ComprehensionMacro {
+    All,
+    Exists,
+    ExistsOne,
+    Map,
+    Filter,
+    TransformList,
+    TransformMap,
+    TransformMapEntry,
+    Reduce,
+    /// `cel.bind(name, init, expr)`, compiled as a comprehension over nothing whose
+    /// accumulator is the bound name
+    Bind,
+}
+
+impl ComprehensionMacro {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "all" => Some(ComprehensionMacro::All),
+            "exists" => Some(ComprehensionMacro::Exists),
+            "exists_one" | "existsOne" => Some(ComprehensionMacro::ExistsOne),
+            "map" => Some(ComprehensionMacro::Map),
+            "filter" => Some(ComprehensionMacro::Filter),
+            "transformList" => Some(ComprehensionMacro::TransformList),
+            "transformMap" => Some(ComprehensionMacro::TransformMap),
+            "transformMapEntry" => Some(ComprehensionMacro::TransformMapEntry),
+            "reduce" => Some(ComprehensionMacro::Reduce),
+            _ => None,
+        }
+    }
+
+    /// The number of iteration variables given `n_args` arguments, `None` when the
+    /// macro doesn't take that many.
+    fn n_vars(&self, n_args: usize) -> Option<usize> {
+        match (self, n_args) {
+            (
+                ComprehensionMacro::All
+                | ComprehensionMacro::Exists
+                | ComprehensionMacro::ExistsOne,
+                2 | 3,
+            ) => Some(n_args - 1),
+            (ComprehensionMacro::Map, 2 | 3) | (ComprehensionMacro::Filter, 2) => Some(1),
+            (
+                ComprehensionMacro::TransformList
+                | ComprehensionMacro::TransformMap
+                | ComprehensionMacro::TransformMapEntry,
+                3 | 4,
+            ) => Some(2),
+            // The accumulator and the iteration variable
+            (ComprehensionMacro::Reduce, 4) => Some(2),
+            (ComprehensionMacro::Bind, 3) => Some(1),
+            _ => None,
+        }
+    }
+}
+
+impl CodeGen {
+    /// Compiles `range.name(args...)` to a comprehension. The arguments are handed back
+    /// when they don't fit the macro so it can be compiled as a regular macro call.
+    pub(super) fn compile_comprehension(
+        &mut self,
+        macro_: ComprehensionMacro,
+        range: CompiledProg,
+        mut args: Vec<CompiledProg>,
+    ) -> Result<CompiledProg, Vec<CompiledProg>> {
+        let idents = match macro_.n_vars(args.len()) {
+            Some(n_vars) => args[..n_vars]
+                .iter()
+                .map(Self::plain_ident)
+                .collect::<Option<Vec<_>>>(),
+            None => None,
+        };
+        let Some(mut vars) = idents else {
+            return Err(args);
+        };
+
+        let mut exprs = args.split_off(vars.len());
+
+        // Params used by the expressions, other than the comprehension's own variables
+        let (outer, scoped) = match macro_ {
+            ComprehensionMacro::Reduce => (&exprs[1..], &exprs[..1]),
+            ComprehensionMacro::Bind => (&exprs[..1], &exprs[1..]),
+            _ => (&exprs[..0], &exprs[..]),
+        };
+        let mut scoped_details = ProgramDetails::new();
+        for expr in scoped.iter() {
+            scoped_details.union_from(expr.details().clone());
+        }
+        for var in vars.iter() {
+            scoped_details.remove_param(var);
+        }
+        let mut details = range.details().clone();
+        details.union_from(scoped_details);
+        for expr in outer.iter() {
+            details.union_from(expr.details().clone());
+        }
+
+        if macro_ == ComprehensionMacro::Bind {
+            let init = exprs.remove(0);
+            let expr = exprs.remove(0);
+
+            // [] init BEGIN(name) expr END
+            let bytecode = [ByteCode::Push(Vec::<CelValue>::new().into()).into()]
+                .into_iter()
+                .chain(code(init))
+                .chain([ByteCode::CompBegin {
+                    vars: Vec::new(),
+                    accu: vars.remove(0),
+                }
+                .into()])
+                .chain(code(expr))
+                .chain([ByteCode::CompEnd.into()])
+                .collect();
+
+            return Ok(CompiledProg::new(NodeValue::Bytecode(bytecode), details));
+        }
+
+        let loop_label = self.new_label();
+        let done_label = self.new_label();
+
+        let result_ident = || ByteCode::Push(CelValue::from_ident(RESULT)).into();
+        let append = |expr: CompiledProg| -> Vec<PreResolvedCodePoint> {
+            code(expr).chain([ByteCode::CompAppend.into()]).collect()
+        };
+
+        let mut accu = RESULT.to_owned();
+        let (init, cond, step, result): (
+            Vec<PreResolvedCodePoint>,
+            Vec<PreResolvedCodePoint>,
+            Vec<PreResolvedCodePoint>,
+            Vec<PreResolvedCodePoint>,
+        ) = match macro_ {
+            ComprehensionMacro::All => (
+                vec![ByteCode::Push(true.into()).into()],
+                vec![
+                    result_ident(),
+                    PreResolvedCodePoint::JmpCond {
+                        when: JmpWhen::False,
+                        label: done_label,
+                    },
+                ],
+                test_accu(exprs.remove(0)),
+                vec![result_ident()],
+            ),
+            ComprehensionMacro::Exists => (
+                vec![ByteCode::Push(false.into()).into()],
+                vec![
+                    result_ident(),
+                    ByteCode::Not.into(),
+                    PreResolvedCodePoint::JmpCond {
+                        when: JmpWhen::False,
+                        label: done_label,
+                    },
+                ],
+                test_accu(exprs.remove(0)),
+                vec![result_ident()],
+            ),
+            ComprehensionMacro::ExistsOne => (
+                vec![ByteCode::Push(0.into()).into()],
+                // A second match already decides the result
+                vec![
+                    result_ident(),
+                    ByteCode::Push(2.into()).into(),
+                    ByteCode::Lt.into(),
+                    PreResolvedCodePoint::JmpCond {
+                        when: JmpWhen::False,
+                        label: done_label,
+                    },
+                ],
+                self.filtered(
+                    exprs.remove(0),
+                    vec![
+                        result_ident(),
+                        ByteCode::Push(1.into()).into(),
+                        ByteCode::Add.into(),
+                        ByteCode::CompAccu.into(),
+                    ],
+                    loop_label,
+                ),
+                vec![
+                    result_ident(),
+                    ByteCode::Push(1.into()).into(),
+                    ByteCode::Eq.into(),
+                ],
+            ),
+            ComprehensionMacro::Map | ComprehensionMacro::TransformList => {
+                let transform = exprs.pop().expect("checked by n_vars");
+                let step = match exprs.pop() {
+                    Some(filter) => self.filtered(filter, append(transform), loop_label),
+                    None => append(transform),
+                };
+
+                (
+                    vec![ByteCode::Push(Vec::<CelValue>::new().into()).into()],
+                    Vec::new(),
+                    step,
+                    vec![result_ident()],
+                )
+            }
+            ComprehensionMacro::Filter => {
+                let keep = vec![
+                    ByteCode::Push(CelValue::from_ident(&vars[0])).into(),
+                    ByteCode::CompAppend.into(),
+                ];
+
+                (
+                    vec![ByteCode::Push(Vec::<CelValue>::new().into()).into()],
+                    Vec::new(),
+                    self.filtered(exprs.remove(0), keep, loop_label),
+                    vec![result_ident()],
+                )
+            }
+            ComprehensionMacro::TransformMap | ComprehensionMacro::TransformMapEntry => {
+                let transform = exprs.pop().expect("checked by n_vars");
+                let keep = if macro_ == ComprehensionMacro::TransformMap {
+                    // Append the single entry map `{key: transform}`
+                    code(transform)
+                        .chain([
+                            ByteCode::Push(CelValue::from_ident(&vars[0])).into(),
+                            ByteCode::MkDict(1).into(),
+                            ByteCode::CompAppend.into(),
+                        ])
+                        .collect()
+                } else {
+                    append(transform)
+                };
+                let step = match exprs.pop() {
+                    Some(filter) => self.filtered(filter, keep, loop_label),
+                    None => keep,
+                };
+
+                (
+                    vec![ByteCode::Push(HashMap::<String, CelValue>::new().into()).into()],
+                    Vec::new(),
+                    step,
+                    vec![result_ident()],
+                )
+            }
+            ComprehensionMacro::Bind => unreachable!("compiled above"),
+            ComprehensionMacro::Reduce => {
+                accu = vars.remove(0);
+                let init = exprs.pop().expect("checked by n_vars");
+                let step = exprs.pop().expect("checked by n_vars");
+
+                (
+                    code(init).collect(),
+                    Vec::new(),
+                    code(step).chain([ByteCode::CompAccu.into()]).collect(),
+                    vec![ByteCode::Push(CelValue::from_ident(&accu)).into()],
+                )
+            }
+        };
+
+        // range; init; BEGIN; loop: cond; NEXT done; step; JMP loop; done: result; END
+        let bytecode = code(range)
+            .chain(init)
+            .chain([
+                ByteCode::CompBegin { vars, accu }.into(),
+                PreResolvedCodePoint::Label(loop_label),
+            ])
+            .chain(cond)
+            .chain([PreResolvedCodePoint::CompNext { label: done_label }])
+            .chain(step)
+            .chain([
+                PreResolvedCodePoint::Jmp { label: loop_label },
+                PreResolvedCodePoint::Label(done_label),
+            ])
+            .chain(result)
+            .chain([ByteCode::CompEnd.into()])
+            .collect();
+
+        Ok(CompiledProg::new(NodeValue::Bytecode(bytecode), details))
+    }
+
+    /// Runs `keep` when `filter` is truthy. An error from the filter becomes the
+    /// accumulator, which ends the comprehension.
+    fn filtered(
+        &mut self,
+        filter: CompiledProg,
+        keep: Vec<PreResolvedCodePoint>,
+        loop_label: u32,
+    ) -> Vec<PreResolvedCodePoint> {
+        let not_kept = self.new_label();
+        let dropped = self.new_label();
+
+        code(filter)
+            .chain([
+                ByteCode::Test.into(),
+                ByteCode::Dup.into(),
+                PreResolvedCodePoint::JmpCond {
+                    when: JmpWhen::False,
+                    label: not_kept,
+                },
+                ByteCode::Pop.into(),
+            ])
+            .chain(keep)
+            .chain([
+                PreResolvedCodePoint::Jmp { label: loop_label },
+                // Either false or an error is left on the stack
+                PreResolvedCodePoint::Label(not_kept),
+                ByteCode::Dup.into(),
+                ByteCode::Not.into(),
+                PreResolvedCodePoint::JmpCond {
+                    when: JmpWhen::True,
+                    label: dropped,
+                },
+                ByteCode::CompAccu.into(),
+                PreResolvedCodePoint::Jmp { label: loop_label },
+                PreResolvedCodePoint::Label(dropped),
+                ByteCode::Pop.into(),
+            ])
+            .collect()
+    }
+
+    /// The name of an argument that is just an ident, like the `x` in `l.all(x, x > 0)`.
+    fn plain_ident(arg: &CompiledProg) -> Option<String> {
+        match &arg.inner {
+            NodeValue::Bytecode(bytecode) if bytecode.len() == 1 => match &bytecode[0] {
+                PreResolvedCodePoint::Bytecode(ByteCode::Resolve(ident))
+                    if !ident.name().contains('.') =>
+                {
+                    Some(ident.name().to_owned())
+                }
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+fn code(prog: CompiledProg) -> impl Iterator<Item = PreResolvedCodePoint> {
+    prog.into_unresolved_bytecode().into_iter()
+}
+
+/// `expr TEST COMPACCU`, the step of `all` and `exists`.
+fn test_accu(expr: CompiledProg) -> Vec<PreResolvedCodePoint> {
+    code(expr)
+        .chain([ByteCode::Test.into(), ByteCode::CompAccu.into()])
+        .collect()
+}
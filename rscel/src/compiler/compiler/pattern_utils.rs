@@ -24,6 +24,17 @@ impl PrefixPattern {
         }
     }
 
+    pub fn from_ast(op: &MatchCmpOp) -> Self {
+        match op {
+            MatchCmpOp::Eq => PrefixPattern::Eq,
+            MatchCmpOp::Neq => PrefixPattern::Neq,
+            MatchCmpOp::Gt => PrefixPattern::Gt,
+            MatchCmpOp::Ge => PrefixPattern::Ge,
+            MatchCmpOp::Lt => PrefixPattern::Lt,
+            MatchCmpOp::Le => PrefixPattern::Le,
+        }
+    }
+
     pub fn as_bytecode(&self) -> ByteCode {
         match self {
             PrefixPattern::Eq => ByteCode::Eq,
@@ -0,0 +1,595 @@
+use super::{
+    ast_node::AstNode, compiler::CelCompiler, grammar::*, source_location::SourceLocation,
+    source_range::SourceRange, tokens::FStringSegment,
+};
+use crate::{CelResult, Program};
+use std::ops::{Add, Div, Mul, Neg, Not, Rem, Sub};
+
+/// Programmatic constructor for CEL expressions.
+///
+/// Builds an `AstNode<Expr>` without going through source text. Parentheses are
+/// inserted automatically wherever an operand binds looser than its operator
+/// so the resulting tree always has the precedence the builder calls imply.
+///
+/// ```
+/// use rscel::{CelContext, BindContext, ExprBuilder};
+///
+/// let prog = ExprBuilder::ident("x")
+///     .gt(10)
+///     .and(ExprBuilder::ident("name").method("startsWith", ["ad"]))
+///     .compile()
+///     .unwrap();
+///
+/// assert_eq!(prog.source(), Some("x > 10 && name.startsWith(\"ad\")"));
+///
+/// let mut ctx = CelContext::new();
+/// let mut exec_ctx = BindContext::new();
+///
+/// ctx.add_program("main", prog);
+/// exec_ctx.bind_param("x", 11.into());
+/// exec_ctx.bind_param("name", "admin".into());
+///
+/// assert_eq!(ctx.exec("main", &exec_ctx).unwrap(), true.into());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExprBuilder {
+    expr: AstNode<Expr>,
+}
+
+impl ExprBuilder {
+    pub fn ident(name: &str) -> ExprBuilder {
+        Self::from_primary(Primary::Ident(Ident(name.to_owned())))
+    }
+
+    pub fn null() -> ExprBuilder {
+        Self::from_literal(LiteralsAndKeywords::NullLit)
+    }
+
+    pub fn int(val: i64) -> ExprBuilder {
+        Self::from_literal(LiteralsAndKeywords::IntegerLit(val))
+    }
+
+    pub fn uint(val: u64) -> ExprBuilder {
+        Self::from_literal(LiteralsAndKeywords::UnsignedLit(val))
+    }
+
+    pub fn float(val: f64) -> ExprBuilder {
+        Self::from_literal(LiteralsAndKeywords::FloatingLit(val))
+    }
+
+    /// Quantity literal, `5kg`.
+    pub fn quantity(val: f64, unit: &str) -> ExprBuilder {
+        Self::from_literal(LiteralsAndKeywords::QuantityLit(val, unit.to_owned()))
+    }
+
+    pub fn bool(val: bool) -> ExprBuilder {
+        Self::from_literal(LiteralsAndKeywords::BooleanLit(val))
+    }
+
+    pub fn string(val: &str) -> ExprBuilder {
+        Self::from_literal(LiteralsAndKeywords::StringLit(val.to_owned()))
+    }
+
+    pub fn bytes(val: &[u8]) -> ExprBuilder {
+        Self::from_literal(LiteralsAndKeywords::ByteStringLit(val.to_vec()))
+    }
+
+    /// Format string built from literal segments and expression source segments.
+    pub fn fstring(segments: Vec<FStringSegment>) -> ExprBuilder {
+        Self::from_literal(LiteralsAndKeywords::FStringList(segments))
+    }
+
+    pub fn list<T: Into<ExprBuilder>>(items: impl IntoIterator<Item = T>) -> ExprBuilder {
+        Self::from_primary(Primary::ListConstruction(node(ExprList {
+            exprs: items.into_iter().map(|i| i.into().expr).collect(),
+        })))
+    }
+
+    pub fn map<K: Into<ExprBuilder>, V: Into<ExprBuilder>>(
+        entries: impl IntoIterator<Item = (K, V)>,
+    ) -> ExprBuilder {
+        Self::from_primary(Primary::ObjectInit(node(ObjInits {
+            inits: entries
+                .into_iter()
+                .map(|(k, v)| {
+                    node(ObjInit {
+                        key: k.into().expr,
+                        value: v.into().expr,
+                    })
+                })
+                .collect(),
+        })))
+    }
+
+    /// Call to a free function, `name(args...)`.
+    pub fn call<T: Into<ExprBuilder>>(
+        name: &str,
+        args: impl IntoIterator<Item = T>,
+    ) -> ExprBuilder {
+        Self::from_member(Member {
+            primary: node(Primary::Ident(Ident(name.to_owned()))),
+            member: vec![call_prime(args)],
+        })
+    }
+
+    /// `condition ? true_clause : false_clause`
+    pub fn ternary(
+        condition: impl Into<ExprBuilder>,
+        true_clause: impl Into<ExprBuilder>,
+        false_clause: impl Into<ExprBuilder>,
+    ) -> ExprBuilder {
+        ExprBuilder {
+            expr: node(Expr::Ternary {
+                condition: Box::new(condition.into().into_or()),
+                true_clause: Box::new(true_clause.into().into_or()),
+                false_clause: Box::new(false_clause.into().expr),
+            }),
+        }
+    }
+
+    pub fn or(self, rhs: impl Into<ExprBuilder>) -> ExprBuilder {
+        Self::from_or(ConditionalOr::Binary {
+            lhs: Box::new(self.into_or()),
+            rhs: rhs.into().into_and(),
+        })
+    }
+
+    pub fn and(self, rhs: impl Into<ExprBuilder>) -> ExprBuilder {
+        Self::from_and(ConditionalAnd::Binary {
+            lhs: Box::new(self.into_and()),
+            rhs: rhs.into().into_relation(),
+        })
+    }
+
+    pub fn eq(self, rhs: impl Into<ExprBuilder>) -> ExprBuilder {
+        self.relation(Relop::Eq, rhs.into())
+    }
+
+    pub fn ne(self, rhs: impl Into<ExprBuilder>) -> ExprBuilder {
+        self.relation(Relop::Ne, rhs.into())
+    }
+
+    pub fn lt(self, rhs: impl Into<ExprBuilder>) -> ExprBuilder {
+        self.relation(Relop::Lt, rhs.into())
+    }
+
+    pub fn le(self, rhs: impl Into<ExprBuilder>) -> ExprBuilder {
+        self.relation(Relop::Le, rhs.into())
+    }
+
+    pub fn gt(self, rhs: impl Into<ExprBuilder>) -> ExprBuilder {
+        self.relation(Relop::Gt, rhs.into())
+    }
+
+    pub fn ge(self, rhs: impl Into<ExprBuilder>) -> ExprBuilder {
+        self.relation(Relop::Ge, rhs.into())
+    }
+
+    /// `self in rhs`
+    pub fn in_(self, rhs: impl Into<ExprBuilder>) -> ExprBuilder {
+        self.relation(Relop::In, rhs.into())
+    }
+
+    /// Field access, `self.name`.
+    pub fn member(self, name: &str) -> ExprBuilder {
+        let mut member = self.into_member();
+        member.node_mut().member.push(access_prime(name));
+
+        Self::from_member_node(member)
+    }
+
+    /// Method call, `self.name(args...)`.
+    pub fn method<T: Into<ExprBuilder>>(
+        self,
+        name: &str,
+        args: impl IntoIterator<Item = T>,
+    ) -> ExprBuilder {
+        let mut member = self.into_member();
+        member.node_mut().member.push(access_prime(name));
+        member.node_mut().member.push(call_prime(args));
+
+        Self::from_member_node(member)
+    }
+
+    /// Index access, `self[index]`.
+    pub fn index(self, index: impl Into<ExprBuilder>) -> ExprBuilder {
+        let mut member = self.into_member();
+        member
+            .node_mut()
+            .member
+            .push(node(MemberPrime::ArrayAccess {
+                access: index.into().expr,
+            }));
+
+        Self::from_member_node(member)
+    }
+
+    pub fn build(self) -> AstNode<Expr> {
+        self.expr
+    }
+
+    /// Compiles the built expression, see `CelCompiler::compile_ast`.
+    pub fn compile(self) -> CelResult<Program> {
+        CelCompiler::compile_ast(self.expr)
+    }
+
+    fn relation(self, op: Relop, rhs: ExprBuilder) -> ExprBuilder {
+        Self::from_relation(Relation::Binary {
+            lhs: Box::new(self.into_relation()),
+            op,
+            rhs: rhs.into_addition(),
+        })
+    }
+
+    fn addition(self, op: AddOp, rhs: ExprBuilder) -> ExprBuilder {
+        Self::from_addition(Addition::Binary {
+            lhs: Box::new(self.into_addition()),
+            op,
+            rhs: rhs.into_multiplication(),
+        })
+    }
+
+    fn multiplication(self, op: MultOp, rhs: ExprBuilder) -> ExprBuilder {
+        Self::from_multiplication(Multiplication::Binary {
+            lhs: Box::new(self.into_multiplication()),
+            op,
+            rhs: rhs.into_unary(),
+        })
+    }
+
+    fn from_or(or: ConditionalOr) -> ExprBuilder {
+        ExprBuilder {
+            expr: node(Expr::Unary(Box::new(node(or)))),
+        }
+    }
+
+    fn from_and(and: ConditionalAnd) -> ExprBuilder {
+        Self::from_or(ConditionalOr::from_unary(node(and)))
+    }
+
+    fn from_relation(relation: Relation) -> ExprBuilder {
+        Self::from_and(ConditionalAnd::from_unary(node(relation)))
+    }
+
+    fn from_addition(addition: Addition) -> ExprBuilder {
+        Self::from_relation(Relation::from_unary(node(addition)))
+    }
+
+    fn from_multiplication(mult: Multiplication) -> ExprBuilder {
+        Self::from_addition(Addition::from_unary(node(mult)))
+    }
+
+    fn from_unary(unary: Unary) -> ExprBuilder {
+        Self::from_multiplication(Multiplication::from_unary(node(unary)))
+    }
+
+    fn from_member(member: Member) -> ExprBuilder {
+        Self::from_member_node(node(member))
+    }
+
+    fn from_member_node(member: AstNode<Member>) -> ExprBuilder {
+        Self::from_unary(Unary::from_unary(member))
+    }
+
+    fn from_primary(primary: Primary) -> ExprBuilder {
+        Self::from_member(Member {
+            primary: node(primary),
+            member: Vec::new(),
+        })
+    }
+
+    fn from_literal(literal: LiteralsAndKeywords) -> ExprBuilder {
+        Self::from_primary(Primary::Literal(literal))
+    }
+
+    // The into_* functions walk down the precedence levels of the grammar as
+    // long as the expression is a plain pass through. If it is not the
+    // expression binds too loosely for the requested level and is wrapped in
+    // parentheses instead.
+
+    fn as_or(&self) -> Option<&AstNode<ConditionalOr>> {
+        match self.expr.node() {
+            Expr::Unary(or) => Some(or),
+            _ => None,
+        }
+    }
+
+    fn as_and(&self) -> Option<&AstNode<ConditionalAnd>> {
+        match self.as_or()?.node() {
+            ConditionalOr::Unary(and) => Some(and),
+            _ => None,
+        }
+    }
+
+    fn as_relation(&self) -> Option<&AstNode<Relation>> {
+        match self.as_and()?.node() {
+            ConditionalAnd::Unary(relation) => Some(relation),
+            _ => None,
+        }
+    }
+
+    fn as_addition(&self) -> Option<&AstNode<Addition>> {
+        match self.as_relation()?.node() {
+            Relation::Unary(addition) => Some(addition),
+            _ => None,
+        }
+    }
+
+    fn as_multiplication(&self) -> Option<&AstNode<Multiplication>> {
+        match self.as_addition()?.node() {
+            Addition::Unary(mult) => Some(mult),
+            _ => None,
+        }
+    }
+
+    fn as_unary(&self) -> Option<&AstNode<Unary>> {
+        match self.as_multiplication()?.node() {
+            Multiplication::Unary(unary) => Some(unary),
+            _ => None,
+        }
+    }
+
+    fn as_member(&self) -> Option<&AstNode<Member>> {
+        match self.as_unary()?.node() {
+            Unary::Member(member) => Some(member),
+            _ => None,
+        }
+    }
+
+    fn into_or(self) -> AstNode<ConditionalOr> {
+        match self.as_or() {
+            Some(or) => or.clone(),
+            None => lift(self.into_and()),
+        }
+    }
+
+    fn into_and(self) -> AstNode<ConditionalAnd> {
+        match self.as_and() {
+            Some(and) => and.clone(),
+            None => lift(self.into_relation()),
+        }
+    }
+
+    fn into_relation(self) -> AstNode<Relation> {
+        match self.as_relation() {
+            Some(relation) => relation.clone(),
+            None => lift(self.into_addition()),
+        }
+    }
+
+    fn into_addition(self) -> AstNode<Addition> {
+        match self.as_addition() {
+            Some(addition) => addition.clone(),
+            None => lift(self.into_multiplication()),
+        }
+    }
+
+    fn into_multiplication(self) -> AstNode<Multiplication> {
+        match self.as_multiplication() {
+            Some(mult) => mult.clone(),
+            None => lift(self.into_unary()),
+        }
+    }
+
+    fn into_unary(self) -> AstNode<Unary> {
+        match self.as_unary() {
+            Some(unary) => unary.clone(),
+            None => lift(self.into_member()),
+        }
+    }
+
+    fn into_member(self) -> AstNode<Member> {
+        match self.as_member() {
+            Some(member) => member.clone(),
+            None => node(Member {
+                primary: node(Primary::Parens(self.expr)),
+                member: Vec::new(),
+            }),
+        }
+    }
+}
+
+impl From<AstNode<Expr>> for ExprBuilder {
+    fn from(expr: AstNode<Expr>) -> Self {
+        ExprBuilder { expr }
+    }
+}
+
+impl From<ExprBuilder> for AstNode<Expr> {
+    fn from(builder: ExprBuilder) -> Self {
+        builder.expr
+    }
+}
+
+macro_rules! impl_binary_op {
+    ($trait:ident, $method:ident, $builder:ident, $op:expr) => {
+        impl<T: Into<ExprBuilder>> $trait<T> for ExprBuilder {
+            type Output = ExprBuilder;
+
+            fn $method(self, rhs: T) -> ExprBuilder {
+                self.$builder($op, rhs.into())
+            }
+        }
+    };
+}
+
+impl_binary_op!(Add, add, addition, AddOp::Add);
+impl_binary_op!(Sub, sub, addition, AddOp::Sub);
+impl_binary_op!(Mul, mul, multiplication, MultOp::Mult);
+impl_binary_op!(Div, div, multiplication, MultOp::Div);
+impl_binary_op!(Rem, rem, multiplication, MultOp::Mod);
+
+impl Not for ExprBuilder {
+    type Output = ExprBuilder;
+
+    fn not(self) -> ExprBuilder {
+        ExprBuilder::from_unary(Unary::NotMember {
+            nots: node(NotList::List {
+                tail: Box::new(node(NotList::EmptyList)),
+            }),
+            member: self.into_member(),
+        })
+    }
+}
+
+impl Neg for ExprBuilder {
+    type Output = ExprBuilder;
+
+    fn neg(self) -> ExprBuilder {
+        ExprBuilder::from_unary(Unary::NegMember {
+            negs: node(NegList::List {
+                tail: Box::new(node(NegList::EmptyList)),
+            }),
+            member: self.into_member(),
+        })
+    }
+}
+
+macro_rules! impl_from_literal {
+    ($t:ty, $ctor:ident, $conv:ty) => {
+        impl From<$t> for ExprBuilder {
+            fn from(val: $t) -> Self {
+                ExprBuilder::$ctor(val as $conv)
+            }
+        }
+    };
+}
+
+impl_from_literal!(i64, int, i64);
+impl_from_literal!(i32, int, i64);
+impl_from_literal!(u64, uint, u64);
+impl_from_literal!(u32, uint, u64);
+impl_from_literal!(f64, float, f64);
+impl_from_literal!(bool, bool, bool);
+
+impl From<&str> for ExprBuilder {
+    fn from(val: &str) -> Self {
+        ExprBuilder::string(val)
+    }
+}
+
+impl From<String> for ExprBuilder {
+    fn from(val: String) -> Self {
+        ExprBuilder::string(&val)
+    }
+}
+
+fn node<T>(val: T) -> AstNode<T> {
+    let loc = SourceLocation::new(0, 0);
+    AstNode::new(val, SourceRange::new(loc, loc))
+}
+
+fn lift<U: FromUnary>(inner: AstNode<U::InputType>) -> AstNode<U> {
+    let range = inner.range();
+    AstNode::new(U::from_unary(inner), range)
+}
+
+fn access_prime(name: &str) -> AstNode<MemberPrime> {
+    node(MemberPrime::MemberAccess {
+        ident: node(Ident(name.to_owned())),
+    })
+}
+
+fn call_prime<T: Into<ExprBuilder>>(args: impl IntoIterator<Item = T>) -> AstNode<MemberPrime> {
+    let mut exprs: Vec<_> = args.into_iter().map(|a| a.into().expr).collect();
+    // match the parser, which stores call arguments in reverse order
+    exprs.reverse();
+
+    node(MemberPrime::Call {
+        call: node(ExprList { exprs }),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::ExprBuilder;
+    use crate::{BindContext, CelContext, CelValue, Program};
+
+    fn eval(builder: ExprBuilder, expected_source: &str, expected: CelValue) {
+        let prog = builder.compile().unwrap();
+        assert_eq!(prog.source(), Some(expected_source));
+
+        let mut ctx = CelContext::new();
+        let mut exec = BindContext::new();
+        ctx.add_program("main", prog);
+        exec.bind_param("x", 4.into());
+
+        assert_eq!(ctx.exec("main", &exec).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_precedence() {
+        eval((ExprBuilder::ident("x") + 2) * 3, "(x + 2) * 3", 18.into());
+        eval(ExprBuilder::ident("x") * 3 + 2, "x * 3 + 2", 14.into());
+        eval(
+            ExprBuilder::int(1) - (ExprBuilder::ident("x") - 1),
+            "1 - (x - 1)",
+            (-2).into(),
+        );
+        eval(
+            (!ExprBuilder::ident("x").gt(3)).or(true),
+            "!(x > 3) || true",
+            true.into(),
+        );
+    }
+
+    #[test]
+    fn test_negative_literals() {
+        eval(
+            ExprBuilder::int(-3) + ExprBuilder::ident("x"),
+            "(-3) + x",
+            1.into(),
+        );
+        eval(
+            ExprBuilder::int(i64::MIN) + 1,
+            "(-9223372036854775808) + 1",
+            (i64::MIN + 1).into(),
+        );
+    }
+
+    #[test]
+    fn test_members() {
+        eval(
+            ExprBuilder::list([1, 2, 3]).index(ExprBuilder::ident("x") - 3)
+                + ExprBuilder::call("int", [ExprBuilder::call("size", ["abc"])]),
+            "[1, 2, 3][x - 3] + int(size(\"abc\"))",
+            5.into(),
+        );
+        eval(
+            -ExprBuilder::map([("a", ExprBuilder::ident("x"))]).member("a"),
+            "-{\"a\": x}.a",
+            (-4).into(),
+        );
+        eval(
+            ExprBuilder::string("foo").method("contains", ["o"]),
+            "\"foo\".contains(\"o\")",
+            true.into(),
+        );
+        eval(
+            ExprBuilder::ternary(
+                ExprBuilder::ident("x").in_(ExprBuilder::list([4])),
+                "yes",
+                "no",
+            ),
+            "x in [4] ? \"yes\" : \"no\"",
+            "yes".into(),
+        );
+    }
+
+    #[test]
+    fn test_synthesized_source() {
+        let builder = ExprBuilder::ident("x")
+            .member("y")
+            .le(-ExprBuilder::float(1.5))
+            .and(ExprBuilder::bytes(b"a\x00").ne(ExprBuilder::null()));
+        let prog = builder.clone().compile().unwrap();
+
+        assert_eq!(prog.source(), Some("x.y <= -1.5 && b\"a\\x00\" != null"));
+
+        let reparsed = Program::from_source(prog.source().unwrap()).unwrap();
+        assert_eq!(prog.bytecode(), reparsed.bytecode());
+
+        // the built ast is compiled as is rather than reparsed from the source
+        assert_eq!(prog.ast(), Some(&builder.build()));
+    }
+}
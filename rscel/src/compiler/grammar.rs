@@ -1,4 +1,4 @@
-use super::{ast_node::AstNode, compiled_prog::CompiledProg, tokens::FStringSegment};
+use super::{ast_node::AstNode, tokens::FStringSegment};
 use serde::{Deserialize, Serialize};
 
 pub trait FromUnary {
@@ -8,13 +8,10 @@ pub trait FromUnary {
 }
 
 #[inline]
-pub fn into_unary<T, U: FromUnary<InputType = T>>(
-    v: (CompiledProg, AstNode<T>),
-) -> (CompiledProg, AstNode<U>) {
-    let (prog, ast) = v;
+pub fn into_unary<T, U: FromUnary<InputType = T>>(ast: AstNode<T>) -> AstNode<U> {
     let loc = ast.range();
 
-    (prog, AstNode::new(U::from_unary(ast), loc))
+    AstNode::new(U::from_unary(ast), loc)
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -83,12 +80,28 @@ impl MatchTypePattern {
             "bytes" => MatchTypePattern::Bytes,
             "list" => MatchTypePattern::List,
             "object" => MatchTypePattern::Object,
-            "null" => MatchTypePattern::Null,
+            "null" | "null_type" => MatchTypePattern::Null,
             "timestamp" => MatchTypePattern::Timestamp,
             "duration" => MatchTypePattern::Duration,
             _ => panic!("Unknown type"),
         }
     }
+
+    pub fn as_type_str(&self) -> &'static str {
+        match self {
+            MatchTypePattern::Int => "int",
+            MatchTypePattern::Uint => "uint",
+            MatchTypePattern::Float => "double",
+            MatchTypePattern::String => "string",
+            MatchTypePattern::Bool => "bool",
+            MatchTypePattern::Bytes => "bytes",
+            MatchTypePattern::List => "list",
+            MatchTypePattern::Object => "object",
+            MatchTypePattern::Null => "null_type",
+            MatchTypePattern::Timestamp => "timestamp",
+            MatchTypePattern::Duration => "duration",
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -303,6 +316,7 @@ pub enum LiteralsAndKeywords {
     IntegerLit(i64),
     UnsignedLit(u64),
     FloatingLit(f64),
+    QuantityLit(f64, String),
     FStringList(Vec<FStringSegment>),
     StringLit(String),
     ByteStringLit(Vec<u8>),
@@ -1,6 +1,8 @@
 pub mod ast_node;
+pub mod ast_tokenizer;
 pub mod compiled_prog;
 pub mod compiler;
+pub mod expr_builder;
 pub mod grammar;
 pub mod source_location;
 pub mod source_range;
@@ -52,6 +52,20 @@ impl<'l> StringScanner<'l> {
         }
     }
 
+    /// The identifier-like word starting at the next character, without consuming it.
+    pub fn peek_word(&mut self) -> String {
+        match self.peek() {
+            Some(c) if c.is_ascii_alphabetic() => std::iter::once(c)
+                .chain(
+                    self.iterator
+                        .clone()
+                        .take_while(|c| c.is_ascii_alphanumeric() || *c == '_'),
+                )
+                .collect(),
+            _ => String::new(),
+        }
+    }
+
     pub fn location(&self) -> SourceLocation {
         SourceLocation::new(self.line, self.column)
     }
@@ -94,4 +108,18 @@ mod test {
         let _ = scanner.next().unwrap();
         assert_eq!(scanner.location(), SourceLocation::new(0, 1));
     }
+
+    #[test]
+    fn string_scanner_peek_word() {
+        let mut scanner = StringScanner::from_input("kg_2+ g");
+
+        assert_eq!(scanner.peek_word(), "kg_2");
+        assert_eq!(scanner.next(), Some('k'));
+        assert_eq!(scanner.peek_word(), "g_2");
+
+        for _ in 0..3 {
+            scanner.next();
+        }
+        assert_eq!(scanner.peek_word(), "");
+    }
 }
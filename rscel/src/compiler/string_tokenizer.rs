@@ -1,3 +1,5 @@
+use crate::context::default_funcs::uom::Unit;
+
 use super::{
     source_location::SourceLocation,
     source_range::SourceRange,
@@ -7,10 +9,17 @@ use super::{
     tokens::{FStringSegment, Token},
 };
 
+enum QuoteStyle {
+    Empty,
+    Single,
+    Triple,
+}
+
 pub struct StringTokenizer<'l> {
     scanner: StringScanner<'l>,
 
     current: Option<TokenWithLoc>,
+    comments: Vec<TokenWithLoc>,
 
     eof: bool,
 }
@@ -20,6 +29,7 @@ impl<'l> StringTokenizer<'l> {
         StringTokenizer {
             scanner: StringScanner::from_input(input),
             current: None,
+            comments: Vec::new(),
             eof: false,
         }
     }
@@ -39,6 +49,11 @@ impl<'l> StringTokenizer<'l> {
                     token_start = self.location();
                     curr_char = self.scanner.next();
                 }
+                Some('/') if self.at_comment_start() => {
+                    self.parse_comment(token_start)?;
+                    token_start = self.location();
+                    curr_char = self.scanner.next();
+                }
                 _ => break 'outer,
             };
         }
@@ -182,10 +197,103 @@ impl<'l> StringTokenizer<'l> {
         res.map(|o| o.map(|t| TokenWithLoc::new(t, SourceRange::new(token_start, self.location()))))
     }
 
+    fn at_comment_start(&mut self) -> bool {
+        match self.scanner.peek() {
+            Some('/') => true,
+            Some('*') => cfg!(feature = "block_comments"),
+            _ => false,
+        }
+    }
+
+    /// Consumes a comment, the leading '/' has already been consumed.
+    fn parse_comment(&mut self, start: SourceLocation) -> Result<(), SyntaxError> {
+        let mut text = String::from("/");
+
+        match self.scanner.next() {
+            Some('/') => {
+                text.push('/');
+                while let Some(c) = self.scanner.peek() {
+                    if c == '\n' {
+                        break;
+                    }
+                    text.push(c);
+                    self.scanner.next();
+                }
+            }
+            Some('*') => {
+                text.push('*');
+                loop {
+                    match self.scanner.next() {
+                        Some('*') if self.scanner.peek() == Some('/') => {
+                            self.scanner.next();
+                            text.push_str("*/");
+                            break;
+                        }
+                        Some(c) => text.push(c),
+                        None => {
+                            return Err(SyntaxError::from_location(start)
+                                .with_message("Unterminated block comment".to_owned()))
+                        }
+                    }
+                }
+            }
+            _ => unreachable!("comments start with // or /*"),
+        }
+
+        self.comments.push(TokenWithLoc::new(
+            Token::Comment(text),
+            SourceRange::new(start, self.location()),
+        ));
+
+        Ok(())
+    }
+
+    /// Determines the kind of literal after its opening quote was consumed.
+    fn quote_style(&mut self, starting: char) -> QuoteStyle {
+        if self.scanner.peek() != Some(starting) {
+            return QuoteStyle::Single;
+        }
+        self.scanner.next();
+
+        if self.scanner.peek() == Some(starting) {
+            self.scanner.next();
+            QuoteStyle::Triple
+        } else {
+            QuoteStyle::Empty
+        }
+    }
+
+    /// Called after reading a quote matching the opening quote. Returns 0 when
+    /// the literal is closed, otherwise the number of quotes that are part of
+    /// the literal's content.
+    fn closing_quote(&mut self, starting: char, triple: bool) -> usize {
+        if !triple {
+            return 0;
+        }
+
+        if self.scanner.peek() != Some(starting) {
+            return 1;
+        }
+        self.scanner.next();
+
+        if self.scanner.peek() != Some(starting) {
+            return 2;
+        }
+        self.scanner.next();
+
+        0
+    }
+
     fn parse_bytes_literal(&mut self, starting: char) -> Result<Option<Token>, SyntaxError> {
         let mut buf = [0u8; 4];
         let mut working = Vec::new();
 
+        let triple = match self.quote_style(starting) {
+            QuoteStyle::Empty => return Ok(Some(Token::ByteStringLit(working.into()))),
+            QuoteStyle::Single => false,
+            QuoteStyle::Triple => true,
+        };
+
         'outer: loop {
             let curr = if let Some(curr) = self.scanner.next() {
                 curr
@@ -194,7 +302,10 @@ impl<'l> StringTokenizer<'l> {
             };
 
             if curr == starting {
-                break 'outer;
+                match self.closing_quote(starting, triple) {
+                    0 => break 'outer,
+                    n => working.extend(std::iter::repeat_n(starting as u8, n)),
+                }
             } else if curr == '\\' {
                 let escaped = if let Some(curr) = self.scanner.next() {
                     curr
@@ -260,6 +371,12 @@ impl<'l> StringTokenizer<'l> {
         let mut working = String::new();
         let mut segments = Vec::new();
 
+        let triple = match self.quote_style(starting) {
+            QuoteStyle::Empty => return Ok(Some(Token::StringLit(working))),
+            QuoteStyle::Single => false,
+            QuoteStyle::Triple => true,
+        };
+
         'outer: loop {
             let curr = if let Some(curr) = self.scanner.next() {
                 curr
@@ -268,7 +385,10 @@ impl<'l> StringTokenizer<'l> {
             };
 
             if curr == starting {
-                break 'outer;
+                match self.closing_quote(starting, triple) {
+                    0 => break 'outer,
+                    n => working.extend(std::iter::repeat_n(starting, n)),
+                }
             } else if curr == '\\' && !is_raw {
                 let escaped = if let Some(curr) = self.scanner.next() {
                     curr
@@ -341,33 +461,18 @@ impl<'l> StringTokenizer<'l> {
                                 .with_message("Empty format specifier".to_string()));
                         }
 
-                        working.push(c);
-                        let mut bracket_count = 1;
-                        while bracket_count > 0 {
-                            if let Some(c) = self.scanner.next() {
-                                match c {
-                                    '}' => {
-                                        bracket_count -= 1;
-                                        if bracket_count > 0 {
-                                            working.push('}');
-                                        }
-                                    }
-                                    '{' => {
-                                        bracket_count += 1;
-                                        working.push('{');
-                                    }
-                                    other => working.push(other),
-                                }
-                            } else {
-                                return Err(SyntaxError::from_location(self.scanner.location()));
-                            }
-                        }
+                        let spec = self.parse_format_expr(c, &mut working)?;
 
-                        if working.is_empty() {
+                        if working.trim().is_empty() {
                             return Err(SyntaxError::from_location(self.scanner.location()));
                         }
 
-                        segments.push(FStringSegment::Expr(working));
+                        match spec {
+                            Some(spec) if !spec.is_empty() => {
+                                segments.push(FStringSegment::FmtExpr(working, spec))
+                            }
+                            _ => segments.push(FStringSegment::Expr(working)),
+                        }
                         working = String::new();
                     }
                 }
@@ -399,6 +504,83 @@ impl<'l> StringTokenizer<'l> {
         }
     }
 
+    /// Collects the expression of a format string placeholder into `working`
+    /// and returns the format spec if one follows a top level ':'. A ':' that
+    /// closes a ternary belongs to the expression.
+    fn parse_format_expr(
+        &mut self,
+        first: char,
+        working: &mut String,
+    ) -> Result<Option<String>, SyntaxError> {
+        let mut bracket_count = 1;
+        let mut nesting = 0usize;
+        let mut pending_ternary = 0;
+        let mut quote: Option<char> = None;
+        let mut next = Some(first);
+
+        while let Some(c) = next {
+            if let Some(q) = quote {
+                working.push(c);
+                if c == '\\' {
+                    if let Some(escaped) = self.scanner.next() {
+                        working.push(escaped);
+                    }
+                } else if c == q {
+                    quote = None;
+                }
+            } else {
+                match c {
+                    '}' if bracket_count == 1 => return Ok(None),
+                    ':' if bracket_count == 1 && nesting == 0 && pending_ternary == 0 => {
+                        let mut spec = String::new();
+                        loop {
+                            match self.scanner.next() {
+                                Some('}') => return Ok(Some(spec)),
+                                Some(c) => spec.push(c),
+                                None => {
+                                    return Err(SyntaxError::from_location(self.scanner.location()))
+                                }
+                            }
+                        }
+                    }
+                    ':' if bracket_count == 1 && nesting == 0 => {
+                        pending_ternary -= 1;
+                        working.push(c);
+                    }
+                    '?' if bracket_count == 1 && nesting == 0 => {
+                        pending_ternary += 1;
+                        working.push(c);
+                    }
+                    '{' => {
+                        bracket_count += 1;
+                        working.push(c);
+                    }
+                    '}' => {
+                        bracket_count -= 1;
+                        working.push(c);
+                    }
+                    '(' | '[' => {
+                        nesting += 1;
+                        working.push(c);
+                    }
+                    ')' | ']' => {
+                        nesting = nesting.saturating_sub(1);
+                        working.push(c);
+                    }
+                    '\'' | '"' => {
+                        quote = Some(c);
+                        working.push(c);
+                    }
+                    other => working.push(other),
+                }
+            }
+
+            next = self.scanner.next();
+        }
+
+        Err(SyntaxError::from_location(self.scanner.location()))
+    }
+
     fn parse_keywords_or_ident(
         &mut self,
         starting: &str,
@@ -497,6 +679,23 @@ impl<'l> StringTokenizer<'l> {
             }
         }
 
+        // A unit right after a decimal number makes it a quantity, like `5kg`
+        if base == 10 && !is_unsigned {
+            let unit = self.scanner.peek_word();
+
+            if unit != "in" && Unit::from_str(&unit).is_some() {
+                for _ in unit.chars() {
+                    self.scanner.next();
+                }
+
+                return match working.parse::<f64>() {
+                    Ok(v) => Ok(Some(Token::QuantityLit(v, unit))),
+                    Err(_) => Err(SyntaxError::from_location(self.scanner.location())
+                        .with_message(format!("Failed to parse quantity {}{}", working, unit))),
+                };
+            }
+        }
+
         let orig = working.clone();
         let fixedup_str = match base {
             10 => &working,
@@ -589,6 +788,10 @@ impl Tokenizer for StringTokenizer<'_> {
     fn location(&self) -> SourceLocation {
         self.scanner.location()
     }
+
+    fn comments(&self) -> &[TokenWithLoc] {
+        &self.comments
+    }
 }
 
 #[cfg(test)]
@@ -648,4 +851,62 @@ mod test {
             }
         );
     }
+
+    #[test]
+    fn line_comments() {
+        let src = "foo // first\n// second\n/ 3";
+
+        let mut tokenizer = StringTokenizer::with_input(src);
+        let tokens: Vec<_> = std::iter::from_fn(|| tokenizer.next().unwrap())
+            .map(|t| t.into_token())
+            .collect();
+
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Ident("foo".to_owned()),
+                Token::Divide,
+                Token::IntLit(3)
+            ]
+        );
+        assert_eq!(
+            tokenizer.comments(),
+            &[
+                TokenWithLoc {
+                    token: Token::Comment("// first".to_owned()),
+                    loc: SourceRange::new(SourceLocation::new(0, 4), SourceLocation::new(0, 12))
+                },
+                TokenWithLoc {
+                    token: Token::Comment("// second".to_owned()),
+                    loc: SourceRange::new(SourceLocation::new(1, 0), SourceLocation::new(1, 9))
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn block_comments() {
+        let mut tokenizer = StringTokenizer::with_input("4 /* four\n */ * 2");
+        let tokens: Vec<_> = std::iter::from_fn(|| tokenizer.next().unwrap())
+            .map(|t| t.into_token())
+            .collect();
+
+        if cfg!(feature = "block_comments") {
+            assert_eq!(
+                tokens,
+                vec![Token::IntLit(4), Token::Multiply, Token::IntLit(2)]
+            );
+            assert_eq!(
+                tokenizer.comments()[0].token,
+                Token::Comment("/* four\n */".to_owned())
+            );
+
+            let mut tokenizer = StringTokenizer::with_input("4 /* four");
+            assert!(tokenizer.next().is_ok());
+            assert!(tokenizer.next().is_err());
+        } else {
+            assert_eq!(tokens[1], Token::Divide);
+            assert_eq!(tokens[2], Token::Multiply);
+        }
+    }
 }
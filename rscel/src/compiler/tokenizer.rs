@@ -17,6 +17,11 @@ pub trait Tokenizer {
 
     fn source<'a>(&'a self) -> &'a str;
     fn location(&self) -> SourceLocation;
+
+    /// Comments skipped over so far, as `Token::Comment` tokens.
+    fn comments(&self) -> &[TokenWithLoc] {
+        &[]
+    }
 }
 
 impl TokenWithLoc {
@@ -37,16 +37,20 @@ pub enum Token {
     IntLit(u64),                     // [-+]?[0-9]+
     UIntLit(u64),                    // [0-9]+u
     FloatLit(f64),                   // [-+]?[0-9]*\.?[0-9]+([eE][-+]?[0-9]+)?
-    StringLit(String),               // r?('|")[^\n]*('|")
-    FStringLit(Vec<FStringSegment>), // f'.*'
-    ByteStringLit(CelBytes),         // b('|")[^\n]('|")
+    QuantityLit(f64, String),        // [0-9]*\.?[0-9]+([eE][-+]?[0-9]+)?[A-Za-z][_A-Za-z0-9]*
+    StringLit(String),               // r?('|")[^\n]*('|") | r?('''|""").*('''|""")
+    FStringLit(Vec<FStringSegment>), // f('|"|'''|""").*('|"|'''|""")
+    ByteStringLit(CelBytes),         // b('|")[^\n]*('|") | b('''|""").*('''|""")
     Ident(String),                   // [_A-Za-z][_A-Za-z0-9]*
+    Comment(String),                 // //.* | /\*.*\*/
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum FStringSegment {
     Lit(String),
     Expr(String),
+    /// An expression followed by a format spec, `{expr:spec}`
+    FmtExpr(String, String),
 }
 
 pub trait AsToken {
@@ -1,11 +1,14 @@
 use std::collections::HashMap;
 
+use chrono::{DateTime, Utc};
 #[cfg(feature = "protobuf")]
 use protobuf::MessageDyn;
 use serde_json::Value;
 
 use crate::{interp::Interpreter, types::CelByteCode, CelError, CelResult, CelValue};
 
+use super::clock::Clock;
+use super::default_funcs::time_funcs::helpers::checked;
 use super::default_macros::{load_compile_macros, load_default_macros};
 use super::{default_funcs::load_default_funcs, type_funcs::load_default_types};
 
@@ -60,6 +63,8 @@ pub struct BindContext<'a> {
     funcs: HashMap<String, &'a RsCelFunction>,
     macros: HashMap<String, &'a RsCelMacro>,
     types: HashMap<String, CelValue>,
+    clock: Option<Clock>,
+    deterministic: bool,
 }
 
 impl<'a> BindContext<'a> {
@@ -70,6 +75,8 @@ impl<'a> BindContext<'a> {
             funcs: HashMap::new(),
             macros: HashMap::new(),
             types: HashMap::new(),
+            clock: None,
+            deterministic: false,
         };
 
         load_default_macros(&mut ctx);
@@ -84,6 +91,9 @@ impl<'a> BindContext<'a> {
             funcs: HashMap::new(),
             macros: HashMap::new(),
             types: HashMap::new(),
+            clock: None,
+            // Constant folding must never capture the time a program was compiled at
+            deterministic: true,
         };
 
         load_compile_macros(&mut ctx);
@@ -142,6 +152,40 @@ impl<'a> BindContext<'a> {
         Some(*self.macros.get(name)?)
     }
 
+    /// Set the clock `now()` and `timestamp()` read the current time from.
+    pub fn set_clock(&mut self, clock: Clock) {
+        self.clock = Some(clock);
+    }
+
+    pub fn clock(&self) -> Option<&Clock> {
+        self.clock.as_ref()
+    }
+
+    /// When deterministic, built-ins that read the current time fail unless a fixed
+    /// or custom clock is set, since both the fallback and `Clock::Offset` read the
+    /// system clock.
+    pub fn set_deterministic(&mut self, deterministic: bool) {
+        self.deterministic = deterministic;
+    }
+
+    pub fn is_deterministic(&self) -> bool {
+        self.deterministic
+    }
+
+    /// The current time according to the clock.
+    pub fn now(&self) -> CelResult<DateTime<Utc>> {
+        match &self.clock {
+            Some(Clock::Offset(_)) if self.deterministic => Err(CelError::runtime(
+                "An offset clock reads the system clock and is unavailable in deterministic evaluation",
+            )),
+            Some(clock) => checked(clock.now()),
+            None if self.deterministic => Err(CelError::runtime(
+                "The current time is unavailable without a clock in deterministic evaluation",
+            )),
+            None => Ok(Utc::now()),
+        }
+    }
+
     pub fn is_bound(&self, name: &str) -> bool {
         self.params.contains_key(name)
             || self.funcs.contains_key(name)
@@ -0,0 +1,42 @@
+use std::{fmt, rc::Rc};
+
+use chrono::{DateTime, Duration, Utc};
+
+/// The source of the current time for `now()` and `timestamp()`.
+///
+/// Without a clock a `BindContext` reads the system clock, or fails when it is
+/// deterministic.
+#[derive(Clone)]
+pub enum Clock {
+    /// Always the given instant, for tests and replaying past evaluations.
+    Fixed(DateTime<Utc>),
+    /// The system clock shifted by the given duration, rejected in deterministic
+    /// evaluation.
+    Offset(Duration),
+    /// Whatever the provider returns each time the time is read.
+    Custom(Rc<dyn Fn() -> DateTime<Utc>>),
+}
+
+impl Clock {
+    pub fn custom(provider: impl Fn() -> DateTime<Utc> + 'static) -> Clock {
+        Clock::Custom(Rc::new(provider))
+    }
+
+    pub fn now(&self) -> DateTime<Utc> {
+        match self {
+            Clock::Fixed(ts) => *ts,
+            Clock::Offset(offset) => Utc::now() + *offset,
+            Clock::Custom(provider) => provider(),
+        }
+    }
+}
+
+impl fmt::Debug for Clock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Clock::Fixed(ts) => f.debug_tuple("Fixed").field(ts).finish(),
+            Clock::Offset(offset) => f.debug_tuple("Offset").field(offset).finish(),
+            Clock::Custom(_) => f.write_str("Custom"),
+        }
+    }
+}
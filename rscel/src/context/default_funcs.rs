@@ -1,18 +1,36 @@
 use super::bind_context::RsCelFunction;
 use crate::{BindContext, CelError, CelValue};
 
+mod encoders;
+mod lists;
 mod math;
+mod optional;
+mod quantity;
+mod reverse;
+mod sets;
 mod size;
 mod sort;
 mod string;
-mod time_funcs;
-mod uom;
+pub(crate) mod time_funcs;
+pub(crate) mod uom;
 
 const DEFAULT_FUNCS: &[(&str, &'static RsCelFunction)] = &[
+    ("charAt", &string::char_at::char_at),
     ("contains", &string::contains::contains),
     ("containsI", &string::contains::contains_i),
     ("size", &size::size),
     ("sort", &sort::sort),
+    ("slice", &lists::slice::slice),
+    ("flatten", &lists::flatten::flatten),
+    ("distinct", &lists::distinct::distinct),
+    ("first", &lists::first::first),
+    ("last", &lists::first::last),
+    ("lists.range", &lists::range::range),
+    ("optional.of", &optional::optional_of),
+    ("optional.none", &optional::optional_none),
+    ("hasValue", &optional::has_value),
+    ("value", &optional::value),
+    ("orValue", &optional::or_value),
     ("startsWith", &string::starts_with::starts_with),
     ("endsWith", &string::ends_with::ends_with),
     ("startsWithI", &string::starts_with::starts_with_i),
@@ -26,8 +44,17 @@ const DEFAULT_FUNCS: &[(&str, &'static RsCelFunction)] = &[
     ("matchReplace", &string::match_replace::match_replace),
     ("toLower", &string::to_lower_impl),
     ("toUpper", &string::to_upper_impl),
+    ("format", &time_funcs::zoned::format),
+    ("indexOf", &string::index_of::index_of),
+    ("join", &string::join::join),
+    ("lastIndexOf", &string::index_of::last_index_of),
+    ("lowerAscii", &string::lower_ascii_impl),
+    ("upperAscii", &string::upper_ascii_impl),
     ("remove", &string::remove::remove),
     ("replace", &string::replace::replace),
+    ("reverse", &reverse::reverse),
+    ("strings.quote", &string::quote::quote),
+    ("substring", &string::substring::substring),
     ("rsplit", &string::split::rsplit),
     ("split", &string::split::split),
     ("splitAt", &string::split::split_at),
@@ -54,33 +81,85 @@ const DEFAULT_FUNCS: &[(&str, &'static RsCelFunction)] = &[
     ("ceil", &math::ceil::ceil),
     ("floor", &math::floor::floor),
     ("round", &math::round::round),
+    ("math.greatest", &math::greatest::greatest),
+    ("math.least", &math::greatest::least),
+    ("math.bitAnd", &math::bit::bit_and),
+    ("math.bitOr", &math::bit::bit_or),
+    ("math.bitXor", &math::bit::bit_xor),
+    ("math.bitNot", &math::bit::bit_not),
+    ("math.bitShiftLeft", &math::bit::bit_shift_left),
+    ("math.bitShiftRight", &math::bit::bit_shift_right),
+    ("math.isNaN", &math::classify::is_nan),
+    ("math.isInf", &math::classify::is_inf),
+    ("math.isFinite", &math::classify::is_finite),
+    ("math.abs", &math::abs::abs),
+    ("math.sqrt", &math::sqrt::sqrt),
+    ("math.ceil", &math::ceil::math_ceil),
+    ("math.floor", &math::floor::math_floor),
+    ("math.round", &math::round::math_round),
+    ("math.trunc", &math::trunc::trunc),
+    ("math.sign", &math::sign::sign),
     ("min", &min_impl),
     ("max", &max_impl),
-    ("getDate", &time_funcs::get_date::get_date),
+    ("getDate", &time_funcs::zoned::get_date),
+    ("getDayOfMonth", &time_funcs::zoned::get_day_of_month),
+    ("getDayOfWeek", &time_funcs::zoned::get_day_of_week),
+    ("getDayOfYear", &time_funcs::zoned::get_day_of_year),
+    ("getFullYear", &time_funcs::zoned::get_full_year),
+    ("getHours", &time_funcs::zoned::get_hours),
+    ("getMilliseconds", &time_funcs::zoned::get_milliseconds),
+    ("getMinutes", &time_funcs::zoned::get_minutes),
+    ("getMonth", &time_funcs::zoned::get_month),
+    ("getSeconds", &time_funcs::zoned::get_seconds),
+    ("addMonths", &time_funcs::zoned::add_months),
+    ("startOfWeek", &time_funcs::zoned::start_of_week),
+    ("toEpochSeconds", &time_funcs::zoned::to_epoch_seconds),
+    ("truncate", &time_funcs::zoned::truncate),
+    ("inZone", &time_funcs::zoned::in_zone),
+    ("toUTC", &time_funcs::zoned::to_utc),
+    ("getZone", &time_funcs::zoned::get_zone),
+    ("isWeekend", &time_funcs::zoned::is_weekend),
     (
-        "getDayOfMonth",
-        &time_funcs::get_day_of_month::get_day_of_month,
+        "businessDaysBetween",
+        &time_funcs::zoned::business_days_between,
     ),
+    ("nextDayOfWeek", &time_funcs::zoned::next_day_of_week),
+    ("endOfMonth", &time_funcs::zoned::end_of_month),
+    ("toISO8601", &time_funcs::to_iso8601::to_iso8601),
+    ("getDaysPart", &time_funcs::duration_parts::get_days_part),
+    ("getHoursPart", &time_funcs::duration_parts::get_hours_part),
     (
-        "getDayOfWeek",
-        &time_funcs::get_day_of_week::get_day_of_week,
+        "getMinutesPart",
+        &time_funcs::duration_parts::get_minutes_part,
     ),
     (
-        "getDayOfYear",
-        &time_funcs::get_day_of_year::get_day_of_year,
+        "getSecondsPart",
+        &time_funcs::duration_parts::get_seconds_part,
     ),
-    ("getFullYear", &time_funcs::get_full_year::get_full_year),
-    ("getHours", &time_funcs::get_hours::get_hours),
     (
-        "getMilliseconds",
-        &time_funcs::get_milliseconds::get_milliseconds,
+        "getMillisecondsPart",
+        &time_funcs::duration_parts::get_milliseconds_part,
+    ),
+    (
+        "getNanosecondsPart",
+        &time_funcs::duration_parts::get_nanoseconds_part,
     ),
-    ("getMinutes", &time_funcs::get_minutes::get_minutes),
-    ("getMonth", &time_funcs::get_month::get_month),
-    ("getSeconds", &time_funcs::get_seconds::get_seconds),
-    ("now", &now_impl),
     ("zip", &zip_impl),
     ("uomConvert", &uom::uom_convert),
+    ("uomUnits", &uom::uom_units),
+    ("quantity", &quantity::quantity),
+    ("to", &quantity::to),
+    ("magnitude", &quantity::magnitude),
+    ("unit", &quantity::unit),
+    ("base64.encode", &encoders::base64_encode),
+    ("base64.decode", &encoders::base64_decode),
+    ("hex.encode", &encoders::hex_encode),
+    ("hex.decode", &encoders::hex_decode),
+    ("url.encode", &encoders::url_encode),
+    ("url.decode", &encoders::url_decode),
+    ("sets.contains", &sets::sets_contains),
+    ("sets.equivalent", &sets::sets_equivalent),
+    ("sets.intersects", &sets::sets_intersects),
 ];
 
 pub fn load_default_funcs(exec_ctx: &mut BindContext) {
@@ -167,11 +246,3 @@ fn zip_impl(_this: CelValue, args: Vec<CelValue>) -> CelValue {
 
     ret_val.into()
 }
-
-fn now_impl(_this: CelValue, args: Vec<CelValue>) -> CelValue {
-    if !args.is_empty() {
-        return CelValue::from_err(CelError::argument("now() expects no arguments"));
-    }
-
-    CelValue::from_timestamp(chrono::Utc::now())
-}
@@ -0,0 +1,89 @@
+use crate::macros::dispatch;
+
+pub use base64_decode::dispatch as base64_decode;
+pub use base64_encode::dispatch as base64_encode;
+pub use hex_decode::dispatch as hex_decode;
+pub use hex_encode::dispatch as hex_encode;
+pub use url_decode::dispatch as url_decode;
+pub use url_encode::dispatch as url_encode;
+
+#[dispatch]
+mod base64_encode {
+    use crate::{types::CelBytes, CelValue};
+    use base64::{engine::general_purpose::STANDARD, Engine};
+
+    fn encode(arg: CelBytes) -> String {
+        STANDARD.encode(arg.as_slice())
+    }
+}
+
+#[dispatch]
+mod base64_decode {
+    use crate::{types::CelBytes, CelError, CelResult, CelValue};
+    use base64::{
+        alphabet,
+        engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig},
+        Engine,
+    };
+
+    // Padding is optional when decoding
+    const ENGINE: GeneralPurpose = GeneralPurpose::new(
+        &alphabet::STANDARD,
+        GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+    );
+
+    fn decode(arg: String) -> CelResult<CelBytes> {
+        ENGINE
+            .decode(arg)
+            .map(CelBytes::from_vec)
+            .map_err(|e| CelError::value(&format!("invalid base64: {}", e)))
+    }
+}
+
+#[dispatch]
+mod hex_encode {
+    use crate::{types::CelBytes, CelValue};
+
+    fn encode(arg: CelBytes) -> String {
+        hex::encode(arg.as_slice())
+    }
+
+    fn encode(arg: String) -> String {
+        hex::encode(arg)
+    }
+}
+
+#[dispatch]
+mod hex_decode {
+    use crate::{types::CelBytes, CelError, CelResult, CelValue};
+
+    fn decode(arg: String) -> CelResult<CelBytes> {
+        hex::decode(arg)
+            .map(CelBytes::from_vec)
+            .map_err(|e| CelError::value(&format!("invalid hex: {}", e)))
+    }
+}
+
+#[dispatch]
+mod url_encode {
+    use crate::{types::CelBytes, CelValue};
+
+    fn encode(arg: String) -> String {
+        urlencoding::encode(&arg).into_owned()
+    }
+
+    fn encode(arg: CelBytes) -> String {
+        urlencoding::encode_binary(arg.as_slice()).into_owned()
+    }
+}
+
+#[dispatch]
+mod url_decode {
+    use crate::{CelError, CelResult, CelValue};
+
+    fn decode(arg: String) -> CelResult<String> {
+        urlencoding::decode(&arg)
+            .map(|s| s.into_owned())
+            .map_err(|e| CelError::value(&format!("invalid url encoding: {}", e)))
+    }
+}
@@ -0,0 +1,5 @@
+pub mod distinct;
+pub mod first;
+pub mod flatten;
+pub mod range;
+pub mod slice;
@@ -0,0 +1,32 @@
+use crate::macros::dispatch;
+
+pub use distinct::dispatch as distinct;
+
+#[dispatch]
+mod distinct {
+    use crate::CelValue;
+
+    fn distinct(this: Vec<CelValue>) -> Vec<CelValue> {
+        super::distinct_impl(this)
+    }
+}
+
+use super::super::sets::ValueSet;
+use crate::CelValue;
+
+/// Drops values equal to an earlier value in the list, keeping the first occurrence.
+fn distinct_impl(list: Vec<CelValue>) -> Vec<CelValue> {
+    let mut seen = ValueSet::new(&[]);
+
+    list.iter()
+        .filter(|value| {
+            if seen.contains(value) {
+                false
+            } else {
+                seen.insert(value);
+                true
+            }
+        })
+        .cloned()
+        .collect()
+}
@@ -0,0 +1,22 @@
+use crate::macros::dispatch;
+
+pub use first::dispatch as first;
+pub use last::dispatch as last;
+
+#[dispatch]
+mod first {
+    use crate::{types::CelOptional, CelValue};
+
+    fn first(this: Vec<CelValue>) -> CelValue {
+        CelOptional::new(this.into_iter().next()).into()
+    }
+}
+
+#[dispatch]
+mod last {
+    use crate::{types::CelOptional, CelValue};
+
+    fn last(this: Vec<CelValue>) -> CelValue {
+        CelOptional::new(this.into_iter().last()).into()
+    }
+}
@@ -0,0 +1,39 @@
+use crate::macros::dispatch;
+
+pub use flatten::dispatch as flatten;
+
+#[dispatch]
+mod flatten {
+    use crate::{CelResult, CelValue};
+
+    fn flatten(this: Vec<CelValue>) -> Vec<CelValue> {
+        super::flatten_impl(this, 1)
+    }
+
+    fn flatten(this: Vec<CelValue>, depth: i64) -> CelResult<Vec<CelValue>> {
+        if depth < 0 {
+            return Err(crate::CelError::value("level must be non-negative"));
+        }
+
+        Ok(super::flatten_impl(this, depth as u64))
+    }
+}
+
+use crate::CelValue;
+
+/// Splices nested lists into their parent up to `depth` levels deep.
+fn flatten_impl(list: Vec<CelValue>, depth: u64) -> Vec<CelValue> {
+    if depth == 0 {
+        return list;
+    }
+
+    let mut res = Vec::with_capacity(list.len());
+    for value in list.into_iter() {
+        match value {
+            CelValue::List(inner) => res.extend(flatten_impl(inner, depth - 1)),
+            other => res.push(other),
+        }
+    }
+
+    res
+}
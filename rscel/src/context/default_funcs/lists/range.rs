@@ -0,0 +1,18 @@
+use crate::macros::dispatch;
+
+pub use range::dispatch as range;
+
+#[dispatch]
+mod range {
+    use crate::{CelError, CelResult, CelValue};
+
+    fn range(n: i64) -> CelResult<Vec<CelValue>> {
+        if n < 0 {
+            return Err(CelError::value(
+                "lists.range() requires a non-negative size",
+            ));
+        }
+
+        Ok((0..n).map(CelValue::from_int).collect())
+    }
+}
@@ -0,0 +1,42 @@
+use crate::macros::dispatch;
+
+pub use slice::dispatch as slice;
+
+#[dispatch]
+mod slice {
+    use crate::{CelResult, CelValue};
+
+    fn slice(this: Vec<CelValue>, start: i64, end: i64) -> CelResult<Vec<CelValue>> {
+        super::slice_impl(this, start, end)
+    }
+}
+
+use crate::{CelError, CelResult, CelValue};
+
+fn slice_impl(mut list: Vec<CelValue>, start: i64, end: i64) -> CelResult<Vec<CelValue>> {
+    if start < 0 || end < 0 {
+        return Err(CelError::value(&format!(
+            "cannot slice({}, {}), negative indexes not supported",
+            start, end
+        )));
+    }
+
+    if start > end {
+        return Err(CelError::value(&format!(
+            "cannot slice({}, {}), start index must be less than or equal to end index",
+            start, end
+        )));
+    }
+
+    if end as u64 > list.len() as u64 {
+        return Err(CelError::value(&format!(
+            "cannot slice({}, {}), list is length {}",
+            start,
+            end,
+            list.len()
+        )));
+    }
+
+    list.truncate(end as usize);
+    Ok(list.split_off(start as usize))
+}
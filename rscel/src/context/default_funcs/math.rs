@@ -1,8 +1,13 @@
 pub mod abs;
+pub mod bit;
 pub mod ceil;
+pub mod classify;
 pub mod floor;
+pub mod greatest;
 pub mod lg;
 pub mod log;
 pub mod pow;
 pub mod round;
+pub mod sign;
 pub mod sqrt;
+pub mod trunc;
@@ -0,0 +1,102 @@
+use crate::macros::dispatch;
+
+pub use bit_and::dispatch as bit_and;
+pub use bit_not::dispatch as bit_not;
+pub use bit_or::dispatch as bit_or;
+pub use bit_shift_left::dispatch as bit_shift_left;
+pub use bit_shift_right::dispatch as bit_shift_right;
+pub use bit_xor::dispatch as bit_xor;
+
+fn check_offset(name: &str, n: i64) -> crate::CelResult<u32> {
+    if n < 0 {
+        Err(crate::CelError::argument(&format!(
+            "math.{}() negative offset: {}",
+            name, n
+        )))
+    } else {
+        Ok(n.min(64) as u32)
+    }
+}
+
+#[dispatch]
+mod bit_and {
+    use crate::CelValue;
+
+    fn bit_and(lhs: i64, rhs: i64) -> i64 {
+        lhs & rhs
+    }
+
+    fn bit_and(lhs: u64, rhs: u64) -> u64 {
+        lhs & rhs
+    }
+}
+
+#[dispatch]
+mod bit_or {
+    use crate::CelValue;
+
+    fn bit_or(lhs: i64, rhs: i64) -> i64 {
+        lhs | rhs
+    }
+
+    fn bit_or(lhs: u64, rhs: u64) -> u64 {
+        lhs | rhs
+    }
+}
+
+#[dispatch]
+mod bit_xor {
+    use crate::CelValue;
+
+    fn bit_xor(lhs: i64, rhs: i64) -> i64 {
+        lhs ^ rhs
+    }
+
+    fn bit_xor(lhs: u64, rhs: u64) -> u64 {
+        lhs ^ rhs
+    }
+}
+
+#[dispatch]
+mod bit_not {
+    use crate::CelValue;
+
+    fn bit_not(n: i64) -> i64 {
+        !n
+    }
+
+    fn bit_not(n: u64) -> u64 {
+        !n
+    }
+}
+
+#[dispatch]
+mod bit_shift_left {
+    use crate::{CelResult, CelValue};
+
+    fn bit_shift_left(n: i64, offset: i64) -> CelResult<i64> {
+        let offset = super::check_offset("bitShiftLeft", offset)?;
+        Ok(n.checked_shl(offset).unwrap_or(0))
+    }
+
+    fn bit_shift_left(n: u64, offset: i64) -> CelResult<u64> {
+        let offset = super::check_offset("bitShiftLeft", offset)?;
+        Ok(n.checked_shl(offset).unwrap_or(0))
+    }
+}
+
+#[dispatch]
+mod bit_shift_right {
+    use crate::{CelResult, CelValue};
+
+    // Right shifts on ints are logical, the sign bit is not extended
+    fn bit_shift_right(n: i64, offset: i64) -> CelResult<i64> {
+        let offset = super::check_offset("bitShiftRight", offset)?;
+        Ok((n as u64).checked_shr(offset).unwrap_or(0) as i64)
+    }
+
+    fn bit_shift_right(n: u64, offset: i64) -> CelResult<u64> {
+        let offset = super::check_offset("bitShiftRight", offset)?;
+        Ok(n.checked_shr(offset).unwrap_or(0))
+    }
+}
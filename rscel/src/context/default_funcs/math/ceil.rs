@@ -1,5 +1,6 @@
 use crate::macros::dispatch;
 
+pub use ext_methods::dispatch as math_ceil;
 pub use methods::dispatch as ceil;
 
 #[dispatch]
@@ -18,3 +19,14 @@ mod methods {
         n.ceil() as i64
     }
 }
+
+/// `math.ceil` from the CEL math extension, defined on doubles only and keeping the
+/// double type.
+#[dispatch]
+mod ext_methods {
+    use crate::CelValue;
+
+    fn ceil(n: f64) -> f64 {
+        n.ceil()
+    }
+}
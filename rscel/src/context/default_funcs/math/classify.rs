@@ -0,0 +1,32 @@
+use crate::macros::dispatch;
+
+pub use is_finite::dispatch as is_finite;
+pub use is_inf::dispatch as is_inf;
+pub use is_nan::dispatch as is_nan;
+
+#[dispatch]
+mod is_nan {
+    use crate::CelValue;
+
+    fn is_nan(n: f64) -> bool {
+        n.is_nan()
+    }
+}
+
+#[dispatch]
+mod is_inf {
+    use crate::CelValue;
+
+    fn is_inf(n: f64) -> bool {
+        n.is_infinite()
+    }
+}
+
+#[dispatch]
+mod is_finite {
+    use crate::CelValue;
+
+    fn is_finite(n: f64) -> bool {
+        n.is_finite()
+    }
+}
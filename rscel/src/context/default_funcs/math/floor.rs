@@ -1,5 +1,6 @@
 use crate::macros::dispatch;
 
+pub use ext_methods::dispatch as math_floor;
 pub use methods::dispatch as floor;
 
 #[dispatch]
@@ -18,3 +19,14 @@ mod methods {
         n.floor() as i64
     }
 }
+
+/// `math.floor` from the CEL math extension, defined on doubles only and keeping the
+/// double type.
+#[dispatch]
+mod ext_methods {
+    use crate::CelValue;
+
+    fn floor(n: f64) -> f64 {
+        n.floor()
+    }
+}
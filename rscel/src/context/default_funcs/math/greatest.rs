@@ -0,0 +1,68 @@
+use std::cmp::Ordering;
+
+use crate::{CelError, CelValue, CelValueDyn};
+
+pub fn greatest(_this: CelValue, args: Vec<CelValue>) -> CelValue {
+    extreme("math.greatest", args, Ordering::Greater)
+}
+
+pub fn least(_this: CelValue, args: Vec<CelValue>) -> CelValue {
+    extreme("math.least", args, Ordering::Less)
+}
+
+/// Finds the numeric extreme of the arguments, or of a single list argument,
+/// keeping the type of the winning value.
+fn extreme(name: &str, args: Vec<CelValue>, wanted: Ordering) -> CelValue {
+    let args = match <[CelValue; 1]>::try_from(args) {
+        Ok([CelValue::List(list)]) => list,
+        Ok([arg]) => vec![arg],
+        Err(args) => args,
+    };
+
+    let mut res: Option<CelValue> = None;
+    for arg in args.into_iter() {
+        match arg {
+            CelValue::Int(_) | CelValue::UInt(_) | CelValue::Float(_) => {}
+            CelValue::Err(_) => return arg,
+            other => {
+                return CelValue::from_err(CelError::argument(&format!(
+                    "{}() does not support {}",
+                    name,
+                    other.as_type()
+                )))
+            }
+        }
+
+        res = match res {
+            Some(curr) if numeric_cmp(&arg, &curr) != Some(wanted) => Some(curr),
+            _ => Some(arg),
+        };
+    }
+
+    res.unwrap_or_else(|| {
+        CelValue::from_err(CelError::argument(&format!(
+            "{}() requires at least one argument",
+            name
+        )))
+    })
+}
+
+/// Compares numbers of any type without losing precision between ints and
+/// uints.
+fn numeric_cmp(lhs: &CelValue, rhs: &CelValue) -> Option<Ordering> {
+    match (lhs, rhs) {
+        (CelValue::Int(l), CelValue::Int(r)) => Some(l.cmp(r)),
+        (CelValue::UInt(l), CelValue::UInt(r)) => Some(l.cmp(r)),
+        (CelValue::Float(l), CelValue::Float(r)) => l.partial_cmp(r),
+        (CelValue::Int(l), CelValue::UInt(r)) => Some(if *l < 0 {
+            Ordering::Less
+        } else {
+            (*l as u64).cmp(r)
+        }),
+        (CelValue::UInt(_), CelValue::Int(_)) => numeric_cmp(rhs, lhs).map(Ordering::reverse),
+        (CelValue::Int(l), CelValue::Float(r)) => (*l as f64).partial_cmp(r),
+        (CelValue::UInt(l), CelValue::Float(r)) => (*l as f64).partial_cmp(r),
+        (CelValue::Float(_), _) => numeric_cmp(rhs, lhs).map(Ordering::reverse),
+        _ => None,
+    }
+}
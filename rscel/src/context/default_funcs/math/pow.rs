@@ -1,33 +1,34 @@
 use crate::macros::dispatch;
+use crate::{CelError, CelResult};
 
 pub use methods::dispatch as pow;
 
 #[dispatch]
 mod methods {
-    use crate::CelValue;
+    use crate::{CelResult, CelValue};
 
-    fn pow(n1: i64, n2: i64) -> i64 {
-        n1.pow(n2 as u32)
+    fn pow(n1: i64, n2: i64) -> CelResult<i64> {
+        super::checked_pow(n1, n2, i64::checked_pow)
     }
 
-    fn pow(n1: i64, n2: u64) -> i64 {
-        n1.pow(n2 as u32)
+    fn pow(n1: i64, n2: u64) -> CelResult<i64> {
+        super::checked_pow(n1, n2, i64::checked_pow)
     }
 
-    fn pow(n1: i64, n2: f64) -> i64 {
-        n1.pow(n2 as u32)
+    fn pow(n1: i64, n2: f64) -> CelResult<i64> {
+        super::checked_pow(n1, n2 as i64, i64::checked_pow)
     }
 
-    fn pow(n1: u64, n2: i64) -> u64 {
-        n1.pow(n2 as u32)
+    fn pow(n1: u64, n2: i64) -> CelResult<u64> {
+        super::checked_pow(n1, n2, u64::checked_pow)
     }
 
-    fn pow(n1: u64, n2: u64) -> u64 {
-        n1.pow(n2 as u32)
+    fn pow(n1: u64, n2: u64) -> CelResult<u64> {
+        super::checked_pow(n1, n2, u64::checked_pow)
     }
 
-    fn pow(n1: u64, n2: f64) -> u64 {
-        n1.pow(n2 as u32)
+    fn pow(n1: u64, n2: f64) -> CelResult<u64> {
+        super::checked_pow(n1, n2 as i64, u64::checked_pow)
     }
 
     fn pow(n1: f64, n2: i64) -> f64 {
@@ -42,3 +43,21 @@ mod methods {
         n1.powf(n2)
     }
 }
+
+fn checked_pow<N, E>(base: N, exp: E, pow: fn(N, u32) -> Option<N>) -> CelResult<N>
+where
+    N: Copy + std::fmt::Display,
+    E: Copy + std::fmt::Display + TryInto<u32> + PartialOrd + Default,
+{
+    if exp < E::default() {
+        return Err(CelError::argument(&format!(
+            "pow() of an integer needs a non-negative exponent, got {}",
+            exp
+        )));
+    }
+
+    exp.try_into()
+        .ok()
+        .and_then(|e| pow(base, e))
+        .ok_or_else(|| CelError::overflow(&format!("pow({}, {})", base, exp)))
+}
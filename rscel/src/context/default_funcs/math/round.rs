@@ -1,5 +1,6 @@
 use crate::macros::dispatch;
 
+pub use ext_methods::dispatch as math_round;
 pub use methods::dispatch as round;
 
 #[dispatch]
@@ -18,3 +19,14 @@ mod methods {
         n.round() as i64
     }
 }
+
+/// `math.round` from the CEL math extension, defined on doubles only and keeping the
+/// double type.
+#[dispatch]
+mod ext_methods {
+    use crate::CelValue;
+
+    fn round(n: f64) -> f64 {
+        n.round()
+    }
+}
@@ -0,0 +1,24 @@
+use crate::macros::dispatch;
+
+pub use methods::dispatch as sign;
+
+#[dispatch]
+mod methods {
+    use crate::CelValue;
+
+    fn sign(n: i64) -> i64 {
+        n.signum()
+    }
+
+    fn sign(n: u64) -> u64 {
+        n.min(1)
+    }
+
+    fn sign(n: f64) -> f64 {
+        if n == 0.0 || n.is_nan() {
+            n
+        } else {
+            n.signum()
+        }
+    }
+}
@@ -0,0 +1,12 @@
+use crate::macros::dispatch;
+
+pub use methods::dispatch as trunc;
+
+#[dispatch]
+mod methods {
+    use crate::CelValue;
+
+    fn trunc(n: f64) -> f64 {
+        n.trunc()
+    }
+}
@@ -0,0 +1,70 @@
+use crate::{types::CelOptional, CelError, CelValue, CelValueDyn};
+
+pub fn optional_of(_this: CelValue, args: Vec<CelValue>) -> CelValue {
+    match <[CelValue; 1]>::try_from(args) {
+        Ok([CelValue::Err(err)]) => err.into(),
+        Ok([value]) => CelOptional::new(Some(value)).into(),
+        Err(_) => CelValue::from_err(CelError::argument(
+            "optional.of() expects exactly 1 argument",
+        )),
+    }
+}
+
+pub fn optional_none(_this: CelValue, args: Vec<CelValue>) -> CelValue {
+    if !args.is_empty() {
+        return CelValue::from_err(CelError::argument("optional.none() expects no arguments"));
+    }
+
+    CelOptional::new(None).into()
+}
+
+pub fn has_value(this: CelValue, args: Vec<CelValue>) -> CelValue {
+    if !args.is_empty() {
+        return CelValue::from_err(CelError::argument("hasValue() expects no arguments"));
+    }
+
+    match CelOptional::from_cel_value(&this) {
+        Some(opt) => opt.value().is_some().into(),
+        None => not_optional("hasValue", &this),
+    }
+}
+
+pub fn value(this: CelValue, args: Vec<CelValue>) -> CelValue {
+    if !args.is_empty() {
+        return CelValue::from_err(CelError::argument("value() expects no arguments"));
+    }
+
+    match CelOptional::from_cel_value(&this) {
+        Some(opt) => match opt.value() {
+            Some(v) => v.clone(),
+            None => CelValue::from_err(CelError::value("optional.none() dereference")),
+        },
+        None => not_optional("value", &this),
+    }
+}
+
+pub fn or_value(this: CelValue, args: Vec<CelValue>) -> CelValue {
+    let default = match <[CelValue; 1]>::try_from(args) {
+        Ok([default]) => default,
+        Err(_) => {
+            return CelValue::from_err(CelError::argument("orValue() expects exactly 1 argument"))
+        }
+    };
+
+    match CelOptional::from_cel_value(&this) {
+        Some(opt) => opt.value().cloned().unwrap_or(default),
+        None => not_optional("orValue", &this),
+    }
+}
+
+fn not_optional(func: &str, this: &CelValue) -> CelValue {
+    if this.is_err() {
+        return this.clone();
+    }
+
+    CelValue::from_err(CelError::value(&format!(
+        "{}() only available on optional_type, was given {}",
+        func,
+        this.as_type()
+    )))
+}
@@ -0,0 +1,68 @@
+use crate::{types::CelQuantity, CelError, CelValue, CelValueDyn};
+
+pub fn quantity(_this: CelValue, args: Vec<CelValue>) -> CelValue {
+    let (value, unit) = match <[CelValue; 2]>::try_from(args) {
+        Ok([CelValue::Err(err), _]) | Ok([_, CelValue::Err(err)]) => return err.into(),
+        Ok([CelValue::Int(i), CelValue::String(unit)]) => (i as f64, unit),
+        Ok([CelValue::UInt(u), CelValue::String(unit)]) => (u as f64, unit),
+        Ok([CelValue::Float(f), CelValue::String(unit)]) => (f, unit),
+        _ => {
+            return CelValue::from_err(CelError::argument("quantity() expects a number and a unit"))
+        }
+    };
+
+    match CelQuantity::parse(value, &unit) {
+        Ok(quantity) => quantity.into(),
+        Err(err) => err.into(),
+    }
+}
+
+pub fn to(this: CelValue, args: Vec<CelValue>) -> CelValue {
+    let unit = match <[CelValue; 1]>::try_from(args) {
+        Ok([CelValue::String(unit)]) => unit,
+        Ok([CelValue::Err(err)]) => return err.into(),
+        _ => return CelValue::from_err(CelError::argument("to() expects a unit")),
+    };
+
+    match CelQuantity::from_cel_value(&this) {
+        Some(quantity) => match quantity.to(&unit) {
+            Ok(converted) => converted.into(),
+            Err(err) => err.into(),
+        },
+        None => not_quantity("to", &this),
+    }
+}
+
+pub fn magnitude(this: CelValue, args: Vec<CelValue>) -> CelValue {
+    if !args.is_empty() {
+        return CelValue::from_err(CelError::argument("magnitude() expects no arguments"));
+    }
+
+    match CelQuantity::from_cel_value(&this) {
+        Some(quantity) => quantity.magnitude().into(),
+        None => not_quantity("magnitude", &this),
+    }
+}
+
+pub fn unit(this: CelValue, args: Vec<CelValue>) -> CelValue {
+    if !args.is_empty() {
+        return CelValue::from_err(CelError::argument("unit() expects no arguments"));
+    }
+
+    match CelQuantity::from_cel_value(&this) {
+        Some(quantity) => quantity.symbol().into(),
+        None => not_quantity("unit", &this),
+    }
+}
+
+fn not_quantity(func: &str, this: &CelValue) -> CelValue {
+    if this.is_err() {
+        return this.clone();
+    }
+
+    CelValue::from_err(CelError::value(&format!(
+        "{}() only available on quantity, was given {}",
+        func,
+        this.as_type()
+    )))
+}
@@ -0,0 +1,17 @@
+use crate::macros::dispatch;
+
+pub use reverse::dispatch as reverse;
+
+#[dispatch]
+mod reverse {
+    use crate::CelValue;
+
+    fn reverse(this: String) -> String {
+        this.chars().rev().collect()
+    }
+
+    fn reverse(mut this: Vec<CelValue>) -> Vec<CelValue> {
+        this.reverse();
+        this
+    }
+}
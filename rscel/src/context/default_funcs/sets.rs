@@ -0,0 +1,124 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::{macros::dispatch, types::CelZonedTimestamp, CelValue, CelValueDyn};
+
+pub use sets_contains::dispatch as sets_contains;
+pub use sets_equivalent::dispatch as sets_equivalent;
+pub use sets_intersects::dispatch as sets_intersects;
+
+#[dispatch]
+mod sets_contains {
+    use crate::CelValue;
+
+    fn contains(list: Vec<CelValue>, sublist: Vec<CelValue>) -> bool {
+        let set = super::ValueSet::new(&list);
+        sublist.iter().all(|v| set.contains(v))
+    }
+}
+
+#[dispatch]
+mod sets_equivalent {
+    use crate::CelValue;
+
+    fn equivalent(lhs: Vec<CelValue>, rhs: Vec<CelValue>) -> bool {
+        let lhs_set = super::ValueSet::new(&lhs);
+        let rhs_set = super::ValueSet::new(&rhs);
+
+        rhs.iter().all(|v| lhs_set.contains(v)) && lhs.iter().all(|v| rhs_set.contains(v))
+    }
+}
+
+#[dispatch]
+mod sets_intersects {
+    use crate::CelValue;
+
+    fn intersects(lhs: Vec<CelValue>, rhs: Vec<CelValue>) -> bool {
+        let set = super::ValueSet::new(&lhs);
+        rhs.iter().any(|v| set.contains(v))
+    }
+}
+
+/// Hashable stand in for the values CEL equality can compare cheaply. Numbers and
+/// bools share one key space since `1 == 1u == 1.0 == true` after type propagation,
+/// zoned timestamps share the key of their instant.
+#[derive(PartialEq, Eq, Hash)]
+enum SetKey<'a> {
+    Number(u64),
+    String(&'a str),
+    Bytes(&'a [u8]),
+    Null,
+    Type(&'a str),
+    TimeStamp(DateTime<Utc>),
+    Duration(&'a Duration),
+}
+
+impl<'a> SetKey<'a> {
+    /// Returns `None` for values that have to be compared one by one, like quantities
+    /// whose equality allows for rounding in unit conversions.
+    fn new(value: &'a CelValue) -> Option<SetKey<'a>> {
+        let number = |n: f64| {
+            // Normalize -0.0 so it hashes alongside 0.0
+            Some(SetKey::Number((n + 0.0).to_bits()))
+        };
+
+        match value {
+            CelValue::Int(i) => number(*i as f64),
+            CelValue::UInt(u) => number(*u as f64),
+            CelValue::Float(f) => number(*f),
+            CelValue::Bool(b) => number(if *b { 1.0 } else { 0.0 }),
+            CelValue::String(s) => Some(SetKey::String(s)),
+            CelValue::Bytes(b) => Some(SetKey::Bytes(b.as_slice())),
+            CelValue::Null => Some(SetKey::Null),
+            CelValue::Type(t) => Some(SetKey::Type(t)),
+            CelValue::TimeStamp(ts) => Some(SetKey::TimeStamp(*ts)),
+            CelValue::Duration(d) => Some(SetKey::Duration(d)),
+            other => CelZonedTimestamp::from_cel_value(other)
+                .map(|zoned| SetKey::TimeStamp(zoned.instant())),
+        }
+    }
+}
+
+/// Membership over a list of values using CEL equality. Values are bucketed by
+/// `SetKey` so lookups only run `CelValueDyn::eq` against likely matches.
+pub(super) struct ValueSet<'a> {
+    buckets: HashMap<SetKey<'a>, Vec<&'a CelValue>>,
+    others: Vec<&'a CelValue>,
+}
+
+impl<'a> ValueSet<'a> {
+    pub(super) fn new(values: &'a [CelValue]) -> ValueSet<'a> {
+        let mut set = ValueSet {
+            buckets: HashMap::new(),
+            others: Vec::new(),
+        };
+
+        for value in values.iter() {
+            set.insert(value);
+        }
+
+        set
+    }
+
+    pub(super) fn insert(&mut self, value: &'a CelValue) {
+        match SetKey::new(value) {
+            Some(key) => self.buckets.entry(key).or_default().push(value),
+            None => self.others.push(value),
+        }
+    }
+
+    pub(super) fn contains(&self, value: &CelValue) -> bool {
+        let candidates = match SetKey::new(value) {
+            Some(key) => match self.buckets.get(&key) {
+                Some(bucket) => bucket.as_slice(),
+                None => return false,
+            },
+            None => self.others.as_slice(),
+        };
+
+        candidates
+            .iter()
+            .any(|c| CelValueDyn::eq(*c, value).is_true())
+    }
+}
@@ -1,16 +1,22 @@
 use crate::{CelError, CelValue};
 
+pub mod char_at;
 pub mod contains;
 pub mod ends_with;
+pub mod format;
+pub mod index_of;
+pub mod join;
 pub mod match_captures;
 pub mod match_replace;
 pub mod match_replace_once;
 pub mod matches;
+pub mod quote;
 pub mod remove;
 pub mod replace;
 pub mod split;
 pub mod split_whitespace;
 pub mod starts_with;
+pub mod substring;
 pub mod trim_end_matches;
 pub mod trim_start_matches;
 
@@ -36,6 +42,8 @@ macro_rules! string_func {
 
 string_func!(toLower, to_lower_impl, to_lowercase);
 string_func!(toUpper, to_upper_impl, to_uppercase);
+string_func!(lowerAscii, lower_ascii_impl, to_ascii_lowercase);
+string_func!(upperAscii, upper_ascii_impl, to_ascii_uppercase);
 string_func!(trim, trim_impl, trim);
 string_func!(trimStart, trim_start_impl, trim_start);
 string_func!(trimEnd, trim_end_impl, trim_end);
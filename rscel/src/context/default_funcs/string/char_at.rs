@@ -0,0 +1,22 @@
+use crate::macros::dispatch;
+
+pub use char_at::dispatch as char_at;
+
+#[dispatch]
+mod char_at {
+    use crate::{CelError, CelResult, CelValue};
+
+    fn char_at(this: String, index: i64) -> CelResult<String> {
+        let len = this.chars().count();
+
+        if index < 0 || index as usize > len {
+            return Err(CelError::value(&format!("index out of range: {}", index)));
+        }
+
+        Ok(this
+            .chars()
+            .nth(index as usize)
+            .map(|c| c.to_string())
+            .unwrap_or_default())
+    }
+}
@@ -0,0 +1,277 @@
+use crate::macros::dispatch;
+
+pub use format::dispatch as format;
+
+#[dispatch]
+mod format {
+    use chrono::{DateTime, Utc};
+    use chrono_tz::Tz;
+
+    use crate::context::default_funcs::time_funcs::helpers::{format_in, parse_timezone};
+    use crate::CelResult;
+    use crate::CelValue;
+
+    fn format(this: String, args: Vec<CelValue>) -> CelResult<String> {
+        super::format_impl(&this, args)
+    }
+
+    fn format(this: DateTime<Utc>, pattern: String) -> CelResult<String> {
+        format_in(this, &pattern, &Tz::UTC)
+    }
+
+    fn format(this: DateTime<Utc>, pattern: String, timezone: String) -> CelResult<String> {
+        format_in(this, &pattern, &parse_timezone(&timezone)?)
+    }
+}
+
+use super::quote::{quote_bytes, quote_str};
+use crate::{
+    context::construct_type,
+    types::{cel_time::format_timestamp, CelZonedTimestamp},
+    utils::scientific,
+    CelError, CelResult, CelValue, CelValueDyn,
+};
+
+/// Implements the CEL strings extension `format`, `%s %d %f %e %x %X %o %b`
+/// verbs with an optional precision for `%f` and `%e`.
+fn format_impl(fmt: &str, args: Vec<CelValue>) -> CelResult<String> {
+    let mut args = args.into_iter();
+    let mut index = 0;
+    let mut res = String::new();
+    let mut chars = fmt.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            res.push(c);
+            continue;
+        }
+
+        if let Some('%') = chars.peek() {
+            chars.next();
+            res.push('%');
+            continue;
+        }
+
+        let precision = if let Some('.') = chars.peek() {
+            chars.next();
+            let mut digits = String::new();
+            while let Some(d) = chars.next_if(|d| d.is_ascii_digit()) {
+                digits.push(d);
+            }
+
+            Some(digits.parse::<usize>().map_err(|_| {
+                CelError::value("could not parse formatting clause: missing precision")
+            })?)
+        } else {
+            None
+        };
+
+        let verb = chars.next().ok_or_else(|| {
+            CelError::value("could not parse formatting clause: unexpected end of string")
+        })?;
+
+        if precision.is_some() && !matches!(verb, 'f' | 'e') {
+            return Err(CelError::value(&format!(
+                "could not parse formatting clause: precision not supported for \"{}\"",
+                verb
+            )));
+        }
+
+        let arg = match args.next() {
+            Some(CelValue::Err(err)) => return Err(err),
+            Some(arg) => arg,
+            None => return Err(CelError::value(&format!("index {} out of range", index))),
+        };
+        index += 1;
+
+        res.push_str(&match verb {
+            's' => format_string(arg)?,
+            'd' => format_decimal(arg)?,
+            'f' | 'e' => format_float(arg, verb, precision.unwrap_or(6))?,
+            'x' | 'X' => format_hex(arg, verb == 'X')?,
+            'o' => format_radix(arg, 'o')?,
+            'b' => format_radix(arg, 'b')?,
+            other => {
+                return Err(CelError::value(&format!(
+                    "could not parse formatting clause: unrecognized formatting clause \"{}\"",
+                    other
+                )))
+            }
+        });
+    }
+
+    Ok(res)
+}
+
+fn clause_error(clause: &str, expected: &str, arg: &CelValue) -> CelError {
+    CelError::value(&format!(
+        "error during formatting: {} clause can only be used on {}, was given {}",
+        clause,
+        expected,
+        arg.as_type()
+    ))
+}
+
+fn format_string(arg: CelValue) -> CelResult<String> {
+    match arg {
+        CelValue::String(s) => Ok(s),
+        CelValue::Null => Ok("null".to_owned()),
+        CelValue::Bool(b) => Ok(b.to_string()),
+        CelValue::Float(f) => Ok(format_double(f)),
+        CelValue::Type(t) => Ok(t),
+        CelValue::TimeStamp(ts) => Ok(format_timestamp(&ts)),
+        CelValue::List(list) => Ok(format!(
+            "[{}]",
+            list.into_iter()
+                .map(quote)
+                .collect::<CelResult<Vec<_>>>()?
+                .join(", ")
+        )),
+        CelValue::Map(map) => {
+            let mut entries = map.into_iter().collect::<Vec<_>>();
+            entries.sort_by(|a, b| a.0.cmp(&b.0));
+
+            Ok(format!(
+                "{{{}}}",
+                entries
+                    .into_iter()
+                    .map(|(k, v)| Ok(format!("{}: {}", quote_str(&k), quote(v)?)))
+                    .collect::<CelResult<Vec<_>>>()?
+                    .join(", ")
+            ))
+        }
+        arg @ (CelValue::Int(_)
+        | CelValue::UInt(_)
+        | CelValue::Bytes(_)
+        | CelValue::Duration(_)) => match construct_type("string", vec![arg]).into_result()? {
+            CelValue::String(s) => Ok(s),
+            other => Err(CelError::internal(&format!(
+                "string conversion returned {}",
+                other.as_type()
+            ))),
+        },
+        CelValue::Err(err) => Err(err),
+        other => match CelZonedTimestamp::from_cel_value(&other) {
+            Some(zoned) => Ok(zoned.to_string()),
+            None => Err(clause_error(
+                "string",
+                "strings, bools, bytes, ints, doubles, maps, lists, types, durations, and timestamps",
+                &other,
+            )),
+        },
+    }
+}
+
+/// Values nested in lists and maps are rendered as CEL literals.
+fn quote(arg: CelValue) -> CelResult<String> {
+    match arg {
+        CelValue::String(s) => Ok(quote_str(&s)),
+        CelValue::Bytes(b) => Ok(quote_bytes(b.as_slice())),
+        CelValue::Float(f) if !f.is_finite() => {
+            Ok(format!("double({})", quote_str(&format_double(f))))
+        }
+        CelValue::Type(t) => Ok(format!("type({})", t)),
+        arg @ CelValue::TimeStamp(_) => {
+            Ok(format!("timestamp({})", quote_str(&format_string(arg)?)))
+        }
+        arg @ CelValue::Duration(_) => Ok(format!("duration({})", quote_str(&format_string(arg)?))),
+        other => match CelZonedTimestamp::from_cel_value(&other) {
+            Some(zoned) => Ok(format!(
+                "timestamp({}).inZone({})",
+                quote_str(&zoned.to_string()),
+                quote_str(zoned.zone().name())
+            )),
+            None => format_string(other),
+        },
+    }
+}
+
+fn format_double(f: f64) -> String {
+    if f.is_nan() {
+        "NaN".to_owned()
+    } else if f.is_infinite() {
+        if f > 0.0 { "Infinity" } else { "-Infinity" }.to_owned()
+    } else {
+        f.to_string()
+    }
+}
+
+fn format_decimal(arg: CelValue) -> CelResult<String> {
+    match arg {
+        CelValue::Int(i) => Ok(i.to_string()),
+        CelValue::UInt(u) => Ok(u.to_string()),
+        other => Err(clause_error("decimal", "integers", &other)),
+    }
+}
+
+fn format_float(arg: CelValue, verb: char, precision: usize) -> CelResult<String> {
+    let val = match arg {
+        CelValue::Float(f) => f,
+        CelValue::Int(i) => i as f64,
+        CelValue::UInt(u) => u as f64,
+        CelValue::String(s) if matches!(s.as_str(), "NaN" | "Infinity" | "-Infinity") => {
+            return Ok(s)
+        }
+        other => {
+            let clause = if verb == 'f' {
+                "fixed-point"
+            } else {
+                "scientific"
+            };
+            return Err(clause_error(clause, "doubles", &other));
+        }
+    };
+
+    if !val.is_finite() {
+        return Ok(format_double(val));
+    }
+
+    let sign = if val.is_sign_negative() { "-" } else { "" };
+    Ok(match verb {
+        'e' => format!("{}{}", sign, scientific(val.abs(), precision)),
+        _ => format!("{}{:.*}", sign, precision, val.abs()),
+    })
+}
+
+fn format_hex(arg: CelValue, upper: bool) -> CelResult<String> {
+    let hex = match arg {
+        CelValue::String(s) => s.bytes().map(|b| format!("{:02x}", b)).collect(),
+        CelValue::Bytes(b) => b.as_slice().iter().map(|b| format!("{:02x}", b)).collect(),
+        other @ (CelValue::Int(_) | CelValue::UInt(_)) => format_radix(other, 'x')?,
+        other => {
+            return Err(clause_error(
+                "hex",
+                "integers, byte buffers, and strings",
+                &other,
+            ))
+        }
+    };
+
+    Ok(if upper { hex.to_uppercase() } else { hex })
+}
+
+fn format_radix(arg: CelValue, radix: char) -> CelResult<String> {
+    let (negative, magnitude) = match arg {
+        CelValue::Int(i) => (i < 0, i.unsigned_abs()),
+        CelValue::UInt(u) => (false, u),
+        CelValue::Bool(b) if radix == 'b' => (false, b as u64),
+        other => {
+            return Err(match radix {
+                'b' => clause_error("binary", "integers and bools", &other),
+                _ => clause_error("octal", "integers", &other),
+            })
+        }
+    };
+
+    let digits = match radix {
+        'b' => format!("{:b}", magnitude),
+        'o' => format!("{:o}", magnitude),
+        _ => format!("{:x}", magnitude),
+    };
+
+    Ok(if negative {
+        format!("-{}", digits)
+    } else {
+        digits
+    })
+}
@@ -0,0 +1,64 @@
+use crate::{macros::dispatch, CelError, CelResult};
+
+pub use index_of::dispatch as index_of;
+pub use last_index_of::dispatch as last_index_of;
+
+/// Finds the code point index of `needle` in `haystack` checking the
+/// candidate positions in the order given.
+fn find_at(haystack: &[char], needle: &[char], mut positions: impl Iterator<Item = usize>) -> i64 {
+    positions
+        .find(|&i| haystack[i..].starts_with(needle))
+        .map_or(-1, |i| i as i64)
+}
+
+fn check_index(index: i64, len: usize) -> CelResult<usize> {
+    if index < 0 || index as usize > len {
+        Err(CelError::value(&format!("index out of range: {}", index)))
+    } else {
+        Ok(index as usize)
+    }
+}
+
+#[dispatch]
+mod index_of {
+    use crate::{CelResult, CelValue};
+
+    fn index_of(this: String, needle: String) -> i64 {
+        let haystack: Vec<char> = this.chars().collect();
+        let needle: Vec<char> = needle.chars().collect();
+
+        super::find_at(&haystack, &needle, 0..haystack.len() + 1)
+    }
+
+    fn index_of(this: String, needle: String, start: i64) -> CelResult<i64> {
+        let haystack: Vec<char> = this.chars().collect();
+        let needle: Vec<char> = needle.chars().collect();
+        let start = super::check_index(start, haystack.len())?;
+
+        Ok(super::find_at(
+            &haystack,
+            &needle,
+            start..haystack.len() + 1,
+        ))
+    }
+}
+
+#[dispatch]
+mod last_index_of {
+    use crate::{CelResult, CelValue};
+
+    fn last_index_of(this: String, needle: String) -> i64 {
+        let haystack: Vec<char> = this.chars().collect();
+        let needle: Vec<char> = needle.chars().collect();
+
+        super::find_at(&haystack, &needle, (0..haystack.len() + 1).rev())
+    }
+
+    fn last_index_of(this: String, needle: String, end: i64) -> CelResult<i64> {
+        let haystack: Vec<char> = this.chars().collect();
+        let needle: Vec<char> = needle.chars().collect();
+        let end = super::check_index(end, haystack.len())?;
+
+        Ok(super::find_at(&haystack, &needle, (0..end + 1).rev()))
+    }
+}
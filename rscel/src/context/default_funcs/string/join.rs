@@ -0,0 +1,31 @@
+use crate::{macros::dispatch, CelError, CelResult, CelValue, CelValueDyn};
+
+pub use join::dispatch as join;
+
+#[dispatch]
+mod join {
+    use crate::{CelResult, CelValue};
+
+    fn join(this: Vec<CelValue>) -> CelResult<String> {
+        super::join_impl(this, "")
+    }
+
+    fn join(this: Vec<CelValue>, separator: String) -> CelResult<String> {
+        super::join_impl(this, &separator)
+    }
+}
+
+fn join_impl(this: Vec<CelValue>, separator: &str) -> CelResult<String> {
+    let parts = this
+        .into_iter()
+        .map(|v| match v {
+            CelValue::String(s) => Ok(s),
+            other => Err(CelError::value(&format!(
+                "join only available on list of strings, found {}",
+                other.as_type()
+            ))),
+        })
+        .collect::<CelResult<Vec<_>>>()?;
+
+    Ok(parts.join(separator))
+}
@@ -0,0 +1,54 @@
+use crate::macros::dispatch;
+
+pub use quote::dispatch as quote;
+
+#[dispatch]
+mod quote {
+    use crate::CelValue;
+
+    fn quote(arg: String) -> String {
+        super::quote_str(&arg)
+    }
+}
+
+/// `s` as a double quoted CEL string literal.
+pub(crate) fn quote_str(s: &str) -> String {
+    let mut res = String::from("\"");
+
+    for c in s.chars() {
+        match c {
+            '\x07' => res.push_str("\\a"),
+            '\x08' => res.push_str("\\b"),
+            '\x0c' => res.push_str("\\f"),
+            '\n' => res.push_str("\\n"),
+            '\r' => res.push_str("\\r"),
+            '\t' => res.push_str("\\t"),
+            '\x0b' => res.push_str("\\v"),
+            '\\' => res.push_str("\\\\"),
+            '"' => res.push_str("\\\""),
+            c if c.is_control() => res.push_str(&format!("\\u{:04x}", c as u32)),
+            c => res.push(c),
+        }
+    }
+
+    res.push('"');
+    res
+}
+
+/// `b` as a double quoted CEL bytes literal, bytes outside of printable ASCII are
+/// written as `\x` escapes.
+pub(crate) fn quote_bytes(b: &[u8]) -> String {
+    let mut res = String::from("b\"");
+
+    for &byte in b {
+        match byte {
+            b'\\' => res.push_str("\\\\"),
+            b'"' => res.push_str("\\\""),
+            b' '..=b'~' => res.push(byte as char),
+            other => res.push_str(&format!("\\x{:02x}", other)),
+        }
+    }
+
+    res.push('"');
+    res
+}
@@ -9,4 +9,12 @@ mod replace {
     fn replace(this: String, needle: String, to: String) -> String {
         this.replace(&needle, &to)
     }
+
+    fn replace(this: String, needle: String, to: String, count: i64) -> String {
+        if count < 0 {
+            this.replace(&needle, &to)
+        } else {
+            this.replacen(&needle, &to, count as usize)
+        }
+    }
 }
@@ -20,6 +20,16 @@ mod split {
     fn split(this: String, needle: String) -> Vec<CelValue> {
         this.split(&needle).map(|s| s.into()).collect()
     }
+
+    fn split(this: String, needle: String, limit: i64) -> Vec<CelValue> {
+        if limit < 0 {
+            this.split(&needle).map(|s| s.into()).collect()
+        } else {
+            this.splitn(limit as usize, &needle)
+                .map(|s| s.into())
+                .collect()
+        }
+    }
 }
 
 #[dispatch]
@@ -0,0 +1,41 @@
+use crate::{macros::dispatch, CelError, CelResult};
+
+pub use substring::dispatch as substring;
+
+#[dispatch]
+mod substring {
+    use crate::{CelResult, CelValue};
+
+    fn substring(this: String, start: i64) -> CelResult<String> {
+        let len = this.chars().count() as i64;
+
+        super::substring_impl(&this, start, len)
+    }
+
+    fn substring(this: String, start: i64, end: i64) -> CelResult<String> {
+        super::substring_impl(&this, start, end)
+    }
+}
+
+fn substring_impl(this: &str, start: i64, end: i64) -> CelResult<String> {
+    let len = this.chars().count() as i64;
+
+    for index in [start, end] {
+        if index < 0 || index > len {
+            return Err(CelError::value(&format!("index out of range: {}", index)));
+        }
+    }
+
+    if start > end {
+        return Err(CelError::value(&format!(
+            "invalid substring range. start: {}, end: {}",
+            start, end
+        )));
+    }
+
+    Ok(this
+        .chars()
+        .skip(start as usize)
+        .take((end - start) as usize)
+        .collect())
+}
@@ -1,5 +1,8 @@
-mod helpers;
+pub mod helpers;
 
+pub mod add_months;
+pub mod calendar;
+pub mod duration_parts;
 pub mod get_date;
 pub mod get_day_of_month;
 pub mod get_day_of_week;
@@ -10,3 +13,8 @@ pub mod get_milliseconds;
 pub mod get_minutes;
 pub mod get_month;
 pub mod get_seconds;
+pub mod start_of_week;
+pub mod to_epoch_seconds;
+pub mod to_iso8601;
+pub mod truncate;
+pub mod zoned;
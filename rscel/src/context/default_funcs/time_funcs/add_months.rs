@@ -0,0 +1,45 @@
+use chrono::{DateTime, Months, Utc};
+use chrono_tz::Tz;
+
+use crate::{macros::dispatch, CelError, CelResult};
+
+use super::helpers::from_local;
+
+pub use methods::dispatch as add_months;
+
+#[dispatch]
+mod methods {
+    use chrono::{DateTime, Utc};
+    use chrono_tz::Tz;
+
+    use super::super::helpers::parse_timezone;
+    use crate::{CelResult, CelValue};
+
+    fn add_months(this: DateTime<Utc>, months: i64) -> CelResult<DateTime<Utc>> {
+        super::add_months_in(this, months, &Tz::UTC)
+    }
+
+    fn add_months(this: DateTime<Utc>, months: i64, timezone: String) -> CelResult<DateTime<Utc>> {
+        super::add_months_in(this, months, &parse_timezone(&timezone)?)
+    }
+}
+
+/// Moves the wall clock time in `tz` by whole months, days past the end of the new
+/// month are clamped to its last day.
+fn add_months_in(this: DateTime<Utc>, months: i64, tz: &Tz) -> CelResult<DateTime<Utc>> {
+    let local = this.with_timezone(tz).naive_local();
+    let delta = u32::try_from(months.unsigned_abs())
+        .map(Months::new)
+        .map_err(|_| CelError::overflow("timestamp out of range"))?;
+
+    let moved = if months < 0 {
+        local.checked_sub_months(delta)
+    } else {
+        local.checked_add_months(delta)
+    };
+
+    match moved {
+        Some(moved) => from_local(moved, tz),
+        None => Err(CelError::overflow("timestamp out of range")),
+    }
+}
@@ -0,0 +1,206 @@
+//! Calendar helpers for business rules. Days are the calendar days of the timestamps
+//! as seen in the timezone, UTC by default, and weekends are Saturday and Sunday.
+use std::collections::BTreeSet;
+
+use chrono::{DateTime, Datelike, Days, Duration, Months, NaiveDate, NaiveTime, Utc, Weekday};
+use chrono_tz::Tz;
+
+use crate::{
+    macros::dispatch, types::CelZonedTimestamp, CelError, CelResult, CelValue, CelValueDyn,
+};
+
+use super::helpers::{checked, from_local};
+
+pub use business_days_between::dispatch as business_days_between;
+pub use end_of_month::dispatch as end_of_month;
+pub use is_weekend::dispatch as is_weekend;
+pub use next_day_of_week::dispatch as next_day_of_week;
+
+#[dispatch]
+mod is_weekend {
+    use chrono::{DateTime, Utc};
+    use chrono_tz::Tz;
+
+    use super::super::helpers::parse_timezone;
+    use crate::{CelResult, CelValue};
+
+    fn is_weekend(this: DateTime<Utc>) -> bool {
+        super::is_weekend_day(super::local_date(this, &Tz::UTC))
+    }
+
+    fn is_weekend(this: DateTime<Utc>, timezone: String) -> CelResult<bool> {
+        Ok(super::is_weekend_day(super::local_date(
+            this,
+            &parse_timezone(&timezone)?,
+        )))
+    }
+}
+
+#[dispatch]
+mod business_days_between {
+    use chrono::{DateTime, Utc};
+    use chrono_tz::Tz;
+
+    use super::super::helpers::parse_timezone;
+    use crate::{CelResult, CelValue};
+
+    fn business_days_between(start: DateTime<Utc>, end: DateTime<Utc>) -> CelResult<i64> {
+        super::business_days_in(start, end, &Tz::UTC, Vec::new())
+    }
+
+    fn business_days_between(
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        timezone: String,
+    ) -> CelResult<i64> {
+        super::business_days_in(start, end, &parse_timezone(&timezone)?, Vec::new())
+    }
+
+    fn business_days_between(
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        holidays: Vec<CelValue>,
+    ) -> CelResult<i64> {
+        super::business_days_in(start, end, &Tz::UTC, holidays)
+    }
+
+    fn business_days_between(
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        timezone: String,
+        holidays: Vec<CelValue>,
+    ) -> CelResult<i64> {
+        super::business_days_in(start, end, &parse_timezone(&timezone)?, holidays)
+    }
+}
+
+#[dispatch]
+mod next_day_of_week {
+    use chrono::{DateTime, Utc};
+    use chrono_tz::Tz;
+
+    use super::super::helpers::parse_timezone;
+    use crate::{CelResult, CelValue};
+
+    fn next_day_of_week(this: DateTime<Utc>, day: i64) -> CelResult<DateTime<Utc>> {
+        super::next_day_of_week_in(this, day, &Tz::UTC)
+    }
+
+    fn next_day_of_week(
+        this: DateTime<Utc>,
+        day: i64,
+        timezone: String,
+    ) -> CelResult<DateTime<Utc>> {
+        super::next_day_of_week_in(this, day, &parse_timezone(&timezone)?)
+    }
+}
+
+#[dispatch]
+mod end_of_month {
+    use chrono::{DateTime, Utc};
+    use chrono_tz::Tz;
+
+    use super::super::helpers::parse_timezone;
+    use crate::{CelResult, CelValue};
+
+    fn end_of_month(this: DateTime<Utc>) -> CelResult<DateTime<Utc>> {
+        super::end_of_month_in(this, &Tz::UTC)
+    }
+
+    fn end_of_month(this: DateTime<Utc>, timezone: String) -> CelResult<DateTime<Utc>> {
+        super::end_of_month_in(this, &parse_timezone(&timezone)?)
+    }
+}
+
+fn local_date(this: DateTime<Utc>, tz: &Tz) -> NaiveDate {
+    this.with_timezone(tz).date_naive()
+}
+
+fn is_weekend_day(date: NaiveDate) -> bool {
+    matches!(date.weekday(), Weekday::Sat | Weekday::Sun)
+}
+
+/// The business days from the day of `start` up to but not including the day of
+/// `end`, negative when `end` is earlier.
+fn business_days_in(
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    tz: &Tz,
+    holidays: Vec<CelValue>,
+) -> CelResult<i64> {
+    let holidays = holidays
+        .into_iter()
+        .map(|day| holiday_date(day, tz))
+        .collect::<CelResult<BTreeSet<_>>>()?;
+
+    let (start, end) = (local_date(start, tz), local_date(end, tz));
+
+    Ok(if end < start {
+        -business_days_from(end, start, &holidays)
+    } else {
+        business_days_from(start, end, &holidays)
+    })
+}
+
+fn business_days_from(start: NaiveDate, end: NaiveDate, holidays: &BTreeSet<NaiveDate>) -> i64 {
+    // Every full week has five business days, only the days left over need checking
+    let weeks = (end - start).num_days() / 7;
+    let mut count = weeks * 5;
+
+    let mut day = start + Duration::days(weeks * 7);
+    while day < end {
+        if !is_weekend_day(day) {
+            count += 1;
+        }
+        day += Duration::days(1);
+    }
+
+    let closed = holidays
+        .range(start..end)
+        .filter(|day| !is_weekend_day(**day))
+        .count();
+
+    count - closed as i64
+}
+
+/// Holidays are `YYYY-MM-DD` strings or timestamps, which fall on their day in `tz`.
+fn holiday_date(day: CelValue, tz: &Tz) -> CelResult<NaiveDate> {
+    match CelZonedTimestamp::unzone(day) {
+        CelValue::String(s) => NaiveDate::parse_from_str(&s, "%Y-%m-%d")
+            .map_err(|_| CelError::value(&format!("Holiday '{}' is not a YYYY-MM-DD date", s))),
+        CelValue::TimeStamp(ts) => Ok(local_date(ts, tz)),
+        other => Err(CelError::value(&format!(
+            "Holidays must be dates or timestamps, found {}",
+            other.as_type()
+        ))),
+    }
+}
+
+/// Midnight of the first day after `this` falling on `day`, `0` being Sunday like
+/// `getDayOfWeek()`.
+fn next_day_of_week_in(this: DateTime<Utc>, day: i64, tz: &Tz) -> CelResult<DateTime<Utc>> {
+    if !(0..7).contains(&day) {
+        return Err(CelError::argument(&format!(
+            "Day of week must be 0 (Sunday) through 6 (Saturday), found {}",
+            day
+        )));
+    }
+
+    let date = local_date(this, tz);
+    let ahead = (day + 6 - date.weekday().num_days_from_sunday() as i64) % 7 + 1;
+    let next = date
+        .checked_add_days(Days::new(ahead as u64))
+        .ok_or_else(|| CelError::overflow("timestamp out of range"))?;
+
+    from_local(next.and_time(NaiveTime::MIN), tz)
+}
+
+/// The last instant of the month containing `this` as seen in `tz`.
+fn end_of_month_in(this: DateTime<Utc>, tz: &Tz) -> CelResult<DateTime<Utc>> {
+    let next_month = local_date(this, tz)
+        .with_day(1)
+        .and_then(|d| d.checked_add_months(Months::new(1)))
+        .ok_or_else(|| CelError::overflow("timestamp out of range"))?;
+
+    checked(from_local(next_month.and_time(NaiveTime::MIN), tz)? - Duration::nanoseconds(1))
+}
@@ -0,0 +1,77 @@
+//! The components of a duration as it would be written out in days, hours, minutes and
+//! seconds, unlike `getHours()` and friends which give totals. Components carry the
+//! sign of the duration.
+use crate::macros::dispatch;
+
+pub use get_days_part::dispatch as get_days_part;
+pub use get_hours_part::dispatch as get_hours_part;
+pub use get_milliseconds_part::dispatch as get_milliseconds_part;
+pub use get_minutes_part::dispatch as get_minutes_part;
+pub use get_nanoseconds_part::dispatch as get_nanoseconds_part;
+pub use get_seconds_part::dispatch as get_seconds_part;
+
+#[dispatch]
+mod get_days_part {
+    use chrono::Duration;
+
+    use crate::CelValue;
+
+    fn get_days_part(this: Duration) -> i64 {
+        this.num_days()
+    }
+}
+
+#[dispatch]
+mod get_hours_part {
+    use chrono::Duration;
+
+    use crate::CelValue;
+
+    fn get_hours_part(this: Duration) -> i64 {
+        this.num_hours() % 24
+    }
+}
+
+#[dispatch]
+mod get_minutes_part {
+    use chrono::Duration;
+
+    use crate::CelValue;
+
+    fn get_minutes_part(this: Duration) -> i64 {
+        this.num_minutes() % 60
+    }
+}
+
+#[dispatch]
+mod get_seconds_part {
+    use chrono::Duration;
+
+    use crate::CelValue;
+
+    fn get_seconds_part(this: Duration) -> i64 {
+        this.num_seconds() % 60
+    }
+}
+
+#[dispatch]
+mod get_milliseconds_part {
+    use chrono::Duration;
+
+    use crate::CelValue;
+
+    fn get_milliseconds_part(this: Duration) -> i64 {
+        this.subsec_nanos() as i64 / 1_000_000
+    }
+}
+
+#[dispatch]
+mod get_nanoseconds_part {
+    use chrono::Duration;
+
+    use crate::CelValue;
+
+    fn get_nanoseconds_part(this: Duration) -> i64 {
+        this.subsec_nanos() as i64
+    }
+}
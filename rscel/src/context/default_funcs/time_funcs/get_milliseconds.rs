@@ -19,6 +19,6 @@ mod methods {
     }
 
     fn get_milliseconds(this: Duration) -> i64 {
-        this.subsec_nanos() as i64 / 1000000i64
+        this.num_milliseconds()
     }
 }
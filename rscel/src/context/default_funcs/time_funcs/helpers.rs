@@ -1,14 +1,83 @@
 use std::str::FromStr;
 
-use chrono::{DateTime, Utc};
+use chrono::{
+    format::{Item, StrftimeItems},
+    DateTime, Duration, NaiveDate, NaiveDateTime, NaiveTime, Offset, TimeZone, Utc,
+};
 use chrono_tz::Tz;
 
-use crate::{CelError, CelResult};
+use crate::{types::cel_time::timestamp_in_range, CelError, CelResult};
 
 pub fn get_adjusted_datetime(this: DateTime<Utc>, timezone: String) -> CelResult<DateTime<Tz>> {
-    if let Ok(tz) = Tz::from_str(&timezone) {
-        Ok(this.with_timezone(&tz))
+    Ok(this.with_timezone(&parse_timezone(&timezone)?))
+}
+
+pub fn parse_timezone(timezone: &str) -> CelResult<Tz> {
+    Tz::from_str(timezone).map_err(|_| CelError::argument("Failed to parse timezone"))
+}
+
+/// The instant a wall clock time in `tz` refers to, the earlier one when the clocks
+/// were turned back. Times skipped when the clocks were turned forward are moved forward
+/// by the length of the gap, so 02:30 on a night going from 02:00 to 03:00 is 03:30.
+pub fn from_local(naive: NaiveDateTime, tz: &Tz) -> CelResult<DateTime<Utc>> {
+    match tz.from_local_datetime(&naive).earliest() {
+        Some(local) => checked(local.to_utc()),
+        None => {
+            // Read the time with the offset in effect before the gap
+            let before = naive.checked_sub_signed(Duration::days(1)).unwrap_or(naive);
+            let offset = tz.offset_from_utc_datetime(&before).fix();
+
+            checked((naive - offset).and_utc())
+        }
+    }
+}
+
+/// Limits computed timestamps to the years 0001 through 9999.
+pub fn checked(ts: DateTime<Utc>) -> CelResult<DateTime<Utc>> {
+    if timestamp_in_range(&ts) {
+        Ok(ts)
+    } else {
+        Err(CelError::overflow("timestamp out of range"))
+    }
+}
+
+fn strftime_items(pattern: &str) -> CelResult<StrftimeItems<'_>> {
+    let items = StrftimeItems::new(pattern);
+
+    if items.clone().any(|item| matches!(item, Item::Error)) {
+        Err(CelError::argument(&format!(
+            "Invalid strftime format specifier '{}'",
+            pattern
+        )))
     } else {
-        Err(CelError::argument("Failed to parse timezone"))
+        Ok(items)
     }
 }
+
+/// Formats `this` with strftime directives as seen in `tz`.
+pub fn format_in(this: DateTime<Utc>, pattern: &str, tz: &Tz) -> CelResult<String> {
+    let items = strftime_items(pattern)?;
+
+    Ok(this.with_timezone(tz).format_with_items(items).to_string())
+}
+
+/// Parses `s` with strftime directives. Times without an offset are wall clock times
+/// in `tz`, and a pattern with only a date gives its midnight.
+pub fn parse_in(s: &str, pattern: &str, tz: &Tz) -> CelResult<DateTime<Utc>> {
+    strftime_items(pattern)?;
+
+    if let Ok(ts) = DateTime::parse_from_str(s, pattern) {
+        return checked(ts.to_utc());
+    }
+
+    let naive = NaiveDateTime::parse_from_str(s, pattern)
+        .or_else(|_| NaiveDate::parse_from_str(s, pattern).map(|d| d.and_time(NaiveTime::MIN)))
+        .map_err(|err| {
+            CelError::value(&format!(
+                "Failed to parse '{}' with '{}': {}",
+                s, pattern, err
+            ))
+        })?;
+
+    from_local(naive, tz)
+}
@@ -0,0 +1,20 @@
+use crate::macros::dispatch;
+
+pub use methods::dispatch as start_of_week;
+
+#[dispatch]
+mod methods {
+    use chrono::{DateTime, Utc};
+    use chrono_tz::Tz;
+
+    use super::super::{helpers::parse_timezone, truncate::truncate_in};
+    use crate::{CelResult, CelValue};
+
+    fn start_of_week(this: DateTime<Utc>) -> CelResult<DateTime<Utc>> {
+        truncate_in(this, "week", &Tz::UTC)
+    }
+
+    fn start_of_week(this: DateTime<Utc>, timezone: String) -> CelResult<DateTime<Utc>> {
+        truncate_in(this, "week", &parse_timezone(&timezone)?)
+    }
+}
@@ -0,0 +1,14 @@
+use crate::macros::dispatch;
+
+pub use methods::dispatch as to_epoch_seconds;
+
+#[dispatch]
+mod methods {
+    use chrono::{DateTime, Utc};
+
+    use crate::CelValue;
+
+    fn to_epoch_seconds(this: DateTime<Utc>) -> i64 {
+        this.timestamp()
+    }
+}
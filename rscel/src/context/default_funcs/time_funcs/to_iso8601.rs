@@ -0,0 +1,14 @@
+use crate::macros::dispatch;
+
+pub use methods::dispatch as to_iso8601;
+
+#[dispatch]
+mod methods {
+    use chrono::Duration;
+
+    use crate::{types::cel_time::format_iso8601_duration, CelValue};
+
+    fn to_iso8601(this: Duration) -> String {
+        format_iso8601_duration(&this)
+    }
+}
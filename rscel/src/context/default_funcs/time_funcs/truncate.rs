@@ -0,0 +1,55 @@
+use chrono::{DateTime, Datelike, Days, NaiveDate, NaiveTime, Timelike, Utc};
+use chrono_tz::Tz;
+
+use crate::{macros::dispatch, CelError, CelResult};
+
+use super::helpers::from_local;
+
+pub use methods::dispatch as truncate;
+
+#[dispatch]
+mod methods {
+    use chrono::{DateTime, Utc};
+    use chrono_tz::Tz;
+
+    use super::super::helpers::parse_timezone;
+    use crate::{CelResult, CelValue};
+
+    fn truncate(this: DateTime<Utc>, unit: String) -> CelResult<DateTime<Utc>> {
+        super::truncate_in(this, &unit, &Tz::UTC)
+    }
+
+    fn truncate(this: DateTime<Utc>, unit: String, timezone: String) -> CelResult<DateTime<Utc>> {
+        super::truncate_in(this, &unit, &parse_timezone(&timezone)?)
+    }
+}
+
+/// The start of the `unit` containing `this` as seen in `tz`, weeks start on Monday.
+pub fn truncate_in(this: DateTime<Utc>, unit: &str, tz: &Tz) -> CelResult<DateTime<Utc>> {
+    let local = this.with_timezone(tz).naive_local();
+    let date = local.date();
+    let time = local.time();
+
+    let start = match unit {
+        "year" => NaiveDate::from_ymd_opt(date.year(), 1, 1).map(|d| d.and_time(NaiveTime::MIN)),
+        "month" => date.with_day(1).map(|d| d.and_time(NaiveTime::MIN)),
+        "week" => date
+            .checked_sub_days(Days::new(date.weekday().num_days_from_monday() as u64))
+            .map(|d| d.and_time(NaiveTime::MIN)),
+        "day" => Some(date.and_time(NaiveTime::MIN)),
+        "hour" => date.and_hms_opt(time.hour(), 0, 0),
+        "minute" => date.and_hms_opt(time.hour(), time.minute(), 0),
+        "second" => date.and_hms_opt(time.hour(), time.minute(), time.second()),
+        other => {
+            return Err(CelError::argument(&format!(
+                "Unknown unit '{}', expected year, month, week, day, hour, minute or second",
+                other
+            )))
+        }
+    };
+
+    match start {
+        Some(start) => from_local(start, tz),
+        None => Err(CelError::overflow("timestamp out of range")),
+    }
+}
@@ -0,0 +1,133 @@
+//! Zoned timestamps, `ts.inZone(tz)`, and the time functions that use their zone.
+
+use crate::{types::CelZonedTimestamp, CelError, CelValue, CelValueDyn};
+
+use super::super::string;
+use super::helpers::parse_timezone;
+use super::{
+    add_months, calendar, get_date, get_day_of_month, get_day_of_week, get_day_of_year,
+    get_full_year, get_hours, get_milliseconds, get_minutes, get_month, get_seconds, start_of_week,
+    to_epoch_seconds, truncate,
+};
+
+pub fn in_zone(this: CelValue, args: Vec<CelValue>) -> CelValue {
+    let zone = match <[CelValue; 1]>::try_from(args) {
+        Ok([CelValue::String(zone)]) => zone,
+        Ok([CelValue::Err(err)]) => return err.into(),
+        _ => return CelValue::from_err(CelError::argument("inZone() expects a timezone string")),
+    };
+
+    let zone = match parse_timezone(&zone) {
+        Ok(zone) => zone,
+        Err(err) => return err.into(),
+    };
+
+    match CelZonedTimestamp::unzone(this) {
+        CelValue::TimeStamp(instant) => CelZonedTimestamp::new(instant, zone).into(),
+        other => not_timestamp("inZone", &other),
+    }
+}
+
+pub fn to_utc(this: CelValue, args: Vec<CelValue>) -> CelValue {
+    if !args.is_empty() {
+        return CelValue::from_err(CelError::argument("toUTC() expects no arguments"));
+    }
+
+    match CelZonedTimestamp::unzone(this) {
+        ts @ CelValue::TimeStamp(_) => ts,
+        other => not_timestamp("toUTC", &other),
+    }
+}
+
+pub fn get_zone(this: CelValue, args: Vec<CelValue>) -> CelValue {
+    if !args.is_empty() {
+        return CelValue::from_err(CelError::argument("getZone() expects no arguments"));
+    }
+
+    match (CelZonedTimestamp::from_cel_value(&this), &this) {
+        (Some(zoned), _) => zoned.zone().name().into(),
+        (None, CelValue::TimeStamp(_)) => "UTC".into(),
+        (None, other) => not_timestamp("getZone", other),
+    }
+}
+
+fn not_timestamp(func: &str, this: &CelValue) -> CelValue {
+    CelValue::from_err(CelError::value(&format!(
+        "{}() is not supported on {}",
+        func,
+        this.as_type()
+    )))
+}
+
+/// Calls `func` on the instant of a zoned `this`, appending its zone as the timezone
+/// argument when the call has `arity` arguments. Timestamp results stay in the zone.
+fn with_zone(
+    this: CelValue,
+    mut args: Vec<CelValue>,
+    arity: Option<usize>,
+    func: fn(CelValue, Vec<CelValue>) -> CelValue,
+) -> CelValue {
+    match CelZonedTimestamp::from_cel_value(&this).copied() {
+        Some(zoned) => {
+            if Some(args.len()) == arity {
+                args.push(zoned.zone().name().into());
+            }
+
+            CelZonedTimestamp::rezone(func(zoned.instant().into(), args), zoned.zone())
+        }
+        None => func(this, args),
+    }
+}
+
+macro_rules! zone_aware {
+    ($($name:ident $(($arity:literal))? => $func:path,)*) => {
+        $(
+            pub fn $name(this: CelValue, args: Vec<CelValue>) -> CelValue {
+                with_zone(this, args, zone_aware!(@arity $($arity)?), $func)
+            }
+        )*
+    };
+    (@arity $arity:literal) => { Some($arity) };
+    (@arity) => { None };
+}
+
+zone_aware! {
+    get_date(0) => get_date::get_date,
+    get_day_of_month(0) => get_day_of_month::get_day_of_month,
+    get_day_of_week(0) => get_day_of_week::get_day_of_week,
+    get_day_of_year(0) => get_day_of_year::get_day_of_year,
+    get_full_year(0) => get_full_year::get_full_year,
+    get_hours(0) => get_hours::get_hours,
+    get_milliseconds(0) => get_milliseconds::get_milliseconds,
+    get_minutes(0) => get_minutes::get_minutes,
+    get_month(0) => get_month::get_month,
+    get_seconds(0) => get_seconds::get_seconds,
+    add_months(1) => add_months::add_months,
+    start_of_week(0) => start_of_week::start_of_week,
+    truncate(1) => truncate::truncate,
+    format(1) => string::format::format,
+    to_epoch_seconds => to_epoch_seconds::to_epoch_seconds,
+    is_weekend(0) => calendar::is_weekend,
+    next_day_of_week(1) => calendar::next_day_of_week,
+    end_of_month(0) => calendar::end_of_month,
+}
+
+/// `businessDaysBetween()` counts days in the zone of a zoned start unless given a
+/// timezone.
+pub fn business_days_between(this: CelValue, mut args: Vec<CelValue>) -> CelValue {
+    let zone = args
+        .first()
+        .and_then(CelZonedTimestamp::from_cel_value)
+        .map(|zoned| zoned.zone());
+
+    if let Some(zone) = zone {
+        if args.len() >= 2 && !matches!(args.get(2), Some(CelValue::String(_))) {
+            args.insert(2, zone.name().into());
+        }
+    }
+
+    calendar::business_days_between(
+        this,
+        args.into_iter().map(CelZonedTimestamp::unzone).collect(),
+    )
+}
@@ -1,11 +1,34 @@
 use rscel_macro::dispatch;
 
+use std::collections::HashMap;
+
 pub use methods::dispatch as uom_convert;
 
-use crate::{CelError, CelResult};
-use uom::si::f64::{Mass, ThermodynamicTemperature, Velocity, Volume};
-use uom::si::mass::{gram, kilogram, milligram, ounce, pound, slug, ton};
+use crate::{CelError, CelResult, CelValue};
+use uom::si::area::{hectare, square_foot, square_kilometer, square_meter, square_mile};
+use uom::si::energy::{
+    btu_it, calorie, joule, kilocalorie, kilojoule, kilowatt_hour, megajoule, watt_hour,
+};
+use uom::si::f64::{
+    Area, Energy, Frequency, Information, Length, Mass, Power, Pressure, ThermodynamicTemperature,
+    Time, Velocity, Volume,
+};
+use uom::si::frequency::{cycle_per_minute, gigahertz, hertz, kilohertz, megahertz};
+use uom::si::information::{
+    bit, byte, gibibyte, gigabit, gigabyte, kibibyte, kilobit, kilobyte, mebibyte, megabit,
+    megabyte, tebibyte, terabyte,
+};
+use uom::si::length::{
+    centimeter, foot, inch, kilometer, meter, mile, millimeter, nautical_mile, yard,
+};
+use uom::si::mass::{gram, kilogram, milligram, pound, slug, ton};
+use uom::si::power::{horsepower, kilowatt, megawatt, watt};
+use uom::si::pressure::{
+    atmosphere, bar, kilopascal, megapascal, millibar, millimeter_of_mercury, pascal,
+    pound_force_per_square_inch,
+};
 use uom::si::thermodynamic_temperature::{degree_celsius, degree_fahrenheit, kelvin};
+use uom::si::time::{day, hour, millisecond, minute, second};
 use uom::si::velocity::{
     foot_per_second, kilometer_per_hour, knot, meter_per_second, mile_per_hour,
 };
@@ -14,7 +37,29 @@ use uom::si::volume::{
     pint_liquid, quart_dry, quart_liquid, tablespoon, teaspoon,
 };
 
+/// Symbols that mean another unit in another case, `MW` is a megawatt while `mW` is a
+/// milliwatt and `B` is a byte while `b` is a bit. These only match as written, other
+/// spellings of them are rejected rather than guessed at.
+const CASE_SENSITIVE_SYMBOLS: &[(&str, Unit)] = &[
+    ("B", Unit::Data(DataUnit::Byte)),
+    ("kB", Unit::Data(DataUnit::Kilobyte)),
+    ("KB", Unit::Data(DataUnit::Kilobyte)),
+    ("MB", Unit::Data(DataUnit::Megabyte)),
+    ("GB", Unit::Data(DataUnit::Gigabyte)),
+    ("TB", Unit::Data(DataUnit::Terabyte)),
+    ("Mbit", Unit::Data(DataUnit::Megabit)),
+    ("MPa", Unit::Pressure(PressureUnit::Megapascal)),
+    ("MJ", Unit::Energy(EnergyUnit::Megajoule)),
+    ("MW", Unit::Power(PowerUnit::Megawatt)),
+    ("MHz", Unit::Frequency(FrequencyUnit::Megahertz)),
+];
+
 const STONE_IN_POUNDS: f64 = 14.0;
+// uom's ounce is rounded to 28.34952 g, the avoirdupois ounce is exactly 1/16 lb
+const OUNCES_PER_POUND: f64 = 16.0;
+const DAYS_PER_WEEK: f64 = 7.0;
+// The international acre, uom's is the US survey acre
+const ACRE_IN_SQUARE_FEET: f64 = 43_560.0;
 
 #[dispatch]
 pub mod methods {
@@ -39,42 +84,288 @@ fn uom_convert_internal(base: f64, from: &str, to: &str) -> CelResult<f64> {
     let to_unit = Unit::from_str(to)
         .ok_or_else(|| CelError::argument(&format!("Unsupported unit '{}'.", to)))?;
 
-    match (from_unit, to_unit) {
-        (Unit::Mass(from_mass), Unit::Mass(to_mass)) => {
-            let mass = from_mass.into_mass(base);
-            Ok(to_mass.from_mass(mass))
-        }
-        (Unit::Volume(from_volume), Unit::Volume(to_volume)) => {
-            let volume = from_volume.into_volume(base);
-            Ok(to_volume.from_volume(volume))
-        }
-        (Unit::Speed(from_speed), Unit::Speed(to_speed)) => {
-            let speed = from_speed.into_velocity(base);
-            Ok(to_speed.from_velocity(speed))
-        }
-        (Unit::Temperature(from_temp), Unit::Temperature(to_temp)) => {
-            let temp = from_temp.into_temperature(base);
-            Ok(to_temp.from_temperature(temp))
-        }
-        _ => Err(CelError::argument(&format!(
+    if from_unit.dimension() != to_unit.dimension() {
+        return Err(CelError::argument(&format!(
             "Cannot convert units '{}' -> '{}'.",
             from, to
-        ))),
+        )));
+    }
+
+    Ok(to_unit.from_si(from_unit.to_si(base)))
+}
+
+/// The symbols of the supported units by category, `uomUnits()`.
+pub fn uom_units(_this: CelValue, args: Vec<CelValue>) -> CelValue {
+    if !args.is_empty() {
+        return CelValue::from_err(CelError::argument("uomUnits() expects no arguments"));
+    }
+
+    Unit::categories()
+        .into_iter()
+        .map(|(category, units)| {
+            let symbols = units.into_iter().map(|u| u.symbol().into()).collect();
+            (category.to_owned(), CelValue::from_list(symbols))
+        })
+        .collect::<HashMap<_, _>>()
+        .into()
+}
+
+/// The exponents of the SI base quantities a unit measures, `m/s` being length 1 and
+/// time -1. Data sizes count information as a base quantity of their own.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct Dimension {
+    length: i8,
+    mass: i8,
+    time: i8,
+    temperature: i8,
+    information: i8,
+}
+
+impl Dimension {
+    pub(crate) const NONE: Dimension = Dimension::new(0, 0, 0, 0, 0);
+    pub(crate) const TIME: Dimension = Dimension::new(0, 0, 1, 0, 0);
+    const MASS: Dimension = Dimension::new(0, 1, 0, 0, 0);
+    const LENGTH: Dimension = Dimension::new(1, 0, 0, 0, 0);
+    const AREA: Dimension = Dimension::new(2, 0, 0, 0, 0);
+    const VOLUME: Dimension = Dimension::new(3, 0, 0, 0, 0);
+    const VELOCITY: Dimension = Dimension::new(1, 0, -1, 0, 0);
+    const TEMPERATURE: Dimension = Dimension::new(0, 0, 0, 1, 0);
+    const INFORMATION: Dimension = Dimension::new(0, 0, 0, 0, 1);
+    const PRESSURE: Dimension = Dimension::new(-1, 1, -2, 0, 0);
+    const ENERGY: Dimension = Dimension::new(2, 1, -2, 0, 0);
+    const POWER: Dimension = Dimension::new(2, 1, -3, 0, 0);
+    const FREQUENCY: Dimension = Dimension::new(0, 0, -1, 0, 0);
+
+    const fn new(length: i8, mass: i8, time: i8, temperature: i8, information: i8) -> Dimension {
+        Dimension {
+            length,
+            mass,
+            time,
+            temperature,
+            information,
+        }
+    }
+
+    pub(crate) fn mul(self, rhs: Dimension) -> Dimension {
+        Dimension::new(
+            self.length + rhs.length,
+            self.mass + rhs.mass,
+            self.time + rhs.time,
+            self.temperature + rhs.temperature,
+            self.information + rhs.information,
+        )
+    }
+
+    pub(crate) fn div(self, rhs: Dimension) -> Dimension {
+        Dimension::new(
+            self.length - rhs.length,
+            self.mass - rhs.mass,
+            self.time - rhs.time,
+            self.temperature - rhs.temperature,
+            self.information - rhs.information,
+        )
+    }
+
+    /// The SI base units, and bytes, raised to their exponents, like `kg*m/s^2`.
+    fn symbol(self) -> String {
+        let bases = [
+            ("kg", self.mass),
+            ("m", self.length),
+            ("s", self.time),
+            ("K", self.temperature),
+            ("B", self.information),
+        ];
+
+        let part = |(base, exp): &(&str, i8)| match exp.abs() {
+            1 => base.to_string(),
+            exp => format!("{}^{}", base, exp),
+        };
+        let numer: Vec<_> = bases.iter().filter(|(_, exp)| *exp > 0).map(part).collect();
+        let denom: Vec<_> = bases.iter().filter(|(_, exp)| *exp < 0).map(part).collect();
+
+        let mut res = if numer.is_empty() {
+            "1".to_owned()
+        } else {
+            numer.join("*")
+        };
+        match denom.len() {
+            0 => {}
+            1 => res.push_str(&format!("/{}", denom[0])),
+            _ => res.push_str(&format!("/({})", denom.join("*"))),
+        }
+
+        res
     }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
-enum Unit {
+pub(crate) enum Unit {
     Mass(MassUnit),
     Volume(VolumeUnit),
     Speed(SpeedUnit),
     Temperature(TemperatureUnit),
+    Length(LengthUnit),
+    Area(AreaUnit),
+    Time(TimeUnit),
+    Data(DataUnit),
+    Pressure(PressureUnit),
+    Energy(EnergyUnit),
+    Power(PowerUnit),
+    Frequency(FrequencyUnit),
+    /// The coherent SI unit of a dimension without a named unit, like `kg*m/s`.
+    Si(Dimension),
 }
 
 impl Unit {
-    fn from_str(unit: &str) -> Option<Self> {
-        let normalized = unit.trim().to_lowercase();
-        let normalized = normalized.trim_matches('°');
+    /// The coherent SI unit of `dimension`, named when the dimension has one.
+    pub(crate) fn si(dimension: Dimension) -> Unit {
+        match dimension {
+            Dimension::MASS => Unit::Mass(MassUnit::Kilogram),
+            Dimension::VOLUME => Unit::Volume(VolumeUnit::CubicMeter),
+            Dimension::VELOCITY => Unit::Speed(SpeedUnit::MeterPerSecond),
+            Dimension::TEMPERATURE => Unit::Temperature(TemperatureUnit::Kelvin),
+            Dimension::LENGTH => Unit::Length(LengthUnit::Meter),
+            Dimension::AREA => Unit::Area(AreaUnit::SquareMeter),
+            Dimension::TIME => Unit::Time(TimeUnit::Second),
+            Dimension::INFORMATION => Unit::Data(DataUnit::Byte),
+            Dimension::PRESSURE => Unit::Pressure(PressureUnit::Pascal),
+            Dimension::ENERGY => Unit::Energy(EnergyUnit::Joule),
+            Dimension::POWER => Unit::Power(PowerUnit::Watt),
+            Dimension::FREQUENCY => Unit::Frequency(FrequencyUnit::Hertz),
+            other => Unit::Si(other),
+        }
+    }
+
+    pub(crate) fn dimension(self) -> Dimension {
+        match self {
+            Unit::Mass(_) => Dimension::MASS,
+            Unit::Volume(_) => Dimension::VOLUME,
+            Unit::Speed(_) => Dimension::VELOCITY,
+            Unit::Temperature(_) => Dimension::TEMPERATURE,
+            Unit::Length(_) => Dimension::LENGTH,
+            Unit::Area(_) => Dimension::AREA,
+            Unit::Time(_) => Dimension::TIME,
+            Unit::Data(_) => Dimension::INFORMATION,
+            Unit::Pressure(_) => Dimension::PRESSURE,
+            Unit::Energy(_) => Dimension::ENERGY,
+            Unit::Power(_) => Dimension::POWER,
+            Unit::Frequency(_) => Dimension::FREQUENCY,
+            Unit::Si(dimension) => dimension,
+        }
+    }
+
+    /// Whether the unit's zero is not the zero of its SI unit, degrees Celsius and
+    /// Fahrenheit.
+    pub(crate) fn has_offset(self) -> bool {
+        matches!(
+            self,
+            Unit::Temperature(TemperatureUnit::Celsius | TemperatureUnit::Fahrenheit)
+        )
+    }
+
+    /// `value` of this unit in the coherent SI unit of its dimension.
+    pub(crate) fn to_si(self, value: f64) -> f64 {
+        match self {
+            Unit::Mass(unit) => unit.into_mass(value).get::<kilogram>(),
+            Unit::Volume(unit) => unit.into_volume(value).get::<cubic_meter>(),
+            Unit::Speed(unit) => unit.into_velocity(value).get::<meter_per_second>(),
+            Unit::Temperature(unit) => unit.into_temperature(value).get::<kelvin>(),
+            Unit::Length(unit) => unit.into_length(value).get::<meter>(),
+            Unit::Area(unit) => unit.into_area(value).get::<square_meter>(),
+            Unit::Time(unit) => unit.into_time(value).get::<second>(),
+            Unit::Data(unit) => unit.into_information(value).get::<byte>(),
+            Unit::Pressure(unit) => unit.into_pressure(value).get::<pascal>(),
+            Unit::Energy(unit) => unit.into_energy(value).get::<joule>(),
+            Unit::Power(unit) => unit.into_power(value).get::<watt>(),
+            Unit::Frequency(unit) => unit.into_frequency(value).get::<hertz>(),
+            Unit::Si(_) => value,
+        }
+    }
+
+    /// `si`, in the coherent SI unit of this unit's dimension, in this unit.
+    pub(crate) fn from_si(self, si: f64) -> f64 {
+        match self {
+            Unit::Mass(unit) => unit.from_mass(Mass::new::<kilogram>(si)),
+            Unit::Volume(unit) => unit.from_volume(Volume::new::<cubic_meter>(si)),
+            Unit::Speed(unit) => unit.from_velocity(Velocity::new::<meter_per_second>(si)),
+            Unit::Temperature(unit) => {
+                unit.from_temperature(ThermodynamicTemperature::new::<kelvin>(si))
+            }
+            Unit::Length(unit) => unit.from_length(Length::new::<meter>(si)),
+            Unit::Area(unit) => unit.from_area(Area::new::<square_meter>(si)),
+            Unit::Time(unit) => unit.from_time(Time::new::<second>(si)),
+            Unit::Data(unit) => unit.from_information(Information::new::<byte>(si)),
+            Unit::Pressure(unit) => unit.from_pressure(Pressure::new::<pascal>(si)),
+            Unit::Energy(unit) => unit.from_energy(Energy::new::<joule>(si)),
+            Unit::Power(unit) => unit.from_power(Power::new::<watt>(si)),
+            Unit::Frequency(unit) => unit.from_frequency(Frequency::new::<hertz>(si)),
+            Unit::Si(_) => si,
+        }
+    }
+
+    /// The short name of the unit, which `from_str` accepts for all but SI units.
+    pub(crate) fn symbol(self) -> String {
+        match self {
+            Unit::Mass(unit) => unit.symbol().to_owned(),
+            Unit::Volume(unit) => unit.symbol().to_owned(),
+            Unit::Speed(unit) => unit.symbol().to_owned(),
+            Unit::Temperature(unit) => unit.symbol().to_owned(),
+            Unit::Length(unit) => unit.symbol().to_owned(),
+            Unit::Area(unit) => unit.symbol().to_owned(),
+            Unit::Time(unit) => unit.symbol().to_owned(),
+            Unit::Data(unit) => unit.symbol().to_owned(),
+            Unit::Pressure(unit) => unit.symbol().to_owned(),
+            Unit::Energy(unit) => unit.symbol().to_owned(),
+            Unit::Power(unit) => unit.symbol().to_owned(),
+            Unit::Frequency(unit) => unit.symbol().to_owned(),
+            Unit::Si(dimension) => dimension.symbol(),
+        }
+    }
+
+    /// Every named unit, by category.
+    pub(crate) fn categories() -> Vec<(&'static str, Vec<Unit>)> {
+        fn units<T: Copy>(all: &[T], unit: fn(T) -> Unit) -> Vec<Unit> {
+            all.iter().copied().map(unit).collect()
+        }
+
+        vec![
+            ("mass", units(MassUnit::ALL, Unit::Mass)),
+            ("volume", units(VolumeUnit::ALL, Unit::Volume)),
+            ("speed", units(SpeedUnit::ALL, Unit::Speed)),
+            (
+                "temperature",
+                units(TemperatureUnit::ALL, Unit::Temperature),
+            ),
+            ("length", units(LengthUnit::ALL, Unit::Length)),
+            ("area", units(AreaUnit::ALL, Unit::Area)),
+            ("time", units(TimeUnit::ALL, Unit::Time)),
+            ("data", units(DataUnit::ALL, Unit::Data)),
+            ("pressure", units(PressureUnit::ALL, Unit::Pressure)),
+            ("energy", units(EnergyUnit::ALL, Unit::Energy)),
+            ("power", units(PowerUnit::ALL, Unit::Power)),
+            ("frequency", units(FrequencyUnit::ALL, Unit::Frequency)),
+        ]
+    }
+
+    /// Parses a unit by symbol or name. Names and most symbols are case-insensitive,
+    /// see `CASE_SENSITIVE_SYMBOLS` for those that aren't.
+    pub(crate) fn from_str(unit: &str) -> Option<Self> {
+        let trimmed = unit.trim().trim_matches('°');
+
+        for (symbol, unit) in CASE_SENSITIVE_SYMBOLS {
+            if *symbol == trimmed {
+                return Some(*unit);
+            }
+        }
+        if CASE_SENSITIVE_SYMBOLS
+            .iter()
+            .any(|(symbol, _)| symbol.eq_ignore_ascii_case(trimmed))
+        {
+            return None;
+        }
+
+        let normalized = trimmed.to_lowercase();
+        let normalized = normalized.as_str();
 
         Some(match normalized {
             "kg" | "kilogram" | "kilograms" => Unit::Mass(MassUnit::Kilogram),
@@ -128,13 +419,100 @@ impl Unit {
             "k" | "kelvin" => Unit::Temperature(TemperatureUnit::Kelvin),
             "c" | "celsius" => Unit::Temperature(TemperatureUnit::Celsius),
             "f" | "fahrenheit" => Unit::Temperature(TemperatureUnit::Fahrenheit),
+
+            "m" | "meter" | "meters" | "metre" | "metres" => Unit::Length(LengthUnit::Meter),
+            "km" | "kilometer" | "kilometers" | "kilometre" | "kilometres" => {
+                Unit::Length(LengthUnit::Kilometer)
+            }
+            "cm" | "centimeter" | "centimeters" | "centimetre" | "centimetres" => {
+                Unit::Length(LengthUnit::Centimeter)
+            }
+            "mm" | "millimeter" | "millimeters" | "millimetre" | "millimetres" => {
+                Unit::Length(LengthUnit::Millimeter)
+            }
+            "in" | "inch" | "inches" => Unit::Length(LengthUnit::Inch),
+            "ft" | "foot" | "feet" => Unit::Length(LengthUnit::Foot),
+            "yd" | "yard" | "yards" => Unit::Length(LengthUnit::Yard),
+            "mi" | "mile" | "miles" => Unit::Length(LengthUnit::Mile),
+            "nmi" | "nautical mile" | "nautical miles" | "nautical_mile" => {
+                Unit::Length(LengthUnit::NauticalMile)
+            }
+
+            "m2" | "m²" | "sq m" | "square meter" | "square meters" | "square_meter"
+            | "square metre" | "square metres" => Unit::Area(AreaUnit::SquareMeter),
+            "km2" | "km²" | "sq km" | "square kilometer" | "square kilometers"
+            | "square_kilometer" => Unit::Area(AreaUnit::SquareKilometer),
+            "ft2" | "ft²" | "sq ft" | "square foot" | "square feet" | "square_foot" => {
+                Unit::Area(AreaUnit::SquareFoot)
+            }
+            "mi2" | "mi²" | "sq mi" | "square mile" | "square miles" | "square_mile" => {
+                Unit::Area(AreaUnit::SquareMile)
+            }
+            "acre" | "acres" | "ac" => Unit::Area(AreaUnit::Acre),
+            "ha" | "hectare" | "hectares" => Unit::Area(AreaUnit::Hectare),
+
+            "s" | "sec" | "secs" | "second" | "seconds" => Unit::Time(TimeUnit::Second),
+            "ms" | "millisecond" | "milliseconds" => Unit::Time(TimeUnit::Millisecond),
+            "min" | "mins" | "minute" | "minutes" => Unit::Time(TimeUnit::Minute),
+            "h" | "hr" | "hrs" | "hour" | "hours" => Unit::Time(TimeUnit::Hour),
+            "d" | "day" | "days" => Unit::Time(TimeUnit::Day),
+            "wk" | "week" | "weeks" => Unit::Time(TimeUnit::Week),
+
+            "bit" | "bits" => Unit::Data(DataUnit::Bit),
+            "kbit" | "kilobit" | "kilobits" => Unit::Data(DataUnit::Kilobit),
+            "megabit" | "megabits" => Unit::Data(DataUnit::Megabit),
+            "gbit" | "gigabit" | "gigabits" => Unit::Data(DataUnit::Gigabit),
+            "byte" | "bytes" => Unit::Data(DataUnit::Byte),
+            "kilobyte" | "kilobytes" => Unit::Data(DataUnit::Kilobyte),
+            "megabyte" | "megabytes" => Unit::Data(DataUnit::Megabyte),
+            "gigabyte" | "gigabytes" => Unit::Data(DataUnit::Gigabyte),
+            "terabyte" | "terabytes" => Unit::Data(DataUnit::Terabyte),
+            "kib" | "kibibyte" | "kibibytes" => Unit::Data(DataUnit::Kibibyte),
+            "mib" | "mebibyte" | "mebibytes" => Unit::Data(DataUnit::Mebibyte),
+            "gib" | "gibibyte" | "gibibytes" => Unit::Data(DataUnit::Gibibyte),
+            "tib" | "tebibyte" | "tebibytes" => Unit::Data(DataUnit::Tebibyte),
+
+            "pa" | "pascal" | "pascals" => Unit::Pressure(PressureUnit::Pascal),
+            "kpa" | "kilopascal" | "kilopascals" => Unit::Pressure(PressureUnit::Kilopascal),
+            "megapascal" | "megapascals" => Unit::Pressure(PressureUnit::Megapascal),
+            "bar" | "bars" => Unit::Pressure(PressureUnit::Bar),
+            "mbar" | "millibar" | "millibars" => Unit::Pressure(PressureUnit::Millibar),
+            "psi" => Unit::Pressure(PressureUnit::Psi),
+            "atm" | "atmosphere" | "atmospheres" => Unit::Pressure(PressureUnit::Atmosphere),
+            "mmhg" | "millimeter of mercury" | "millimeters of mercury" => {
+                Unit::Pressure(PressureUnit::MillimeterOfMercury)
+            }
+
+            "j" | "joule" | "joules" => Unit::Energy(EnergyUnit::Joule),
+            "kj" | "kilojoule" | "kilojoules" => Unit::Energy(EnergyUnit::Kilojoule),
+            "megajoule" | "megajoules" => Unit::Energy(EnergyUnit::Megajoule),
+            "wh" | "watt hour" | "watt hours" | "watt_hour" => Unit::Energy(EnergyUnit::WattHour),
+            "kwh" | "kilowatt hour" | "kilowatt hours" | "kilowatt_hour" => {
+                Unit::Energy(EnergyUnit::KilowattHour)
+            }
+            "cal" | "calorie" | "calories" => Unit::Energy(EnergyUnit::Calorie),
+            "kcal" | "kilocalorie" | "kilocalories" => Unit::Energy(EnergyUnit::Kilocalorie),
+            "btu" | "btus" | "british thermal unit" | "british thermal units" => {
+                Unit::Energy(EnergyUnit::Btu)
+            }
+
+            "w" | "watt" | "watts" => Unit::Power(PowerUnit::Watt),
+            "kw" | "kilowatt" | "kilowatts" => Unit::Power(PowerUnit::Kilowatt),
+            "megawatt" | "megawatts" => Unit::Power(PowerUnit::Megawatt),
+            "hp" | "horsepower" => Unit::Power(PowerUnit::Horsepower),
+
+            "hz" | "hertz" => Unit::Frequency(FrequencyUnit::Hertz),
+            "khz" | "kilohertz" => Unit::Frequency(FrequencyUnit::Kilohertz),
+            "megahertz" => Unit::Frequency(FrequencyUnit::Megahertz),
+            "ghz" | "gigahertz" => Unit::Frequency(FrequencyUnit::Gigahertz),
+            "rpm" | "revolutions per minute" => Unit::Frequency(FrequencyUnit::Rpm),
             _ => return None,
         })
     }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
-enum MassUnit {
+pub(crate) enum MassUnit {
     Kilogram,
     Gram,
     Milligram,
@@ -146,13 +524,37 @@ enum MassUnit {
 }
 
 impl MassUnit {
+    const ALL: &'static [MassUnit] = &[
+        MassUnit::Kilogram,
+        MassUnit::Gram,
+        MassUnit::Milligram,
+        MassUnit::Pound,
+        MassUnit::Ounce,
+        MassUnit::Stone,
+        MassUnit::Ton,
+        MassUnit::Slug,
+    ];
+
+    fn symbol(self) -> &'static str {
+        match self {
+            MassUnit::Kilogram => "kg",
+            MassUnit::Gram => "g",
+            MassUnit::Milligram => "mg",
+            MassUnit::Pound => "lb",
+            MassUnit::Ounce => "oz",
+            MassUnit::Stone => "st",
+            MassUnit::Ton => "ton",
+            MassUnit::Slug => "slug",
+        }
+    }
+
     fn into_mass(self, value: f64) -> Mass {
         match self {
             MassUnit::Kilogram => Mass::new::<kilogram>(value),
             MassUnit::Gram => Mass::new::<gram>(value),
             MassUnit::Milligram => Mass::new::<milligram>(value),
             MassUnit::Pound => Mass::new::<pound>(value),
-            MassUnit::Ounce => Mass::new::<ounce>(value),
+            MassUnit::Ounce => Mass::new::<pound>(value / OUNCES_PER_POUND),
             MassUnit::Stone => Mass::new::<pound>(value * STONE_IN_POUNDS),
             MassUnit::Ton => Mass::new::<ton>(value),
             MassUnit::Slug => Mass::new::<slug>(value),
@@ -165,7 +567,7 @@ impl MassUnit {
             MassUnit::Gram => mass.get::<gram>(),
             MassUnit::Milligram => mass.get::<milligram>(),
             MassUnit::Pound => mass.get::<pound>(),
-            MassUnit::Ounce => mass.get::<ounce>(),
+            MassUnit::Ounce => mass.get::<pound>() * OUNCES_PER_POUND,
             MassUnit::Stone => mass.get::<pound>() / STONE_IN_POUNDS,
             MassUnit::Ton => mass.get::<ton>(),
             MassUnit::Slug => mass.get::<slug>(),
@@ -174,7 +576,7 @@ impl MassUnit {
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
-enum VolumeUnit {
+pub(crate) enum VolumeUnit {
     Liter,
     Milliliter,
     Gallon,
@@ -192,6 +594,42 @@ enum VolumeUnit {
 }
 
 impl VolumeUnit {
+    const ALL: &'static [VolumeUnit] = &[
+        VolumeUnit::Liter,
+        VolumeUnit::Milliliter,
+        VolumeUnit::Gallon,
+        VolumeUnit::QuartLiquid,
+        VolumeUnit::QuartDry,
+        VolumeUnit::PintLiquid,
+        VolumeUnit::PintDry,
+        VolumeUnit::Cup,
+        VolumeUnit::FluidOunce,
+        VolumeUnit::Tablespoon,
+        VolumeUnit::Teaspoon,
+        VolumeUnit::CubicMeter,
+        VolumeUnit::CubicFoot,
+        VolumeUnit::CubicYard,
+    ];
+
+    fn symbol(self) -> &'static str {
+        match self {
+            VolumeUnit::Liter => "l",
+            VolumeUnit::Milliliter => "ml",
+            VolumeUnit::Gallon => "gal",
+            VolumeUnit::QuartLiquid => "qt",
+            VolumeUnit::QuartDry => "dry_quart",
+            VolumeUnit::PintLiquid => "pt",
+            VolumeUnit::PintDry => "dry_pint",
+            VolumeUnit::Cup => "cup",
+            VolumeUnit::FluidOunce => "fl oz",
+            VolumeUnit::Tablespoon => "tbsp",
+            VolumeUnit::Teaspoon => "tsp",
+            VolumeUnit::CubicMeter => "m3",
+            VolumeUnit::CubicFoot => "ft3",
+            VolumeUnit::CubicYard => "yd3",
+        }
+    }
+
     fn into_volume(self, value: f64) -> Volume {
         match self {
             VolumeUnit::Liter => Volume::new::<liter>(value),
@@ -232,7 +670,7 @@ impl VolumeUnit {
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
-enum SpeedUnit {
+pub(crate) enum SpeedUnit {
     MeterPerSecond,
     KilometerPerHour,
     MilePerHour,
@@ -241,6 +679,24 @@ enum SpeedUnit {
 }
 
 impl SpeedUnit {
+    const ALL: &'static [SpeedUnit] = &[
+        SpeedUnit::MeterPerSecond,
+        SpeedUnit::KilometerPerHour,
+        SpeedUnit::MilePerHour,
+        SpeedUnit::FootPerSecond,
+        SpeedUnit::Knot,
+    ];
+
+    fn symbol(self) -> &'static str {
+        match self {
+            SpeedUnit::MeterPerSecond => "m/s",
+            SpeedUnit::KilometerPerHour => "km/h",
+            SpeedUnit::MilePerHour => "mph",
+            SpeedUnit::FootPerSecond => "ft/s",
+            SpeedUnit::Knot => "kn",
+        }
+    }
+
     fn into_velocity(self, value: f64) -> Velocity {
         match self {
             SpeedUnit::MeterPerSecond => Velocity::new::<meter_per_second>(value),
@@ -263,13 +719,27 @@ impl SpeedUnit {
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
-enum TemperatureUnit {
+pub(crate) enum TemperatureUnit {
     Kelvin,
     Celsius,
     Fahrenheit,
 }
 
 impl TemperatureUnit {
+    const ALL: &'static [TemperatureUnit] = &[
+        TemperatureUnit::Kelvin,
+        TemperatureUnit::Celsius,
+        TemperatureUnit::Fahrenheit,
+    ];
+
+    fn symbol(self) -> &'static str {
+        match self {
+            TemperatureUnit::Kelvin => "K",
+            TemperatureUnit::Celsius => "°C",
+            TemperatureUnit::Fahrenheit => "°F",
+        }
+    }
+
     fn into_temperature(self, value: f64) -> ThermodynamicTemperature {
         match self {
             TemperatureUnit::Kelvin => ThermodynamicTemperature::new::<kelvin>(value),
@@ -288,3 +758,490 @@ impl TemperatureUnit {
         }
     }
 }
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum LengthUnit {
+    Meter,
+    Kilometer,
+    Centimeter,
+    Millimeter,
+    Inch,
+    Foot,
+    Yard,
+    Mile,
+    NauticalMile,
+}
+
+impl LengthUnit {
+    const ALL: &'static [LengthUnit] = &[
+        LengthUnit::Meter,
+        LengthUnit::Kilometer,
+        LengthUnit::Centimeter,
+        LengthUnit::Millimeter,
+        LengthUnit::Inch,
+        LengthUnit::Foot,
+        LengthUnit::Yard,
+        LengthUnit::Mile,
+        LengthUnit::NauticalMile,
+    ];
+
+    fn symbol(self) -> &'static str {
+        match self {
+            LengthUnit::Meter => "m",
+            LengthUnit::Kilometer => "km",
+            LengthUnit::Centimeter => "cm",
+            LengthUnit::Millimeter => "mm",
+            LengthUnit::Inch => "in",
+            LengthUnit::Foot => "ft",
+            LengthUnit::Yard => "yd",
+            LengthUnit::Mile => "mi",
+            LengthUnit::NauticalMile => "nmi",
+        }
+    }
+
+    fn into_length(self, value: f64) -> Length {
+        match self {
+            LengthUnit::Meter => Length::new::<meter>(value),
+            LengthUnit::Kilometer => Length::new::<kilometer>(value),
+            LengthUnit::Centimeter => Length::new::<centimeter>(value),
+            LengthUnit::Millimeter => Length::new::<millimeter>(value),
+            LengthUnit::Inch => Length::new::<inch>(value),
+            LengthUnit::Foot => Length::new::<foot>(value),
+            LengthUnit::Yard => Length::new::<yard>(value),
+            LengthUnit::Mile => Length::new::<mile>(value),
+            LengthUnit::NauticalMile => Length::new::<nautical_mile>(value),
+        }
+    }
+
+    fn from_length(self, length: Length) -> f64 {
+        match self {
+            LengthUnit::Meter => length.get::<meter>(),
+            LengthUnit::Kilometer => length.get::<kilometer>(),
+            LengthUnit::Centimeter => length.get::<centimeter>(),
+            LengthUnit::Millimeter => length.get::<millimeter>(),
+            LengthUnit::Inch => length.get::<inch>(),
+            LengthUnit::Foot => length.get::<foot>(),
+            LengthUnit::Yard => length.get::<yard>(),
+            LengthUnit::Mile => length.get::<mile>(),
+            LengthUnit::NauticalMile => length.get::<nautical_mile>(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum AreaUnit {
+    SquareMeter,
+    SquareKilometer,
+    SquareFoot,
+    SquareMile,
+    Acre,
+    Hectare,
+}
+
+impl AreaUnit {
+    const ALL: &'static [AreaUnit] = &[
+        AreaUnit::SquareMeter,
+        AreaUnit::SquareKilometer,
+        AreaUnit::SquareFoot,
+        AreaUnit::SquareMile,
+        AreaUnit::Acre,
+        AreaUnit::Hectare,
+    ];
+
+    fn symbol(self) -> &'static str {
+        match self {
+            AreaUnit::SquareMeter => "m2",
+            AreaUnit::SquareKilometer => "km2",
+            AreaUnit::SquareFoot => "ft2",
+            AreaUnit::SquareMile => "mi2",
+            AreaUnit::Acre => "acre",
+            AreaUnit::Hectare => "ha",
+        }
+    }
+
+    fn into_area(self, value: f64) -> Area {
+        match self {
+            AreaUnit::SquareMeter => Area::new::<square_meter>(value),
+            AreaUnit::SquareKilometer => Area::new::<square_kilometer>(value),
+            AreaUnit::SquareFoot => Area::new::<square_foot>(value),
+            AreaUnit::SquareMile => Area::new::<square_mile>(value),
+            AreaUnit::Acre => Area::new::<square_foot>(value * ACRE_IN_SQUARE_FEET),
+            AreaUnit::Hectare => Area::new::<hectare>(value),
+        }
+    }
+
+    fn from_area(self, area: Area) -> f64 {
+        match self {
+            AreaUnit::SquareMeter => area.get::<square_meter>(),
+            AreaUnit::SquareKilometer => area.get::<square_kilometer>(),
+            AreaUnit::SquareFoot => area.get::<square_foot>(),
+            AreaUnit::SquareMile => area.get::<square_mile>(),
+            AreaUnit::Acre => area.get::<square_foot>() / ACRE_IN_SQUARE_FEET,
+            AreaUnit::Hectare => area.get::<hectare>(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum TimeUnit {
+    Second,
+    Millisecond,
+    Minute,
+    Hour,
+    Day,
+    Week,
+}
+
+impl TimeUnit {
+    const ALL: &'static [TimeUnit] = &[
+        TimeUnit::Second,
+        TimeUnit::Millisecond,
+        TimeUnit::Minute,
+        TimeUnit::Hour,
+        TimeUnit::Day,
+        TimeUnit::Week,
+    ];
+
+    fn symbol(self) -> &'static str {
+        match self {
+            TimeUnit::Second => "s",
+            TimeUnit::Millisecond => "ms",
+            TimeUnit::Minute => "min",
+            TimeUnit::Hour => "h",
+            TimeUnit::Day => "d",
+            TimeUnit::Week => "wk",
+        }
+    }
+
+    fn into_time(self, value: f64) -> Time {
+        match self {
+            TimeUnit::Second => Time::new::<second>(value),
+            TimeUnit::Millisecond => Time::new::<millisecond>(value),
+            TimeUnit::Minute => Time::new::<minute>(value),
+            TimeUnit::Hour => Time::new::<hour>(value),
+            TimeUnit::Day => Time::new::<day>(value),
+            TimeUnit::Week => Time::new::<day>(value * DAYS_PER_WEEK),
+        }
+    }
+
+    fn from_time(self, time: Time) -> f64 {
+        match self {
+            TimeUnit::Second => time.get::<second>(),
+            TimeUnit::Millisecond => time.get::<millisecond>(),
+            TimeUnit::Minute => time.get::<minute>(),
+            TimeUnit::Hour => time.get::<hour>(),
+            TimeUnit::Day => time.get::<day>(),
+            TimeUnit::Week => time.get::<day>() / DAYS_PER_WEEK,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum DataUnit {
+    Bit,
+    Kilobit,
+    Megabit,
+    Gigabit,
+    Byte,
+    Kilobyte,
+    Megabyte,
+    Gigabyte,
+    Terabyte,
+    Kibibyte,
+    Mebibyte,
+    Gibibyte,
+    Tebibyte,
+}
+
+impl DataUnit {
+    const ALL: &'static [DataUnit] = &[
+        DataUnit::Bit,
+        DataUnit::Kilobit,
+        DataUnit::Megabit,
+        DataUnit::Gigabit,
+        DataUnit::Byte,
+        DataUnit::Kilobyte,
+        DataUnit::Megabyte,
+        DataUnit::Gigabyte,
+        DataUnit::Terabyte,
+        DataUnit::Kibibyte,
+        DataUnit::Mebibyte,
+        DataUnit::Gibibyte,
+        DataUnit::Tebibyte,
+    ];
+
+    fn symbol(self) -> &'static str {
+        match self {
+            DataUnit::Bit => "bit",
+            DataUnit::Kilobit => "kbit",
+            DataUnit::Megabit => "Mbit",
+            DataUnit::Gigabit => "Gbit",
+            DataUnit::Byte => "B",
+            DataUnit::Kilobyte => "kB",
+            DataUnit::Megabyte => "MB",
+            DataUnit::Gigabyte => "GB",
+            DataUnit::Terabyte => "TB",
+            DataUnit::Kibibyte => "KiB",
+            DataUnit::Mebibyte => "MiB",
+            DataUnit::Gibibyte => "GiB",
+            DataUnit::Tebibyte => "TiB",
+        }
+    }
+
+    fn into_information(self, value: f64) -> Information {
+        match self {
+            DataUnit::Bit => Information::new::<bit>(value),
+            DataUnit::Kilobit => Information::new::<kilobit>(value),
+            DataUnit::Megabit => Information::new::<megabit>(value),
+            DataUnit::Gigabit => Information::new::<gigabit>(value),
+            DataUnit::Byte => Information::new::<byte>(value),
+            DataUnit::Kilobyte => Information::new::<kilobyte>(value),
+            DataUnit::Megabyte => Information::new::<megabyte>(value),
+            DataUnit::Gigabyte => Information::new::<gigabyte>(value),
+            DataUnit::Terabyte => Information::new::<terabyte>(value),
+            DataUnit::Kibibyte => Information::new::<kibibyte>(value),
+            DataUnit::Mebibyte => Information::new::<mebibyte>(value),
+            DataUnit::Gibibyte => Information::new::<gibibyte>(value),
+            DataUnit::Tebibyte => Information::new::<tebibyte>(value),
+        }
+    }
+
+    fn from_information(self, information: Information) -> f64 {
+        match self {
+            DataUnit::Bit => information.get::<bit>(),
+            DataUnit::Kilobit => information.get::<kilobit>(),
+            DataUnit::Megabit => information.get::<megabit>(),
+            DataUnit::Gigabit => information.get::<gigabit>(),
+            DataUnit::Byte => information.get::<byte>(),
+            DataUnit::Kilobyte => information.get::<kilobyte>(),
+            DataUnit::Megabyte => information.get::<megabyte>(),
+            DataUnit::Gigabyte => information.get::<gigabyte>(),
+            DataUnit::Terabyte => information.get::<terabyte>(),
+            DataUnit::Kibibyte => information.get::<kibibyte>(),
+            DataUnit::Mebibyte => information.get::<mebibyte>(),
+            DataUnit::Gibibyte => information.get::<gibibyte>(),
+            DataUnit::Tebibyte => information.get::<tebibyte>(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum PressureUnit {
+    Pascal,
+    Kilopascal,
+    Megapascal,
+    Bar,
+    Millibar,
+    Psi,
+    Atmosphere,
+    MillimeterOfMercury,
+}
+
+impl PressureUnit {
+    const ALL: &'static [PressureUnit] = &[
+        PressureUnit::Pascal,
+        PressureUnit::Kilopascal,
+        PressureUnit::Megapascal,
+        PressureUnit::Bar,
+        PressureUnit::Millibar,
+        PressureUnit::Psi,
+        PressureUnit::Atmosphere,
+        PressureUnit::MillimeterOfMercury,
+    ];
+
+    fn symbol(self) -> &'static str {
+        match self {
+            PressureUnit::Pascal => "Pa",
+            PressureUnit::Kilopascal => "kPa",
+            PressureUnit::Megapascal => "MPa",
+            PressureUnit::Bar => "bar",
+            PressureUnit::Millibar => "mbar",
+            PressureUnit::Psi => "psi",
+            PressureUnit::Atmosphere => "atm",
+            PressureUnit::MillimeterOfMercury => "mmHg",
+        }
+    }
+
+    fn into_pressure(self, value: f64) -> Pressure {
+        match self {
+            PressureUnit::Pascal => Pressure::new::<pascal>(value),
+            PressureUnit::Kilopascal => Pressure::new::<kilopascal>(value),
+            PressureUnit::Megapascal => Pressure::new::<megapascal>(value),
+            PressureUnit::Bar => Pressure::new::<bar>(value),
+            PressureUnit::Millibar => Pressure::new::<millibar>(value),
+            PressureUnit::Psi => Pressure::new::<pound_force_per_square_inch>(value),
+            PressureUnit::Atmosphere => Pressure::new::<atmosphere>(value),
+            PressureUnit::MillimeterOfMercury => Pressure::new::<millimeter_of_mercury>(value),
+        }
+    }
+
+    fn from_pressure(self, pressure: Pressure) -> f64 {
+        match self {
+            PressureUnit::Pascal => pressure.get::<pascal>(),
+            PressureUnit::Kilopascal => pressure.get::<kilopascal>(),
+            PressureUnit::Megapascal => pressure.get::<megapascal>(),
+            PressureUnit::Bar => pressure.get::<bar>(),
+            PressureUnit::Millibar => pressure.get::<millibar>(),
+            PressureUnit::Psi => pressure.get::<pound_force_per_square_inch>(),
+            PressureUnit::Atmosphere => pressure.get::<atmosphere>(),
+            PressureUnit::MillimeterOfMercury => pressure.get::<millimeter_of_mercury>(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum EnergyUnit {
+    Joule,
+    Kilojoule,
+    Megajoule,
+    WattHour,
+    KilowattHour,
+    Calorie,
+    Kilocalorie,
+    Btu,
+}
+
+impl EnergyUnit {
+    const ALL: &'static [EnergyUnit] = &[
+        EnergyUnit::Joule,
+        EnergyUnit::Kilojoule,
+        EnergyUnit::Megajoule,
+        EnergyUnit::WattHour,
+        EnergyUnit::KilowattHour,
+        EnergyUnit::Calorie,
+        EnergyUnit::Kilocalorie,
+        EnergyUnit::Btu,
+    ];
+
+    fn symbol(self) -> &'static str {
+        match self {
+            EnergyUnit::Joule => "J",
+            EnergyUnit::Kilojoule => "kJ",
+            EnergyUnit::Megajoule => "MJ",
+            EnergyUnit::WattHour => "Wh",
+            EnergyUnit::KilowattHour => "kWh",
+            EnergyUnit::Calorie => "cal",
+            EnergyUnit::Kilocalorie => "kcal",
+            EnergyUnit::Btu => "BTU",
+        }
+    }
+
+    fn into_energy(self, value: f64) -> Energy {
+        match self {
+            EnergyUnit::Joule => Energy::new::<joule>(value),
+            EnergyUnit::Kilojoule => Energy::new::<kilojoule>(value),
+            EnergyUnit::Megajoule => Energy::new::<megajoule>(value),
+            EnergyUnit::WattHour => Energy::new::<watt_hour>(value),
+            EnergyUnit::KilowattHour => Energy::new::<kilowatt_hour>(value),
+            EnergyUnit::Calorie => Energy::new::<calorie>(value),
+            EnergyUnit::Kilocalorie => Energy::new::<kilocalorie>(value),
+            EnergyUnit::Btu => Energy::new::<btu_it>(value),
+        }
+    }
+
+    fn from_energy(self, energy: Energy) -> f64 {
+        match self {
+            EnergyUnit::Joule => energy.get::<joule>(),
+            EnergyUnit::Kilojoule => energy.get::<kilojoule>(),
+            EnergyUnit::Megajoule => energy.get::<megajoule>(),
+            EnergyUnit::WattHour => energy.get::<watt_hour>(),
+            EnergyUnit::KilowattHour => energy.get::<kilowatt_hour>(),
+            EnergyUnit::Calorie => energy.get::<calorie>(),
+            EnergyUnit::Kilocalorie => energy.get::<kilocalorie>(),
+            EnergyUnit::Btu => energy.get::<btu_it>(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum PowerUnit {
+    Watt,
+    Kilowatt,
+    Megawatt,
+    Horsepower,
+}
+
+impl PowerUnit {
+    const ALL: &'static [PowerUnit] = &[
+        PowerUnit::Watt,
+        PowerUnit::Kilowatt,
+        PowerUnit::Megawatt,
+        PowerUnit::Horsepower,
+    ];
+
+    fn symbol(self) -> &'static str {
+        match self {
+            PowerUnit::Watt => "W",
+            PowerUnit::Kilowatt => "kW",
+            PowerUnit::Megawatt => "MW",
+            PowerUnit::Horsepower => "hp",
+        }
+    }
+
+    fn into_power(self, value: f64) -> Power {
+        match self {
+            PowerUnit::Watt => Power::new::<watt>(value),
+            PowerUnit::Kilowatt => Power::new::<kilowatt>(value),
+            PowerUnit::Megawatt => Power::new::<megawatt>(value),
+            PowerUnit::Horsepower => Power::new::<horsepower>(value),
+        }
+    }
+
+    fn from_power(self, power: Power) -> f64 {
+        match self {
+            PowerUnit::Watt => power.get::<watt>(),
+            PowerUnit::Kilowatt => power.get::<kilowatt>(),
+            PowerUnit::Megawatt => power.get::<megawatt>(),
+            PowerUnit::Horsepower => power.get::<horsepower>(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum FrequencyUnit {
+    Hertz,
+    Kilohertz,
+    Megahertz,
+    Gigahertz,
+    Rpm,
+}
+
+impl FrequencyUnit {
+    const ALL: &'static [FrequencyUnit] = &[
+        FrequencyUnit::Hertz,
+        FrequencyUnit::Kilohertz,
+        FrequencyUnit::Megahertz,
+        FrequencyUnit::Gigahertz,
+        FrequencyUnit::Rpm,
+    ];
+
+    fn symbol(self) -> &'static str {
+        match self {
+            FrequencyUnit::Hertz => "Hz",
+            FrequencyUnit::Kilohertz => "kHz",
+            FrequencyUnit::Megahertz => "MHz",
+            FrequencyUnit::Gigahertz => "GHz",
+            FrequencyUnit::Rpm => "rpm",
+        }
+    }
+
+    fn into_frequency(self, value: f64) -> Frequency {
+        match self {
+            FrequencyUnit::Hertz => Frequency::new::<hertz>(value),
+            FrequencyUnit::Kilohertz => Frequency::new::<kilohertz>(value),
+            FrequencyUnit::Megahertz => Frequency::new::<megahertz>(value),
+            FrequencyUnit::Gigahertz => Frequency::new::<gigahertz>(value),
+            FrequencyUnit::Rpm => Frequency::new::<cycle_per_minute>(value),
+        }
+    }
+
+    fn from_frequency(self, frequency: Frequency) -> f64 {
+        match self {
+            FrequencyUnit::Hertz => frequency.get::<hertz>(),
+            FrequencyUnit::Kilohertz => frequency.get::<kilohertz>(),
+            FrequencyUnit::Megahertz => frequency.get::<megahertz>(),
+            FrequencyUnit::Gigahertz => frequency.get::<gigahertz>(),
+            FrequencyUnit::Rpm => frequency.get::<cycle_per_minute>(),
+        }
+    }
+}
@@ -3,6 +3,7 @@ use crate::BindContext;
 use super::bind_context::RsCelMacro;
 
 mod all;
+mod bind;
 mod coalesce;
 mod exists;
 mod exists_one;
@@ -10,35 +11,57 @@ mod filter;
 mod has;
 mod helpers;
 mod map;
+mod now;
 mod reduce;
+mod sort_by;
+mod transform_list;
+mod transform_map;
 
 pub use all::all_impl;
+pub use bind::bind_impl;
 pub use coalesce::coalesce_impl;
 pub use exists::exists_impl;
 pub use exists_one::exists_one_impl;
 pub use filter::filter_impl;
 pub use has::has_impl;
 pub use map::map_impl;
+pub use now::now_impl;
 pub use reduce::reduce_impl;
+pub use sort_by::sort_by_impl;
+pub use transform_list::transform_list_impl;
+pub use transform_map::{transform_map_entry_impl, transform_map_impl};
 
 const DEFAULT_MACROS: &[(&str, &'static RsCelMacro)] = &[
     ("has", &has_impl),
     ("all", &all_impl),
     ("exists", &exists_impl),
     ("exists_one", &exists_one_impl),
+    ("existsOne", &exists_one_impl),
     ("filter", &filter_impl),
     ("map", &map_impl),
     ("reduce", &reduce_impl),
+    ("sortBy", &sort_by_impl),
+    ("transformList", &transform_list_impl),
+    ("transformMap", &transform_map_impl),
+    ("transformMapEntry", &transform_map_entry_impl),
     ("coalesce", &coalesce_impl),
+    ("cel.bind", &bind_impl),
+    ("now", &now_impl),
 ];
 
 const COMPILE_MACROS: &[(&str, &'static RsCelMacro)] = &[
     ("all", &all_impl),
     ("exists", &exists_impl),
     ("exists_one", &exists_one_impl),
+    ("existsOne", &exists_one_impl),
     ("filter", &filter_impl),
     ("map", &map_impl),
     ("reduce", &reduce_impl),
+    ("sortBy", &sort_by_impl),
+    ("transformList", &transform_list_impl),
+    ("transformMap", &transform_map_impl),
+    ("transformMapEntry", &transform_map_entry_impl),
+    ("cel.bind", &bind_impl),
 ];
 
 pub fn load_default_macros(exec_ctx: &mut BindContext) {
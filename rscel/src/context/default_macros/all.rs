@@ -1,41 +1,30 @@
 use super::helpers;
 use crate::interp::Interpreter;
 use crate::types::CelByteCode;
-use crate::utils::eval_ident;
 use crate::{CelError, CelValue, CelValueDyn};
 
 pub fn all_impl(ctx: &Interpreter, this: CelValue, bytecode: &[&CelByteCode]) -> CelValue {
-    if bytecode.len() != 2 {
-        return CelValue::from_err(CelError::argument(
-            "all() macro expects exactly 2 arguments",
-        ));
+    if !(2..=3).contains(&bytecode.len()) {
+        return CelValue::from_err(CelError::argument("all() macro expects 2 or 3 arguments"));
     }
 
-    let ident_name = match eval_ident(bytecode[0]) {
-        Ok(s) => s,
-        Err(e) => return e.into(),
+    let (vars, body) = bytecode.split_at(bytecode.len() - 1);
+    let vars = match helpers::eval_idents(vars) {
+        Ok(vars) => vars,
+        Err(err) => return err.into(),
     };
 
-    match this {
-        CelValue::List(list) => {
-            let (cel, mut bindings) = helpers::setup_context(ctx);
-
-            for value in list.into_iter() {
-                bindings.bind_param(&ident_name, value.clone());
-                let interp = Interpreter::new(&cel, &bindings);
-
-                let res = match interp.run_raw(bytecode[1], true) {
-                    Ok(val) => val,
-                    Err(err) => return err.into(),
-                };
-
-                if !res.is_truthy() {
-                    return false.into();
-                }
-            }
+    let iterations = match helpers::iteration_values("all", this, vars.len()) {
+        Ok(iterations) => iterations,
+        Err(err) => return err.into(),
+    };
 
-            true.into()
-        }
-        _ => CelValue::from_err(CelError::value("all() only available on list")),
+    let mut res = true;
+    match helpers::for_each(ctx, &vars, iterations, body, |_, results| {
+        res = results[0].is_truthy();
+        Ok(res)
+    }) {
+        Ok(()) => res.into(),
+        Err(err) => err.into(),
     }
 }
@@ -0,0 +1,33 @@
+use super::helpers;
+use crate::interp::Interpreter;
+use crate::types::CelByteCode;
+use crate::utils::eval_ident;
+use crate::{CelError, CelValue};
+
+// cel.bind(name, init, expr)
+pub fn bind_impl(ctx: &Interpreter, _this: CelValue, bytecode: &[&CelByteCode]) -> CelValue {
+    if bytecode.len() != 3 {
+        return CelValue::from_err(CelError::argument(
+            "cel.bind() macro expects exactly 3 arguments",
+        ));
+    }
+
+    let name = match eval_ident(bytecode[0]) {
+        Ok(name) => name,
+        Err(err) => return err.into(),
+    };
+
+    let init = match ctx.run_raw(bytecode[1], true) {
+        Ok(val) => val,
+        Err(err) => return err.into(),
+    };
+
+    let (cel, mut bindings) = helpers::setup_context(ctx);
+    bindings.bind_param(&name, init);
+
+    let interp = ctx.child(&cel, &bindings, &[&name]);
+    match interp.run_raw(bytecode[2], true) {
+        Ok(val) => val,
+        Err(err) => err.into(),
+    }
+}
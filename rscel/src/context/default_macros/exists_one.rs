@@ -1,45 +1,34 @@
 use super::helpers;
 use crate::interp::Interpreter;
 use crate::types::CelByteCode;
-use crate::utils::eval_ident;
 use crate::{CelError, CelValue, CelValueDyn};
 
 pub fn exists_one_impl(ctx: &Interpreter, this: CelValue, bytecode: &[&CelByteCode]) -> CelValue {
-    if bytecode.len() != 2 {
+    if !(2..=3).contains(&bytecode.len()) {
         return CelValue::from_err(CelError::argument(
-            "exists_one() macro expects exactly 2 arguments",
+            "exists_one() macro expects 2 or 3 arguments",
         ));
     }
 
-    let ident_name = match eval_ident(bytecode[0]) {
-        Ok(name) => name,
+    let (vars, body) = bytecode.split_at(bytecode.len() - 1);
+    let vars = match helpers::eval_idents(vars) {
+        Ok(vars) => vars,
         Err(err) => return err.into(),
     };
 
-    match this {
-        CelValue::List(list) => {
-            let (cel, mut bindings) = helpers::setup_context(ctx);
-            let mut count = 0;
-
-            for value in list.into_iter() {
-                bindings.bind_param(&ident_name, value.clone());
-                let interp = Interpreter::new(&cel, &bindings);
-
-                let res = match interp.run_raw(bytecode[1], true) {
-                    Ok(val) => val,
-                    Err(err) => return err.into(),
-                };
-
-                if res.is_truthy() {
-                    count += 1;
-                    if count > 1 {
-                        return false.into();
-                    }
-                }
-            }
+    let iterations = match helpers::iteration_values("exists_one", this, vars.len()) {
+        Ok(iterations) => iterations,
+        Err(err) => return err.into(),
+    };
 
-            (count == 1).into()
+    let mut count = 0;
+    match helpers::for_each(ctx, &vars, iterations, body, |_, results| {
+        if results[0].is_truthy() {
+            count += 1;
         }
-        _ => CelValue::from_err(CelError::value("exists_one() only available on list")),
+        Ok(count <= 1)
+    }) {
+        Ok(()) => (count == 1).into(),
+        Err(err) => err.into(),
     }
 }
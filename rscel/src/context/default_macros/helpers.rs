@@ -1,5 +1,7 @@
 use crate::interp::Interpreter;
-use crate::{BindContext, CelContext};
+use crate::types::CelByteCode;
+use crate::utils::eval_ident;
+use crate::{BindContext, CelContext, CelError, CelResult, CelValue, CelValueDyn};
 
 pub(super) fn setup_context<'a>(ctx: &'a Interpreter<'a>) -> (CelContext, BindContext<'a>) {
     (
@@ -7,3 +9,70 @@ pub(super) fn setup_context<'a>(ctx: &'a Interpreter<'a>) -> (CelContext, BindCo
         ctx.bindings_copy().unwrap_or_else(BindContext::new),
     )
 }
+
+/// Names of the loop variables, the leading idents of a macro's arguments.
+pub(super) fn eval_idents(bytecode: &[&CelByteCode]) -> CelResult<Vec<String>> {
+    bytecode.iter().map(|bc| eval_ident(bc)).collect()
+}
+
+/// Values bound to the loop variables on each iteration, see `interp::iteration_values`.
+pub(super) fn iteration_values(
+    macro_name: &str,
+    this: CelValue,
+    n_vars: usize,
+) -> CelResult<Vec<Vec<CelValue>>> {
+    match this {
+        CelValue::List(_) | CelValue::Map(_) if n_vars == 1 || n_vars == 2 => {
+            crate::interp::iteration_values(this, n_vars)
+        }
+        CelValue::List(_) | CelValue::Map(_) => Err(CelError::argument(&format!(
+            "{}() macro expects one or two variables",
+            macro_name
+        ))),
+        _ => Err(CelError::value(&format!(
+            "{}() only available on list or map",
+            macro_name
+        ))),
+    }
+}
+
+/// Runs a macro body once per iteration with the loop variables bound, handing each
+/// result to `step`. Iteration stops early when `step` returns `false`.
+pub(super) fn for_each<F>(
+    ctx: &Interpreter,
+    vars: &[String],
+    iterations: Vec<Vec<CelValue>>,
+    body: &[&CelByteCode],
+    mut step: F,
+) -> CelResult<()>
+where
+    F: FnMut(Vec<CelValue>, Vec<CelValue>) -> CelResult<bool>,
+{
+    let (cel, mut bindings) = setup_context(ctx);
+    let locals: Vec<&str> = vars.iter().map(|v| v.as_str()).collect();
+
+    for values in iterations.into_iter() {
+        for (var, value) in vars.iter().zip(values.iter()) {
+            bindings.bind_param(var, value.clone());
+        }
+
+        let interp = ctx.child(&cel, &bindings, &locals);
+        let mut results = Vec::with_capacity(body.len());
+        for bc in body.iter() {
+            let res = interp.run_raw(bc, true)?;
+            // Later expressions, such as a mapper behind a filter, only run while
+            // the earlier ones are truthy
+            let stop = body.len() > 1 && results.is_empty() && !res.is_truthy();
+            results.push(res);
+            if stop {
+                break;
+            }
+        }
+
+        if !step(values, results)? {
+            break;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,15 @@
+use crate::interp::Interpreter;
+use crate::types::CelByteCode;
+use crate::{CelError, CelValue};
+
+/// A macro rather than a function so it can read the clock of the bindings.
+pub fn now_impl(ctx: &Interpreter, _this: CelValue, bytecode: &[&CelByteCode]) -> CelValue {
+    if !bytecode.is_empty() {
+        return CelValue::from_err(CelError::argument("now() expects no arguments"));
+    }
+
+    match ctx.now() {
+        Ok(ts) => ts.into(),
+        Err(err) => CelValue::from_err(err),
+    }
+}
@@ -0,0 +1,63 @@
+use std::cmp::Ordering;
+
+use super::helpers;
+use crate::types::CelByteCode;
+use crate::utils::eval_ident;
+use crate::{interp::Interpreter, CelError, CelValue};
+
+// sortBy [].sortBy(x, key)
+pub fn sort_by_impl(ctx: &Interpreter, this: CelValue, bytecode: &[&CelByteCode]) -> CelValue {
+    if bytecode.len() != 2 {
+        return CelValue::from_err(CelError::argument(
+            "sortBy() macro expects exactly 2 arguments",
+        ));
+    }
+
+    let ident_name = match eval_ident(bytecode[0]) {
+        Ok(s) => s,
+        Err(e) => return e.into(),
+    };
+
+    let list = match this {
+        CelValue::List(list) => list,
+        _ => return CelValue::from_err(CelError::value("sortBy() only available on list")),
+    };
+
+    let (cel, mut bindings) = helpers::setup_context(ctx);
+    let mut keyed = Vec::with_capacity(list.len());
+
+    for value in list.into_iter() {
+        bindings.bind_param(&ident_name, value.clone());
+        let interp = ctx.child(&cel, &bindings, &[&ident_name]);
+
+        match interp.run_raw(bytecode[1], true) {
+            Ok(key) => keyed.push((key, value)),
+            Err(err) => return err.into(),
+        }
+    }
+
+    let mut err = None;
+    // Stable, so values with equal keys keep their order
+    keyed.sort_by(|(a, _), (b, _)| match a.clone().ord(b.clone()) {
+        Ok(Some(ord)) => ord,
+        Ok(None) => {
+            err.get_or_insert_with(|| {
+                CelError::invalid_op(&format!("sortBy() keys {:?} and {:?} are unordered", a, b))
+            });
+            Ordering::Equal
+        }
+        Err(e) => {
+            err.get_or_insert(e);
+            Ordering::Equal
+        }
+    });
+
+    match err {
+        Some(err) => err.into(),
+        None => keyed
+            .into_iter()
+            .map(|(_, value)| value)
+            .collect::<Vec<_>>()
+            .into(),
+    }
+}
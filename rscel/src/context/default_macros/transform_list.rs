@@ -0,0 +1,40 @@
+use super::helpers;
+use crate::interp::Interpreter;
+use crate::types::CelByteCode;
+use crate::{CelError, CelValue};
+
+// transformList [].transformList(i, v, [filter,] expr)
+pub fn transform_list_impl(
+    ctx: &Interpreter,
+    this: CelValue,
+    bytecode: &[&CelByteCode],
+) -> CelValue {
+    if !(3..=4).contains(&bytecode.len()) {
+        return CelValue::from_err(CelError::argument(
+            "transformList() macro expects 3 or 4 arguments",
+        ));
+    }
+
+    let (vars, body) = bytecode.split_at(2);
+    let vars = match helpers::eval_idents(vars) {
+        Ok(vars) => vars,
+        Err(err) => return err.into(),
+    };
+
+    let iterations = match helpers::iteration_values("transformList", this, vars.len()) {
+        Ok(iterations) => iterations,
+        Err(err) => return err.into(),
+    };
+
+    let mut transformed = Vec::new();
+    match helpers::for_each(ctx, &vars, iterations, body, |_, mut results| {
+        // Results stop short when the filter rejected the element
+        if results.len() == body.len() {
+            transformed.extend(results.pop());
+        }
+        Ok(true)
+    }) {
+        Ok(()) => transformed.into(),
+        Err(err) => err.into(),
+    }
+}
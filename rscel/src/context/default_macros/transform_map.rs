@@ -0,0 +1,101 @@
+use std::collections::HashMap;
+
+use super::helpers;
+use crate::interp::Interpreter;
+use crate::types::CelByteCode;
+use crate::{CelError, CelResult, CelValue, CelValueDyn};
+
+// transformMap {}.transformMap(k, v, [filter,] expr)
+pub fn transform_map_impl(
+    ctx: &Interpreter,
+    this: CelValue,
+    bytecode: &[&CelByteCode],
+) -> CelValue {
+    transform("transformMap", ctx, this, bytecode, |map, key, value| {
+        map.insert(key, value);
+        Ok(())
+    })
+}
+
+// transformMapEntry {}.transformMapEntry(k, v, [filter,] {new_k: new_v})
+pub fn transform_map_entry_impl(
+    ctx: &Interpreter,
+    this: CelValue,
+    bytecode: &[&CelByteCode],
+) -> CelValue {
+    transform("transformMapEntry", ctx, this, bytecode, |map, _, entry| {
+        let entry = match entry {
+            CelValue::Map(entry) => entry,
+            other => {
+                return Err(CelError::value(&format!(
+                    "transformMapEntry() expects a map entry, got {}",
+                    other.as_type()
+                )))
+            }
+        };
+
+        for (key, value) in entry.into_iter() {
+            if map.contains_key(&key) {
+                return Err(CelError::value(&format!(
+                    "insert failed: key {} already exists",
+                    key
+                )));
+            }
+            map.insert(key, value);
+        }
+
+        Ok(())
+    })
+}
+
+fn transform<F>(
+    name: &str,
+    ctx: &Interpreter,
+    this: CelValue,
+    bytecode: &[&CelByteCode],
+    mut insert: F,
+) -> CelValue
+where
+    F: FnMut(&mut HashMap<String, CelValue>, String, CelValue) -> CelResult<()>,
+{
+    if !(3..=4).contains(&bytecode.len()) {
+        return CelValue::from_err(CelError::argument(&format!(
+            "{}() macro expects 3 or 4 arguments",
+            name
+        )));
+    }
+
+    if !matches!(this, CelValue::Map(_)) {
+        return CelValue::from_err(CelError::value(&format!(
+            "{}() only available on map",
+            name
+        )));
+    }
+
+    let (vars, body) = bytecode.split_at(2);
+    let vars = match helpers::eval_idents(vars) {
+        Ok(vars) => vars,
+        Err(err) => return err.into(),
+    };
+
+    let iterations = match helpers::iteration_values(name, this, vars.len()) {
+        Ok(iterations) => iterations,
+        Err(err) => return err.into(),
+    };
+
+    let mut transformed = HashMap::new();
+    match helpers::for_each(ctx, &vars, iterations, body, |values, mut results| {
+        // Results stop short when the filter rejected the entry
+        if results.len() == body.len() {
+            if let (Some(CelValue::String(key)), Some(res)) =
+                (values.into_iter().next(), results.pop())
+            {
+                insert(&mut transformed, key, res)?;
+            }
+        }
+        Ok(true)
+    }) {
+        Ok(()) => transformed.into(),
+        Err(err) => err.into(),
+    }
+}
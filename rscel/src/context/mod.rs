@@ -1,7 +1,8 @@
 use std::collections::HashMap;
 
 mod bind_context;
-mod default_funcs;
+mod clock;
+pub(crate) mod default_funcs;
 mod default_macros;
 mod type_funcs;
 use crate::{
@@ -11,7 +12,9 @@ use crate::{
     CelResult, CelValue,
 };
 pub use bind_context::{BindContext, RsCelFunction, RsCelMacro};
+pub use clock::Clock;
 pub use type_funcs::construct_type;
+pub(crate) use type_funcs::parse_bool_literal;
 
 /// The CelContext is the core context in RsCel. This context contains
 /// Program information as well as the primary entry point for evaluating
@@ -12,6 +12,7 @@ mod type_type;
 mod uint_type;
 
 pub use bool_type::bool_impl;
+pub(crate) use bool_type::parse_bool_literal;
 pub use bytes_type::bytes_impl;
 pub use double_type::double_impl;
 pub use duration_type::duration_impl;
@@ -2,7 +2,7 @@ use rscel_macro::dispatch;
 
 pub use methods::dispatch as bool_impl;
 
-fn parse_bool_literal(s: &str) -> Option<bool> {
+pub(crate) fn parse_bool_literal(s: &str) -> Option<bool> {
     match s {
         "1" | "t" | "true" | "TRUE" | "True" => Some(true),
         "0" | "f" | "false" | "FALSE" | "False" => Some(false),
@@ -1,18 +1,42 @@
 use rscel_macro::dispatch;
 
+use chrono::Duration;
+
+use crate::{types::cel_time::duration_in_range, CelError, CelResult};
+
 pub use methods::dispatch as duration_impl;
 
+fn in_range(d: Duration) -> CelResult<Duration> {
+    if duration_in_range(&d) {
+        Ok(d)
+    } else {
+        Err(CelError::value("duration out of range"))
+    }
+}
+
 #[dispatch]
 mod methods {
-    use crate::{CelError, CelResult, CelValue};
+    use super::in_range;
+    use crate::{
+        types::cel_time::{parse_duration, parse_iso8601_duration},
+        CelError, CelResult, CelValue,
+    };
     use chrono::Duration;
 
     fn duration(arg: String) -> CelResult<Duration> {
-        duration_str::parse_chrono(&arg).map_err(|_| CelError::value("Invalid duration format"))
+        match parse_duration(&arg).or_else(|| parse_iso8601_duration(&arg)) {
+            Some(d) => in_range(d),
+            // Longer forms such as `1d` or `2 hours`
+            None => duration_str::parse_chrono(&arg)
+                .map_err(|_| CelError::value("Invalid duration format"))
+                .and_then(in_range),
+        }
     }
 
     fn duration(arg: i64) -> CelResult<Duration> {
-        Duration::new(arg, 0).ok_or_else(|| CelError::value("Invalid argument for duration"))
+        Duration::new(arg, 0)
+            .ok_or_else(|| CelError::value("Invalid argument for duration"))
+            .and_then(in_range)
     }
 
     fn duration(arg: Duration) -> Duration {
@@ -20,7 +44,9 @@ mod methods {
     }
 
     fn duration(seconds: i64, nanos: i64) -> CelResult<Duration> {
-        Duration::new(seconds, nanos as u32)
+        Duration::new(seconds, 0)
+            .and_then(|d| d.checked_add(&Duration::nanoseconds(nanos)))
             .ok_or_else(|| CelError::value("Invalid argument for duration"))
+            .and_then(in_range)
     }
 }
@@ -11,12 +11,17 @@ mod methods {
         arg
     }
 
-    fn int(arg: u64) -> i64 {
-        arg as i64
+    fn int(arg: u64) -> CelResult<i64> {
+        i64::try_from(arg).map_err(|_| CelError::overflow(&format!("int({}u) out of range", arg)))
     }
 
-    fn int(arg: f64) -> i64 {
-        arg as i64
+    fn int(arg: f64) -> CelResult<i64> {
+        // i64::MIN is exactly representable as a double, i64::MAX rounds up past it
+        if arg.is_finite() && arg >= i64::MIN as f64 && arg < i64::MAX as f64 {
+            Ok(arg as i64)
+        } else {
+            Err(CelError::overflow(&format!("int({}) out of range", arg)))
+        }
     }
 
     fn int(arg: bool) -> i64 {
@@ -4,7 +4,10 @@ pub use methods::dispatch as string_impl;
 
 #[dispatch]
 mod methods {
-    use crate::types::CelBytes;
+    use crate::types::{
+        cel_time::{format_duration, format_timestamp},
+        CelBytes, CelQuantity, CelZonedTimestamp,
+    };
     use crate::{CelError, CelResult, CelValue};
     use chrono::{DateTime, Duration, Utc};
 
@@ -29,20 +32,24 @@ mod methods {
     }
 
     fn string(arg: DateTime<Utc>) -> String {
-        arg.to_rfc3339()
+        format_timestamp(&arg)
     }
 
     fn string(arg: Duration) -> String {
-        format!(
-            "{}s",
-            arg.num_nanoseconds().unwrap() as f64 / 1_000_000_000.0
-        )
+        format_duration(&arg)
     }
 
     fn string(arg: CelValue) -> CelResult<String> {
-        Err(CelError::value(&format!(
-            "string() invalid for value {:?}",
-            arg
-        )))
+        if let Some(quantity) = CelQuantity::from_cel_value(&arg) {
+            return Ok(quantity.to_string());
+        }
+
+        match CelZonedTimestamp::from_cel_value(&arg) {
+            Some(zoned) => Ok(zoned.to_string()),
+            None => Err(CelError::value(&format!(
+                "string() invalid for value {:?}",
+                arg
+            ))),
+        }
     }
 }
@@ -1,32 +1,54 @@
 use rscel_macro::dispatch;
 
+use chrono::{DateTime, Utc};
+
+use crate::{types::cel_time::timestamp_in_range, CelError, CelResult};
+
 pub use methods::dispatch as timestamp_impl;
 
+fn in_range(ts: DateTime<Utc>) -> CelResult<DateTime<Utc>> {
+    if timestamp_in_range(&ts) {
+        Ok(ts)
+    } else {
+        Err(CelError::value("timestamp out of range"))
+    }
+}
+
 #[dispatch]
 mod methods {
-    use crate::{CelError, CelResult, CelValue};
+    use super::in_range;
+    use crate::{
+        context::default_funcs::time_funcs::helpers::{parse_in, parse_timezone},
+        types::CelZonedTimestamp,
+        CelError, CelResult, CelValue,
+    };
     use chrono::{DateTime, TimeZone, Utc};
-
-    fn timestamp() -> DateTime<Utc> {
-        Utc::now()
-    }
+    use chrono_tz::Tz;
 
     fn timestamp(arg: String) -> CelResult<DateTime<Utc>> {
         if let Ok(val) = arg.parse::<DateTime<Utc>>() {
-            Ok(val)
+            in_range(val)
         } else if let Ok(val) = DateTime::parse_from_rfc2822(&arg) {
-            Ok(val.to_utc())
+            in_range(val.to_utc())
         } else if let Ok(val) = DateTime::parse_from_rfc3339(&arg) {
-            Ok(val.to_utc())
+            in_range(val.to_utc())
         } else {
             Err(CelError::value("Invalid timestamp format"))
         }
     }
 
+    fn timestamp(arg: String, pattern: String) -> CelResult<DateTime<Utc>> {
+        parse_in(&arg, &pattern, &Tz::UTC)
+    }
+
+    fn timestamp(arg: String, pattern: String, timezone: String) -> CelResult<DateTime<Utc>> {
+        parse_in(&arg, &pattern, &parse_timezone(&timezone)?)
+    }
+
     fn timestamp(arg: i64) -> CelResult<DateTime<Utc>> {
         use chrono::MappedLocalTime;
         match Utc.timestamp_opt(arg, 0) {
-            MappedLocalTime::Single(s) => Ok(s),
+            MappedLocalTime::Single(s) => in_range(s),
             _ => Err(CelError::value("Invalid timestamp value")),
         }
     }
@@ -34,7 +56,7 @@ mod methods {
     fn timestamp(arg: u64) -> CelResult<DateTime<Utc>> {
         use chrono::MappedLocalTime;
         match Utc.timestamp_opt(arg as i64, 0) {
-            MappedLocalTime::Single(s) => Ok(s),
+            MappedLocalTime::Single(s) => in_range(s),
             _ => Err(CelError::value("Invalid timestamp value")),
         }
     }
@@ -42,4 +64,14 @@ mod methods {
     fn timestamp(arg: DateTime<Utc>) -> DateTime<Utc> {
         arg
     }
+
+    fn timestamp(arg: CelValue) -> CelResult<DateTime<Utc>> {
+        match CelZonedTimestamp::from_cel_value(&arg) {
+            Some(zoned) => Ok(zoned.instant()),
+            None => Err(CelError::value(&format!(
+                "timestamp() invalid for value {:?}",
+                arg
+            ))),
+        }
+    }
 }
@@ -10,12 +10,17 @@ mod methods {
         arg
     }
 
-    fn uint(arg: i64) -> u64 {
-        arg as u64
+    fn uint(arg: i64) -> CelResult<u64> {
+        u64::try_from(arg).map_err(|_| CelError::overflow(&format!("uint({}) out of range", arg)))
     }
 
-    fn uint(arg: f64) -> u64 {
-        arg as u64
+    fn uint(arg: f64) -> CelResult<u64> {
+        // u64::MAX rounds up past itself as a double
+        if arg.is_finite() && arg.trunc() >= 0.0 && arg < u64::MAX as f64 {
+            Ok(arg as u64)
+        } else {
+            Err(CelError::overflow(&format!("uint({}) out of range", arg)))
+        }
     }
 
     fn uint(arg: bool) -> u64 {
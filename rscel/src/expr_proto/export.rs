@@ -0,0 +1,569 @@
+use std::collections::HashMap;
+
+use protobuf::{well_known_types::struct_::NullValue, MessageField};
+
+use super::syntax::{
+    self,
+    constant::Constant_kind,
+    expr::{
+        create_struct::{entry::Key_kind, Entry},
+        Call, Comprehension, CreateList, CreateStruct, Expr_kind, Ident as IdentExpr, Select,
+    },
+    Constant, SourceInfo,
+};
+use crate::{
+    compiler::tokens::FStringSegment, AddOp, Addition, AstNode, CelError, CelResult,
+    ConditionalAnd, ConditionalOr, Expr, LiteralsAndKeywords, Member, MemberPrime, MultOp,
+    Multiplication, NegList, NotList, Primary, Program, Relation, Relop, SourceLocation, Unary,
+};
+
+/// Name of the accumulator variable used when expanding macros.
+const ACCU_VAR: &str = "__result__";
+
+pub struct Exporter {
+    next_id: i64,
+    line_offsets: Vec<i32>,
+    positions: HashMap<i64, i32>,
+    macro_calls: HashMap<i64, syntax::Expr>,
+}
+
+impl Exporter {
+    pub fn new(source: Option<&str>) -> Exporter {
+        let mut line_offsets = Vec::new();
+
+        if let Some(source) = source {
+            let mut len = 0;
+            for (i, c) in source.chars().enumerate() {
+                if c == '\n' {
+                    line_offsets.push(i as i32 + 1);
+                }
+                len = i + 1;
+            }
+            line_offsets.push(len as i32 + 1);
+        }
+
+        Exporter {
+            next_id: 1,
+            line_offsets,
+            positions: HashMap::new(),
+            macro_calls: HashMap::new(),
+        }
+    }
+
+    pub fn export(mut self, ast: &AstNode<Expr>) -> CelResult<(syntax::Expr, SourceInfo)> {
+        let expr = self.expr(ast)?;
+
+        let mut info = SourceInfo::new();
+        info.line_offsets = self.line_offsets;
+        info.positions = self.positions;
+        info.macro_calls = self.macro_calls;
+
+        Ok((expr, info))
+    }
+
+    fn new_expr(&mut self, loc: SourceLocation, kind: Expr_kind) -> syntax::Expr {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        if !self.line_offsets.is_empty() {
+            let line_start = match loc.line() {
+                0 => 0,
+                n => self.line_offsets.get(n - 1).copied().unwrap_or(0),
+            };
+            self.positions.insert(id, line_start + loc.col() as i32);
+        }
+
+        let mut expr = syntax::Expr::new();
+        expr.id = id;
+        expr.expr_kind = Some(kind);
+        expr
+    }
+
+    fn constant(&mut self, loc: SourceLocation, kind: Constant_kind) -> syntax::Expr {
+        let mut constant = Constant::new();
+        constant.constant_kind = Some(kind);
+
+        self.new_expr(loc, Expr_kind::ConstExpr(constant))
+    }
+
+    fn ident(&mut self, loc: SourceLocation, name: &str) -> syntax::Expr {
+        let mut ident = IdentExpr::new();
+        ident.name = name.to_owned();
+
+        self.new_expr(loc, Expr_kind::IdentExpr(ident))
+    }
+
+    fn call(
+        &mut self,
+        loc: SourceLocation,
+        target: Option<syntax::Expr>,
+        function: &str,
+        args: Vec<syntax::Expr>,
+    ) -> syntax::Expr {
+        let mut call = Call::new();
+        call.target = MessageField::from_option(target);
+        call.function = function.to_owned();
+        call.args = args;
+
+        self.new_expr(loc, Expr_kind::CallExpr(call))
+    }
+
+    fn list(&mut self, loc: SourceLocation, elements: Vec<syntax::Expr>) -> syntax::Expr {
+        let mut list = CreateList::new();
+        list.elements = elements;
+
+        self.new_expr(loc, Expr_kind::ListExpr(list))
+    }
+
+    fn expr(&mut self, ast: &AstNode<Expr>) -> CelResult<syntax::Expr> {
+        match ast.node() {
+            Expr::Ternary {
+                condition,
+                true_clause,
+                false_clause,
+            } => {
+                let args = vec![
+                    self.conditional_or(condition)?,
+                    self.conditional_or(true_clause)?,
+                    self.expr(false_clause)?,
+                ];
+                Ok(self.call(ast.start(), None, "_?_:_", args))
+            }
+            Expr::Match { .. } => Err(CelError::value(
+                "match expressions have no canonical CEL representation",
+            )),
+            Expr::Unary(or) => self.conditional_or(or),
+        }
+    }
+
+    fn conditional_or(&mut self, ast: &AstNode<ConditionalOr>) -> CelResult<syntax::Expr> {
+        match ast.node() {
+            ConditionalOr::Binary { lhs, rhs } => {
+                let args = vec![self.conditional_or(lhs)?, self.conditional_and(rhs)?];
+                Ok(self.call(ast.start(), None, "_||_", args))
+            }
+            ConditionalOr::Unary(and) => self.conditional_and(and),
+        }
+    }
+
+    fn conditional_and(&mut self, ast: &AstNode<ConditionalAnd>) -> CelResult<syntax::Expr> {
+        match ast.node() {
+            ConditionalAnd::Binary { lhs, rhs } => {
+                let args = vec![self.conditional_and(lhs)?, self.relation(rhs)?];
+                Ok(self.call(ast.start(), None, "_&&_", args))
+            }
+            ConditionalAnd::Unary(relation) => self.relation(relation),
+        }
+    }
+
+    fn relation(&mut self, ast: &AstNode<Relation>) -> CelResult<syntax::Expr> {
+        match ast.node() {
+            Relation::Binary { lhs, op, rhs } => {
+                let function = match op {
+                    Relop::Le => "_<=_",
+                    Relop::Lt => "_<_",
+                    Relop::Ge => "_>=_",
+                    Relop::Gt => "_>_",
+                    Relop::Eq => "_==_",
+                    Relop::Ne => "_!=_",
+                    Relop::In => "@in",
+                };
+                let args = vec![self.relation(lhs)?, self.addition(rhs)?];
+                Ok(self.call(ast.start(), None, function, args))
+            }
+            Relation::Unary(addition) => self.addition(addition),
+        }
+    }
+
+    fn addition(&mut self, ast: &AstNode<Addition>) -> CelResult<syntax::Expr> {
+        match ast.node() {
+            Addition::Binary { lhs, op, rhs } => {
+                let function = match op {
+                    AddOp::Add => "_+_",
+                    AddOp::Sub => "_-_",
+                };
+                let args = vec![self.addition(lhs)?, self.multiplication(rhs)?];
+                Ok(self.call(ast.start(), None, function, args))
+            }
+            Addition::Unary(mult) => self.multiplication(mult),
+        }
+    }
+
+    fn multiplication(&mut self, ast: &AstNode<Multiplication>) -> CelResult<syntax::Expr> {
+        match ast.node() {
+            Multiplication::Binary { lhs, op, rhs } => {
+                let function = match op {
+                    MultOp::Mult => "_*_",
+                    MultOp::Div => "_/_",
+                    MultOp::Mod => "_%_",
+                };
+                let args = vec![self.multiplication(lhs)?, self.unary(rhs)?];
+                Ok(self.call(ast.start(), None, function, args))
+            }
+            Multiplication::Unary(unary) => self.unary(unary),
+        }
+    }
+
+    fn unary(&mut self, ast: &AstNode<Unary>) -> CelResult<syntax::Expr> {
+        match ast.node() {
+            Unary::Member(member) => self.member(member),
+            Unary::NotMember { nots, member } => {
+                let mut expr = self.member(member)?;
+                let mut curr = nots.node();
+                while let NotList::List { tail } = curr {
+                    expr = self.call(ast.start(), None, "!_", vec![expr]);
+                    curr = tail.node();
+                }
+                Ok(expr)
+            }
+            Unary::NegMember { negs, member } => {
+                let mut count = 0;
+                let mut curr = negs.node();
+                while let NegList::List { tail } = curr {
+                    count += 1;
+                    curr = tail.node();
+                }
+
+                // Like the reference parsers, fold a single negation into
+                // numeric literals.
+                if count == 1 && member.node().member.is_empty() {
+                    if let Primary::Literal(lit) = member.node().primary.node() {
+                        match lit {
+                            LiteralsAndKeywords::IntegerLit(i) => {
+                                return Ok(self.constant(
+                                    ast.start(),
+                                    Constant_kind::Int64Value(i.wrapping_neg()),
+                                ))
+                            }
+                            LiteralsAndKeywords::FloatingLit(f) => {
+                                return Ok(
+                                    self.constant(ast.start(), Constant_kind::DoubleValue(-f))
+                                )
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+
+                let mut expr = self.member(member)?;
+                for _ in 0..count {
+                    expr = self.call(ast.start(), None, "-_", vec![expr]);
+                }
+                Ok(expr)
+            }
+        }
+    }
+
+    fn member(&mut self, ast: &AstNode<Member>) -> CelResult<syntax::Expr> {
+        let member = ast.node();
+        let loc = ast.start();
+        let mut primes = member.member.iter().peekable();
+
+        // A call directly on an identifier is a global function call
+        let mut expr = match (member.primary.node(), primes.peek().map(|p| p.node())) {
+            (Primary::Ident(ident), Some(MemberPrime::Call { call })) => {
+                let args: Vec<_> = call.node().exprs.iter().rev().collect();
+                primes.next();
+                self.global_call(loc, &ident.0, &args)?
+            }
+            _ => self.primary(&member.primary)?,
+        };
+
+        while let Some(prime) = primes.next() {
+            expr = match prime.node() {
+                MemberPrime::MemberAccess { ident } => {
+                    if let Some(MemberPrime::Call { call }) = primes.peek().map(|p| p.node()) {
+                        let args: Vec<_> = call.node().exprs.iter().rev().collect();
+                        primes.next();
+                        self.method_call(loc, expr, &ident.node().0, &args)?
+                    } else {
+                        let mut select = Select::new();
+                        select.operand = MessageField::some(expr);
+                        select.field = ident.node().0.clone();
+                        self.new_expr(prime.start(), Expr_kind::SelectExpr(select))
+                    }
+                }
+                MemberPrime::ArrayAccess { access } => {
+                    let index = self.expr(access)?;
+                    self.call(prime.start(), None, "_[_]", vec![expr, index])
+                }
+                MemberPrime::Call { .. } => {
+                    return Err(CelError::value(
+                        "only identifiers and members can be called in canonical CEL",
+                    ))
+                }
+                MemberPrime::Empty => expr,
+            };
+        }
+
+        Ok(expr)
+    }
+
+    fn global_call(
+        &mut self,
+        loc: SourceLocation,
+        function: &str,
+        args: &[&AstNode<Expr>],
+    ) -> CelResult<syntax::Expr> {
+        // has(a.b) is represented as a test only select
+        if function == "has" && args.len() == 1 {
+            let arg = self.expr(args[0])?;
+            if let Some(Expr_kind::SelectExpr(mut select)) = arg.expr_kind.clone() {
+                select.test_only = true;
+                let expr = self.new_expr(loc, Expr_kind::SelectExpr(select));
+                self.macro_calls
+                    .insert(expr.id, self.macro_call(None, function, vec![arg]));
+                return Ok(expr);
+            }
+
+            return Err(CelError::argument("has() macro expects a field selection"));
+        }
+
+        let args = args
+            .iter()
+            .map(|a| self.expr(a))
+            .collect::<CelResult<Vec<_>>>()?;
+
+        Ok(self.call(loc, None, function, args))
+    }
+
+    fn method_call(
+        &mut self,
+        loc: SourceLocation,
+        target: syntax::Expr,
+        function: &str,
+        args: &[&AstNode<Expr>],
+    ) -> CelResult<syntax::Expr> {
+        let args = args
+            .iter()
+            .map(|a| self.expr(a))
+            .collect::<CelResult<Vec<_>>>()?;
+
+        let is_macro = matches!(
+            (function, args.len()),
+            ("all" | "exists" | "exists_one" | "filter", 2) | ("map", 2 | 3)
+        );
+
+        if !is_macro {
+            return Ok(self.call(loc, Some(target), function, args));
+        }
+
+        let iter_var = match &args[0].expr_kind {
+            Some(Expr_kind::IdentExpr(ident)) => ident.name.clone(),
+            _ => {
+                return Err(CelError::argument(
+                    "macro iteration variable must be an identifier",
+                ))
+            }
+        };
+
+        let expr = self.comprehension(loc, target.clone(), function, &iter_var, &args[1..]);
+        self.macro_calls
+            .insert(expr.id, self.macro_call(Some(target), function, args));
+
+        Ok(expr)
+    }
+
+    /// Expands a macro the same way the reference parsers do.
+    fn comprehension(
+        &mut self,
+        loc: SourceLocation,
+        range: syntax::Expr,
+        function: &str,
+        iter_var: &str,
+        args: &[syntax::Expr],
+    ) -> syntax::Expr {
+        let accu = self.ident(loc, ACCU_VAR);
+        let (init, condition, step, result) = match function {
+            "all" | "exists" => {
+                let (init, step_fn) = match function {
+                    "all" => (true, "_&&_"),
+                    _ => (false, "_||_"),
+                };
+                let init = self.constant(loc, Constant_kind::BoolValue(init));
+                let mut cond_accu = self.ident(loc, ACCU_VAR);
+                if function == "exists" {
+                    cond_accu = self.call(loc, None, "!_", vec![cond_accu]);
+                }
+                let condition = self.call(loc, None, "@not_strictly_false", vec![cond_accu]);
+                let step = self.call(loc, None, step_fn, vec![accu.clone(), args[0].clone()]);
+                let result = self.ident(loc, ACCU_VAR);
+
+                (init, condition, step, result)
+            }
+            "exists_one" => {
+                let init = self.constant(loc, Constant_kind::Int64Value(0));
+                let condition = self.constant(loc, Constant_kind::BoolValue(true));
+                let one = self.constant(loc, Constant_kind::Int64Value(1));
+                let inc = self.call(loc, None, "_+_", vec![accu.clone(), one]);
+                let step = self.call(loc, None, "_?_:_", vec![args[0].clone(), inc, accu.clone()]);
+                let one = self.constant(loc, Constant_kind::Int64Value(1));
+                let result = self.call(loc, None, "_==_", vec![accu, one]);
+
+                (init, condition, step, result)
+            }
+            _ => {
+                // map and filter
+                let init = self.list(loc, Vec::new());
+                let condition = self.constant(loc, Constant_kind::BoolValue(true));
+                let (predicate, element) = match (function, args) {
+                    ("filter", [pred]) => (Some(pred), self.ident(loc, iter_var)),
+                    ("map", [pred, transform]) => (Some(pred), transform.clone()),
+                    (_, [transform]) => (None, transform.clone()),
+                    _ => unreachable!(),
+                };
+                let element = self.list(loc, vec![element]);
+                let mut step = self.call(loc, None, "_+_", vec![accu.clone(), element]);
+                if let Some(predicate) = predicate {
+                    step = self.call(loc, None, "_?_:_", vec![predicate.clone(), step, accu]);
+                }
+                let result = self.ident(loc, ACCU_VAR);
+
+                (init, condition, step, result)
+            }
+        };
+
+        let mut comp = Comprehension::new();
+        comp.iter_var = iter_var.to_owned();
+        comp.iter_range = MessageField::some(range);
+        comp.accu_var = ACCU_VAR.to_owned();
+        comp.accu_init = MessageField::some(init);
+        comp.loop_condition = MessageField::some(condition);
+        comp.loop_step = MessageField::some(step);
+        comp.result = MessageField::some(result);
+
+        self.new_expr(loc, Expr_kind::ComprehensionExpr(comp))
+    }
+
+    fn macro_call(
+        &self,
+        target: Option<syntax::Expr>,
+        function: &str,
+        args: Vec<syntax::Expr>,
+    ) -> syntax::Expr {
+        let mut call = Call::new();
+        call.target = MessageField::from_option(target);
+        call.function = function.to_owned();
+        call.args = args;
+
+        let mut expr = syntax::Expr::new();
+        expr.expr_kind = Some(Expr_kind::CallExpr(call));
+        expr
+    }
+
+    fn primary(&mut self, ast: &AstNode<Primary>) -> CelResult<syntax::Expr> {
+        let loc = ast.start();
+
+        match ast.node() {
+            Primary::Type => Ok(self.ident(loc, "type")),
+            Primary::Ident(ident) => Ok(self.ident(loc, &ident.0)),
+            Primary::Parens(expr) => self.expr(expr),
+            Primary::ListConstruction(list) => {
+                let elements = list
+                    .node()
+                    .exprs
+                    .iter()
+                    .map(|e| self.expr(e))
+                    .collect::<CelResult<Vec<_>>>()?;
+                Ok(self.list(loc, elements))
+            }
+            Primary::ObjectInit(inits) => {
+                let mut create = CreateStruct::new();
+                for init in inits.node().inits.iter() {
+                    let mut entry = Entry::new();
+                    entry.id = self.next_id;
+                    self.next_id += 1;
+                    entry.key_kind = Some(Key_kind::MapKey(self.expr(&init.node().key)?));
+                    entry.value = MessageField::some(self.expr(&init.node().value)?);
+                    create.entries.push(entry);
+                }
+                Ok(self.new_expr(loc, Expr_kind::StructExpr(create)))
+            }
+            Primary::Literal(literal) => self.literal(loc, literal),
+        }
+    }
+
+    fn literal(
+        &mut self,
+        loc: SourceLocation,
+        literal: &LiteralsAndKeywords,
+    ) -> CelResult<syntax::Expr> {
+        let kind = match literal {
+            LiteralsAndKeywords::Type => return Ok(self.ident(loc, "type")),
+            LiteralsAndKeywords::NullType => return Ok(self.ident(loc, "null_type")),
+            LiteralsAndKeywords::Int => return Ok(self.ident(loc, "int")),
+            LiteralsAndKeywords::Uint => return Ok(self.ident(loc, "uint")),
+            LiteralsAndKeywords::Float => return Ok(self.ident(loc, "double")),
+            LiteralsAndKeywords::Bool => return Ok(self.ident(loc, "bool")),
+            LiteralsAndKeywords::String => return Ok(self.ident(loc, "string")),
+            LiteralsAndKeywords::Bytes => return Ok(self.ident(loc, "bytes")),
+            LiteralsAndKeywords::Timestamp => return Ok(self.ident(loc, "timestamp")),
+            LiteralsAndKeywords::Duration => return Ok(self.ident(loc, "duration")),
+            LiteralsAndKeywords::FStringList(segments) => return self.fstring(loc, segments),
+            LiteralsAndKeywords::QuantityLit(f, unit) => {
+                let args = vec![
+                    self.constant(loc, Constant_kind::DoubleValue(*f)),
+                    self.constant(loc, Constant_kind::StringValue(unit.clone())),
+                ];
+                return Ok(self.call(loc, None, "quantity", args));
+            }
+            LiteralsAndKeywords::NullLit => Constant_kind::NullValue(NullValue::NULL_VALUE.into()),
+            LiteralsAndKeywords::IntegerLit(i) => Constant_kind::Int64Value(*i),
+            LiteralsAndKeywords::UnsignedLit(u) => Constant_kind::Uint64Value(*u),
+            LiteralsAndKeywords::FloatingLit(f) => Constant_kind::DoubleValue(*f),
+            LiteralsAndKeywords::StringLit(s) => Constant_kind::StringValue(s.clone()),
+            LiteralsAndKeywords::ByteStringLit(b) => Constant_kind::BytesValue(b.clone()),
+            LiteralsAndKeywords::BooleanLit(b) => Constant_kind::BoolValue(*b),
+        };
+
+        Ok(self.constant(loc, kind))
+    }
+
+    /// Format strings are lowered into a concatenation of strings.
+    fn fstring(
+        &mut self,
+        loc: SourceLocation,
+        segments: &[FStringSegment],
+    ) -> CelResult<syntax::Expr> {
+        let mut parts = Vec::new();
+
+        for segment in segments.iter() {
+            let part = match segment {
+                FStringSegment::Lit(s) => self.constant(loc, Constant_kind::StringValue(s.clone())),
+                FStringSegment::Expr(source) => {
+                    let prog = Program::from_source(source)?;
+                    let ast = prog
+                        .ast()
+                        .ok_or_else(|| CelError::internal("format segment is missing its ast"))?;
+
+                    // Segment locations are relative to the segment source
+                    let mut inner = Exporter::new(None);
+                    inner.next_id = self.next_id;
+                    let expr = inner.expr(ast)?;
+                    self.next_id = inner.next_id;
+                    self.macro_calls.extend(inner.macro_calls);
+
+                    self.call(loc, None, "string", vec![expr])
+                }
+                FStringSegment::FmtExpr(_, spec) => {
+                    return Err(CelError::value(&format!(
+                        "format specifier '{}' has no canonical form",
+                        spec
+                    )))
+                }
+            };
+            parts.push(part);
+        }
+
+        let mut parts = parts.into_iter();
+        let Some(mut expr) = parts.next() else {
+            return Ok(self.constant(loc, Constant_kind::StringValue(String::new())));
+        };
+
+        for part in parts {
+            expr = self.call(loc, None, "_+_", vec![expr, part]);
+        }
+
+        Ok(expr)
+    }
+}
@@ -0,0 +1,315 @@
+use std::collections::HashMap;
+
+use super::syntax::{
+    self,
+    constant::Constant_kind,
+    expr::{create_struct::entry::Key_kind, Call, Comprehension, Expr_kind},
+    SourceInfo,
+};
+use crate::{
+    types::cel_time::{format_duration, format_timestamp},
+    CelError, CelResult, ExprBuilder,
+};
+
+pub struct Importer<'a> {
+    macro_calls: &'a HashMap<i64, syntax::Expr>,
+    exprs: HashMap<i64, &'a syntax::Expr>,
+}
+
+impl<'a> Importer<'a> {
+    pub fn new(expr: &'a syntax::Expr, info: &'a SourceInfo) -> Importer<'a> {
+        let mut importer = Importer {
+            macro_calls: &info.macro_calls,
+            exprs: HashMap::new(),
+        };
+        importer.index(expr);
+
+        importer
+    }
+
+    /// Macro call arguments may reference sub expressions by id only, keep
+    /// track of every expression so they can be resolved.
+    fn index(&mut self, expr: &'a syntax::Expr) {
+        self.exprs.insert(expr.id, expr);
+
+        match &expr.expr_kind {
+            Some(Expr_kind::SelectExpr(select)) => self.index(&select.operand),
+            Some(Expr_kind::CallExpr(call)) => {
+                if let Some(target) = call.target.as_ref() {
+                    self.index(target);
+                }
+                call.args.iter().for_each(|a| self.index(a));
+            }
+            Some(Expr_kind::ListExpr(list)) => list.elements.iter().for_each(|e| self.index(e)),
+            Some(Expr_kind::StructExpr(create)) => {
+                for entry in create.entries.iter() {
+                    if let Some(Key_kind::MapKey(key)) = &entry.key_kind {
+                        self.index(key);
+                    }
+                    self.index(&entry.value);
+                }
+            }
+            Some(Expr_kind::ComprehensionExpr(comp)) => {
+                self.index(&comp.iter_range);
+                self.index(&comp.accu_init);
+                self.index(&comp.loop_condition);
+                self.index(&comp.loop_step);
+                self.index(&comp.result);
+            }
+            Some(Expr_kind::ConstExpr(_)) | Some(Expr_kind::IdentExpr(_)) | None => {}
+        }
+    }
+
+    pub fn import(&self, expr: &syntax::Expr) -> CelResult<ExprBuilder> {
+        match &expr.expr_kind {
+            Some(Expr_kind::ConstExpr(constant)) => match &constant.constant_kind {
+                Some(Constant_kind::NullValue(_)) => Ok(ExprBuilder::null()),
+                Some(Constant_kind::BoolValue(b)) => Ok(ExprBuilder::bool(*b)),
+                Some(Constant_kind::Int64Value(i)) => Ok(ExprBuilder::int(*i)),
+                Some(Constant_kind::Uint64Value(u)) => Ok(ExprBuilder::uint(*u)),
+                Some(Constant_kind::DoubleValue(f)) => Ok(ExprBuilder::float(*f)),
+                Some(Constant_kind::StringValue(s)) => Ok(ExprBuilder::string(s)),
+                Some(Constant_kind::BytesValue(b)) => Ok(ExprBuilder::bytes(b)),
+                Some(Constant_kind::DurationValue(d)) => {
+                    let duration = chrono::Duration::new(d.seconds, 0)
+                        .and_then(|s| s.checked_add(&chrono::Duration::nanoseconds(d.nanos as i64)))
+                        .ok_or_else(|| CelError::value("duration constant out of range"))?;
+                    Ok(ExprBuilder::call("duration", [format_duration(&duration)]))
+                }
+                Some(Constant_kind::TimestampValue(t)) => {
+                    let ts = chrono::DateTime::from_timestamp(t.seconds, t.nanos as u32)
+                        .ok_or_else(|| CelError::value("timestamp constant out of range"))?;
+                    Ok(ExprBuilder::call("timestamp", [format_timestamp(&ts)]))
+                }
+                None => Err(CelError::value("constant is missing a value")),
+            },
+            Some(Expr_kind::IdentExpr(ident)) => Ok(qualified_ident(&ident.name)),
+            Some(Expr_kind::SelectExpr(select)) => {
+                let operand = self.import(&select.operand)?;
+                let member = operand.member(&select.field);
+
+                if select.test_only {
+                    Ok(ExprBuilder::call("has", [member]))
+                } else {
+                    Ok(member)
+                }
+            }
+            Some(Expr_kind::CallExpr(call)) => self.call(call),
+            Some(Expr_kind::ListExpr(list)) => {
+                if !list.optional_indices.is_empty() {
+                    return Err(CelError::value("optional list elements are not supported"));
+                }
+
+                Ok(ExprBuilder::list(
+                    list.elements
+                        .iter()
+                        .map(|e| self.import(e))
+                        .collect::<CelResult<Vec<_>>>()?,
+                ))
+            }
+            Some(Expr_kind::StructExpr(create)) => {
+                if !create.message_name.is_empty() {
+                    return Err(CelError::value(&format!(
+                        "message construction is not supported: {}",
+                        create.message_name
+                    )));
+                }
+
+                let mut entries = Vec::new();
+                for entry in create.entries.iter() {
+                    if entry.optional_entry {
+                        return Err(CelError::value("optional map entries are not supported"));
+                    }
+
+                    let key = match &entry.key_kind {
+                        Some(Key_kind::MapKey(key)) => self.import(key)?,
+                        Some(Key_kind::FieldKey(key)) => ExprBuilder::string(key),
+                        None => return Err(CelError::value("map entry is missing a key")),
+                    };
+                    entries.push((key, self.import(&entry.value)?));
+                }
+
+                Ok(ExprBuilder::map(entries))
+            }
+            Some(Expr_kind::ComprehensionExpr(comp)) => match self.macro_calls.get(&expr.id) {
+                Some(macro_call) => self.import(macro_call),
+                None => self.comprehension(comp),
+            },
+            None => match self.exprs.get(&expr.id) {
+                Some(referenced) if referenced.expr_kind.is_some() => self.import(referenced),
+                _ => Err(CelError::value(&format!(
+                    "expression {} has no kind",
+                    expr.id
+                ))),
+            },
+        }
+    }
+
+    fn call(&self, call: &Call) -> CelResult<ExprBuilder> {
+        let mut args = call
+            .args
+            .iter()
+            .map(|a| self.import(a))
+            .collect::<CelResult<Vec<_>>>()?
+            .into_iter();
+
+        if let Some(target) = call.target.as_ref() {
+            return Ok(self.import(target)?.method(&call.function, args));
+        }
+
+        let mut next = || {
+            args.next().ok_or_else(|| {
+                CelError::argument(&format!("too few arguments for {}", call.function))
+            })
+        };
+
+        let builder = match call.function.as_str() {
+            "_?_:_" => ExprBuilder::ternary(next()?, next()?, next()?),
+            "_||_" => next()?.or(next()?),
+            "_&&_" => next()?.and(next()?),
+            "_==_" => next()?.eq(next()?),
+            "_!=_" => next()?.ne(next()?),
+            "_<_" => next()?.lt(next()?),
+            "_<=_" => next()?.le(next()?),
+            "_>_" => next()?.gt(next()?),
+            "_>=_" => next()?.ge(next()?),
+            "@in" | "_in_" => next()?.in_(next()?),
+            "_+_" => next()? + next()?,
+            "_-_" => next()? - next()?,
+            "_*_" => next()? * next()?,
+            "_/_" => next()? / next()?,
+            "_%_" => next()? % next()?,
+            "!_" => !next()?,
+            "-_" => -next()?,
+            "_[_]" => next()?.index(next()?),
+            function if function.starts_with('_') || function.starts_with('@') => {
+                return Err(CelError::value(&format!(
+                    "unsupported operator: {}",
+                    function
+                )))
+            }
+            function => match function.rsplit_once('.') {
+                Some((namespace, name)) => qualified_ident(namespace).method(name, args),
+                None => ExprBuilder::call(function, args),
+            },
+        };
+
+        Ok(builder)
+    }
+
+    /// Recovers the macro call for comprehensions in the shape the reference
+    /// parsers expand them to.
+    fn comprehension(&self, comp: &Comprehension) -> CelResult<ExprBuilder> {
+        let accu = comp.accu_var.as_str();
+        let iter_var = ExprBuilder::ident(&comp.iter_var);
+        let range = self.import(&comp.iter_range)?;
+
+        let unsupported = || CelError::value("unsupported comprehension");
+        let call = |function: &str, args: &[&syntax::Expr]| -> CelResult<ExprBuilder> {
+            let mut imported = vec![iter_var.clone()];
+            for arg in args.iter() {
+                imported.push(self.import(arg)?);
+            }
+            Ok(range.clone().method(function, imported))
+        };
+
+        let step = &*comp.loop_step;
+        let result_is_accu = is_ident(&comp.result, accu);
+
+        match const_kind(&comp.accu_init) {
+            Some(Constant_kind::BoolValue(init)) if result_is_accu => {
+                let function = if *init { "_&&_" } else { "_||_" };
+                match call_args(step, function) {
+                    Some([lhs, pred]) if is_ident(lhs, accu) => {
+                        call(if *init { "all" } else { "exists" }, &[pred])
+                    }
+                    _ => Err(unsupported()),
+                }
+            }
+            Some(Constant_kind::Int64Value(0)) => match call_args(step, "_?_:_") {
+                Some([pred, inc, other]) if is_ident(other, accu) => match call_args(inc, "_+_") {
+                    Some([lhs, one]) if is_ident(lhs, accu) && is_int(one, 1) => {
+                        match call_args(&comp.result, "_==_") {
+                            Some([lhs, one]) if is_ident(lhs, accu) && is_int(one, 1) => {
+                                call("exists_one", &[pred])
+                            }
+                            _ => Err(unsupported()),
+                        }
+                    }
+                    _ => Err(unsupported()),
+                },
+                _ => Err(unsupported()),
+            },
+            _ if is_empty_list(&comp.accu_init) && result_is_accu => {
+                if let Some(element) = list_append(step, accu) {
+                    return call("map", &[element]);
+                }
+
+                match call_args(step, "_?_:_") {
+                    Some([pred, append, other]) if is_ident(other, accu) => {
+                        match list_append(append, accu) {
+                            Some(element) if is_ident(element, &comp.iter_var) => {
+                                call("filter", &[pred])
+                            }
+                            Some(element) => call("map", &[pred, element]),
+                            None => Err(unsupported()),
+                        }
+                    }
+                    _ => Err(unsupported()),
+                }
+            }
+            _ => Err(unsupported()),
+        }
+    }
+}
+
+/// Dotted names are split into member accesses the same way the parser does.
+fn qualified_ident(name: &str) -> ExprBuilder {
+    let mut parts = name.split('.');
+    let mut builder = ExprBuilder::ident(parts.next().unwrap_or_default());
+
+    for part in parts {
+        builder = builder.member(part);
+    }
+
+    builder
+}
+
+fn const_kind(expr: &syntax::Expr) -> Option<&Constant_kind> {
+    match &expr.expr_kind {
+        Some(Expr_kind::ConstExpr(constant)) => constant.constant_kind.as_ref(),
+        _ => None,
+    }
+}
+
+fn is_int(expr: &syntax::Expr, val: i64) -> bool {
+    matches!(const_kind(expr), Some(Constant_kind::Int64Value(i)) if *i == val)
+}
+
+fn is_ident(expr: &syntax::Expr, name: &str) -> bool {
+    matches!(&expr.expr_kind, Some(Expr_kind::IdentExpr(ident)) if ident.name == name)
+}
+
+fn is_empty_list(expr: &syntax::Expr) -> bool {
+    matches!(&expr.expr_kind, Some(Expr_kind::ListExpr(list)) if list.elements.is_empty())
+}
+
+fn call_args<'a>(expr: &'a syntax::Expr, function: &str) -> Option<&'a [syntax::Expr]> {
+    match &expr.expr_kind {
+        Some(Expr_kind::CallExpr(call)) if call.function == function && call.target.is_none() => {
+            Some(&call.args)
+        }
+        _ => None,
+    }
+}
+
+/// Matches `accu + [element]` returning the element.
+fn list_append<'a>(expr: &'a syntax::Expr, accu: &str) -> Option<&'a syntax::Expr> {
+    match call_args(expr, "_+_") {
+        Some([lhs, list]) if is_ident(lhs, accu) => match &list.expr_kind {
+            Some(Expr_kind::ListExpr(list)) if list.elements.len() == 1 => list.elements.first(),
+            _ => None,
+        },
+        _ => None,
+    }
+}
@@ -0,0 +1,167 @@
+//! Conversion between the rscel AST and the canonical CEL expression protos
+//! (`google.api.expr.v1alpha1`) used by cel-go, cel-cpp and cel-java.
+//!
+//! Standard macros (`has`, `all`, `exists`, `exists_one`, `map` and `filter`)
+//! are expanded into comprehensions on export, with the original call recorded
+//! in `SourceInfo.macro_calls`. On import comprehensions are turned back into
+//! macro calls, either from `macro_calls` or by recognizing the standard
+//! expansions. Format strings are lowered into string concatenation; `match`
+//! expressions have no canonical form and fail to export.
+//!
+//! ```
+//! use rscel::{expr_proto, Program};
+//!
+//! let prog = Program::from_source("[1, 2, 3].all(x, x > 0) && foo.bar == 'baz'").unwrap();
+//! let parsed = expr_proto::to_parsed_expr(prog.ast().unwrap(), prog.source()).unwrap();
+//!
+//! let ast = expr_proto::from_parsed_expr(&parsed).unwrap();
+//! assert_eq!(
+//!     rscel::CelCompiler::compile_ast(ast).unwrap().source(),
+//!     Some("[1, 2, 3].all(x, x > 0) && foo.bar == \"baz\"")
+//! );
+//! ```
+
+mod export;
+mod import;
+
+mod generated {
+    include!(concat!(env!("OUT_DIR"), "/cel_protos/mod.rs"));
+}
+
+pub use generated::{checked, syntax};
+
+use protobuf::MessageField;
+
+use crate::{AstNode, CelResult, Expr};
+use checked::CheckedExpr;
+use export::Exporter;
+use import::Importer;
+use syntax::ParsedExpr;
+
+/// Converts an AST into a `ParsedExpr`. When the source is provided the
+/// source info will contain positions for every expression.
+pub fn to_parsed_expr(ast: &AstNode<Expr>, source: Option<&str>) -> CelResult<ParsedExpr> {
+    let (expr, info) = Exporter::new(source).export(ast)?;
+
+    let mut parsed = ParsedExpr::new();
+    parsed.expr = MessageField::some(expr);
+    parsed.source_info = MessageField::some(info);
+
+    Ok(parsed)
+}
+
+/// Converts an AST into a `CheckedExpr`. rscel does not type check ahead of
+/// time so the reference and type maps are left empty.
+pub fn to_checked_expr(ast: &AstNode<Expr>, source: Option<&str>) -> CelResult<CheckedExpr> {
+    let (expr, info) = Exporter::new(source).export(ast)?;
+
+    let mut checked = CheckedExpr::new();
+    checked.expr = MessageField::some(expr);
+    checked.source_info = MessageField::some(info);
+
+    Ok(checked)
+}
+
+pub fn from_parsed_expr(parsed: &ParsedExpr) -> CelResult<AstNode<Expr>> {
+    let importer = Importer::new(&parsed.expr, &parsed.source_info);
+
+    Ok(importer.import(&parsed.expr)?.build())
+}
+
+/// Converts a `CheckedExpr` into an AST. Type information is not used.
+pub fn from_checked_expr(checked: &CheckedExpr) -> CelResult<AstNode<Expr>> {
+    let importer = Importer::new(&checked.expr, &checked.source_info);
+
+    Ok(importer.import(&checked.expr)?.build())
+}
+
+#[cfg(test)]
+mod test {
+    use protobuf::Message;
+    use test_case::test_case;
+
+    use super::{
+        from_checked_expr, from_parsed_expr, syntax::ParsedExpr, to_checked_expr, to_parsed_expr,
+    };
+    use crate::{BindContext, CelCompiler, CelContext, CelValue, Program};
+
+    fn eval(prog: Program) -> CelValue {
+        let mut ctx = CelContext::new();
+        let mut exec = BindContext::new();
+
+        ctx.add_program("main", prog);
+        exec.bind_param("x", 3.into());
+        exec.bind_param("m", CelValue::from_val_slice(&["a".into(), "b".into()]));
+
+        ctx.exec("main", &exec).unwrap()
+    }
+
+    #[test_case("x * 2 + 1 > -4 ? 'big' : 'small'"; "operators")]
+    #[test_case("!(x in [1, 2, 3]) || {'a': b'\\x01'}['a'] == b'\\x01'"; "collections")]
+    #[test_case("'abc'.startsWith('a') && size(m) == 2u"; "calls")]
+    #[test_case("[1, 2, 3].all(i, i > 0) && [1, 2].exists(i, i == x)"; "all exists")]
+    #[test_case("[1, 2, 3].exists_one(i, i == 2)"; "exists one")]
+    #[test_case("[1, 2, 3].map(i, i * x) + [1, 2, 3].map(i, i > 1, i) + [1, 2, 3].filter(i, i != 2)"; "map filter")]
+    #[test_case("has({'a': 1}.a) && !has({'a': 1}.b)"; "has")]
+    #[test_case("f'{x} is {x + 1.5}'"; "format string")]
+    fn round_trip(source: &str) {
+        let prog = Program::from_source(source).unwrap();
+        let expected = eval(prog.clone());
+
+        let parsed = to_parsed_expr(prog.ast().unwrap(), prog.source()).unwrap();
+
+        // Make sure the message survives the wire
+        let bytes = parsed.write_to_bytes().unwrap();
+        let parsed = ParsedExpr::parse_from_bytes(&bytes).unwrap();
+
+        let ast = from_parsed_expr(&parsed).unwrap();
+        assert_eq!(eval(CelCompiler::compile_ast(ast).unwrap()), expected);
+
+        let checked = to_checked_expr(prog.ast().unwrap(), prog.source()).unwrap();
+        let ast = from_checked_expr(&checked).unwrap();
+        assert_eq!(eval(CelCompiler::compile_ast(ast).unwrap()), expected);
+    }
+
+    #[test]
+    fn comprehension_without_macro_calls() {
+        let prog =
+            Program::from_source("[1, 2, 3].filter(i, i > 1).map(i, i * 2).exists(i, i == 6)")
+                .unwrap();
+
+        let mut parsed = to_parsed_expr(prog.ast().unwrap(), prog.source()).unwrap();
+        parsed
+            .source_info
+            .mut_or_insert_default()
+            .macro_calls
+            .clear();
+
+        let ast = from_parsed_expr(&parsed).unwrap();
+        let prog = CelCompiler::compile_ast(ast).unwrap();
+
+        assert_eq!(
+            prog.source(),
+            Some("[1, 2, 3].filter(i, i > 1).map(i, i * 2).exists(i, i == 6)")
+        );
+        assert_eq!(eval(prog), true.into());
+    }
+
+    #[test]
+    fn positions() {
+        let prog = Program::from_source("a +\n  b").unwrap();
+        let parsed = to_parsed_expr(prog.ast().unwrap(), prog.source()).unwrap();
+
+        let info = &parsed.source_info;
+        assert_eq!(info.line_offsets, vec![4, 8]);
+
+        let call = parsed.expr.call_expr();
+        assert_eq!(info.positions[&call.args[0].id], 0);
+        assert_eq!(info.positions[&call.args[1].id], 6);
+    }
+
+    #[test]
+    fn match_not_supported() {
+        let prog = Program::from_source("match x { case _: 1 }").unwrap();
+
+        assert!(to_parsed_expr(prog.ast().unwrap(), prog.source()).is_err());
+    }
+}
@@ -0,0 +1,128 @@
+use crate::{CelError, CelResult, CelValue, CelValueDyn};
+
+/// State of an in progress comprehension, the iteration variables bound for the
+/// current element and the accumulator.
+pub(super) struct CompFrame {
+    vars: Vec<String>,
+    values: Vec<CelValue>,
+    accu_name: String,
+    accu: CelValue,
+    iter: std::vec::IntoIter<Vec<CelValue>>,
+}
+
+impl CompFrame {
+    pub(super) fn new(
+        vars: Vec<String>,
+        accu_name: String,
+        range: CelValue,
+        init: CelValue,
+    ) -> Self {
+        let (iterations, accu) = match iteration_values(range, vars.len()) {
+            Ok(iterations) => (iterations, init),
+            Err(err) => (Vec::new(), CelValue::from_err(err)),
+        };
+
+        CompFrame {
+            vars,
+            values: Vec::new(),
+            accu_name,
+            accu,
+            iter: iterations.into_iter(),
+        }
+    }
+
+    /// Binds the next element, returns `false` once the comprehension is done.
+    pub(super) fn advance(&mut self) -> bool {
+        if self.accu.is_err() {
+            return false;
+        }
+
+        match self.iter.next() {
+            Some(values) => {
+                self.values = values;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub(super) fn set_accu(&mut self, value: CelValue) {
+        self.accu = value;
+    }
+
+    pub(super) fn append(&mut self, value: CelValue) {
+        let accu = std::mem::replace(&mut self.accu, CelValue::from_null());
+
+        self.accu = match (accu, value) {
+            (_, CelValue::Err(err)) => CelValue::from_err(err),
+            (CelValue::List(mut list), value) => {
+                list.push(value);
+                list.into()
+            }
+            (CelValue::Map(mut map), CelValue::Map(entry)) => {
+                for (key, value) in entry.into_iter() {
+                    if map.contains_key(&key) {
+                        return self.accu = CelValue::from_err(CelError::value(&format!(
+                            "insert failed: key {} already exists",
+                            key
+                        )));
+                    }
+                    map.insert(key, value);
+                }
+                map.into()
+            }
+            (accu @ CelValue::Err(_), _) => accu,
+            (accu, value) => CelValue::from_err(CelError::invalid_op(&format!(
+                "cannot append {} to {}",
+                value.as_type(),
+                accu.as_type()
+            ))),
+        };
+    }
+
+    /// Looks up an iteration variable or the accumulator.
+    pub(super) fn get(&self, name: &str) -> Option<&CelValue> {
+        if let Some(i) = self.vars.iter().position(|v| v == name) {
+            return self.values.get(i);
+        }
+
+        if self.accu_name == name {
+            Some(&self.accu)
+        } else {
+            None
+        }
+    }
+
+    pub(super) fn locals(&self) -> impl Iterator<Item = (&str, &CelValue)> {
+        self.vars
+            .iter()
+            .map(|v| v.as_str())
+            .zip(self.values.iter())
+            .chain(std::iter::once((self.accu_name.as_str(), &self.accu)))
+    }
+}
+
+/// Values bound to the iteration variables on each iteration. One variable receives list
+/// elements or map keys, two receive the list index and element or map key and value.
+/// With no variables a list is iterated without binding anything.
+pub(crate) fn iteration_values(range: CelValue, n_vars: usize) -> CelResult<Vec<Vec<CelValue>>> {
+    match (range, n_vars) {
+        (CelValue::List(list), 0) => Ok(list.into_iter().map(|_| Vec::new()).collect()),
+        (CelValue::List(list), 1) => Ok(list.into_iter().map(|v| vec![v]).collect()),
+        (CelValue::List(list), 2) => Ok(list
+            .into_iter()
+            .enumerate()
+            .map(|(i, v)| vec![CelValue::from_int(i as i64), v])
+            .collect()),
+        (CelValue::Map(map), 1) => Ok(map.into_keys().map(|k| vec![k.into()]).collect()),
+        (CelValue::Map(map), 2) => Ok(map.into_iter().map(|(k, v)| vec![k.into(), v]).collect()),
+        (CelValue::Err(err), _) => Err(err),
+        (CelValue::List(_) | CelValue::Map(_), _) => Err(CelError::argument(
+            "comprehensions expect one or two iteration variables",
+        )),
+        (other, _) => Err(CelError::value(&format!(
+            "cannot iterate over {}, expected list or map",
+            other.as_type()
+        ))),
+    }
+}
@@ -1,10 +1,12 @@
-pub use super::types::{ByteCode, CelStackValue, JmpWhen, RsCallable};
+use super::comprehension::CompFrame;
+pub use super::types::{ByteCode, CelStackValue, JmpWhen, QualifiedIdent, RsCallable};
 use crate::{types::CelByteCode, CelValueDyn};
-use std::{collections::HashMap, fmt};
+use chrono::{DateTime, Utc};
+use std::{cell::RefCell, collections::HashMap, fmt};
 
 use crate::{
-    context::construct_type, utils::ScopedCounter, BindContext, CelContext, CelError, CelResult,
-    CelValue, RsCelFunction, RsCelMacro,
+    utils::ScopedCounter, BindContext, CelContext, CelError, CelResult, CelValue, Dialect,
+    RsCelFunction, RsCelMacro,
 };
 
 struct InterpStack<'a, 'b> {
@@ -34,22 +36,13 @@ impl<'a, 'b> InterpStack<'a, 'b> {
             Some(stack_val) => {
                 if let CelStackValue::Value(val) = stack_val {
                     if let CelValue::Ident(name) = val {
-                        if let Some(val) = self.ctx.get_type_by_name(&name) {
-                            return Ok(CelStackValue::Value(val.clone()));
+                        match self.resolve_ident(&name) {
+                            Some(res) => res.map(|x| x.into()),
+                            None => Ok(CelValue::from_err(CelError::binding(
+                                name.split('.').next().unwrap_or(&name),
+                            ))
+                            .into()),
                         }
-
-                        if let Some(val) = self.ctx.get_param_by_name(&name) {
-                            return Ok(CelStackValue::Value(val.clone()));
-                        }
-
-                        if let Some(ctx) = self.ctx.cel {
-                            // Allow for loaded programs to run as values
-                            if let Some(prog) = ctx.get_program(&name) {
-                                return self.ctx.run_raw(prog.bytecode(), true).map(|x| x.into());
-                            }
-                        }
-
-                        Ok(CelValue::from_err(CelError::binding(&name)).into())
                     } else {
                         Ok(val.into())
                     }
@@ -61,6 +54,46 @@ impl<'a, 'b> InterpStack<'a, 'b> {
         }
     }
 
+    /// Resolves an ident by its exact name.
+    fn resolve_ident(&self, name: &str) -> Option<CelResult<CelValue>> {
+        match self.ctx.get_frame_value(name) {
+            Some(val) => Some(Ok(val)),
+            None => self.resolve_exact(name),
+        }
+    }
+
+    /// Finds the longest bound candidate of `ident`, returning its value and the number
+    /// of the name's idents it spells out.
+    fn resolve_prefix(&self, ident: &QualifiedIdent) -> Option<(CelResult<CelValue>, usize)> {
+        let root = ident.root();
+
+        // Comprehension variables shadow any qualified name
+        if let Some(val) = self.ctx.get_frame_value(root) {
+            return Some((Ok(val), 1));
+        }
+        if self.ctx.is_local(root) {
+            return self.resolve_exact(root).map(|val| (val, 1));
+        }
+
+        ident
+            .candidates()
+            .find_map(|(name, n)| self.resolve_exact(name).map(|val| (val, n)))
+    }
+
+    fn resolve_exact(&self, name: &str) -> Option<CelResult<CelValue>> {
+        if let Some(val) = self.ctx.get_type_by_name(name) {
+            return Some(Ok(val.clone()));
+        }
+
+        if let Some(val) = self.ctx.get_param_by_name(name) {
+            return Some(Ok(val.clone()));
+        }
+
+        // Allow for loaded programs to run as values
+        let prog = self.ctx.cel?.get_program(name)?;
+        Some(self.ctx.run_raw(prog.bytecode(), true))
+    }
+
     fn pop_val(&mut self) -> CelResult<CelValue> {
         self.pop()?.into_value()
     }
@@ -76,7 +109,9 @@ impl<'a, 'b> InterpStack<'a, 'b> {
         match self.stack.pop() {
             Some(val) => match val.try_into()? {
                 CelValue::Ident(name) => {
-                    if let Some(val) = self.ctx.get_param_by_name(&name) {
+                    if let Some(val) = self.ctx.get_frame_value(&name) {
+                        Ok(val.into())
+                    } else if let Some(val) = self.ctx.get_param_by_name(&name) {
                         Ok(val.clone().into())
                     } else {
                         Ok(CelStackValue::Value(CelValue::from_ident(&name)))
@@ -98,6 +133,9 @@ impl<'a, 'b> fmt::Debug for InterpStack<'a, 'b> {
 pub struct Interpreter<'a> {
     cel: Option<&'a CelContext>,
     bindings: Option<&'a BindContext<'a>>,
+    dialect: Dialect,
+    locals: Vec<String>,
+    frames: RefCell<Vec<CompFrame>>,
     depth: ScopedCounter,
 }
 
@@ -106,18 +144,56 @@ impl<'a> Interpreter<'a> {
         Interpreter {
             cel: Some(cel),
             bindings: Some(bindings),
+            dialect: Dialect::default(),
+            locals: Vec::new(),
+            frames: RefCell::new(Vec::new()),
             depth: ScopedCounter::new(),
         }
     }
 
+    /// Creates an interpreter for evaluating a macro body, `locals` are the variables
+    /// the macro binds and take precedence over qualified names.
+    pub fn child<'b>(
+        &self,
+        cel: &'b CelContext,
+        bindings: &'b BindContext,
+        locals: &[&'b str],
+    ) -> Interpreter<'b>
+    where
+        'a: 'b,
+    {
+        let mut child = Interpreter::new(cel, bindings).with_dialect(self.dialect);
+        child.locals = self
+            .locals
+            .iter()
+            .cloned()
+            .chain(self.frame_locals().into_iter().map(|(name, _)| name))
+            .chain(locals.iter().map(|l| l.to_string()))
+            .collect();
+        child
+    }
+
     pub fn empty() -> Interpreter<'a> {
         Interpreter {
             cel: None,
             bindings: None,
+            dialect: Dialect::default(),
+            locals: Vec::new(),
+            frames: RefCell::new(Vec::new()),
             depth: ScopedCounter::new(),
         }
     }
 
+    /// Evaluate with the rules of `dialect`, programs run by name use their own.
+    pub fn with_dialect(mut self, dialect: Dialect) -> Interpreter<'a> {
+        self.dialect = dialect;
+        self
+    }
+
+    pub fn dialect(&self) -> Dialect {
+        self.dialect
+    }
+
     pub fn add_bindings(&mut self, bindings: &'a BindContext) {
         self.bindings = Some(bindings);
     }
@@ -126,13 +202,36 @@ impl<'a> Interpreter<'a> {
         self.cel.cloned()
     }
 
+    /// A copy of the bindings with the variables of any running comprehension bound
+    /// as params.
     pub fn bindings_copy(&self) -> Option<BindContext<'_>> {
-        self.bindings.cloned()
+        let mut bindings = self.bindings.cloned()?;
+        for (name, value) in self.frame_locals().into_iter() {
+            bindings.bind_param(&name, value);
+        }
+        Some(bindings)
+    }
+
+    /// The current time according to the clock of the bindings.
+    pub fn now(&self) -> CelResult<DateTime<Utc>> {
+        match self.bindings {
+            Some(bindings) => bindings.now(),
+            None => Err(CelError::runtime("Invalid state: no bindings")),
+        }
     }
 
     pub fn run_program(&self, name: &str) -> CelResult<CelValue> {
         match self.cel {
             Some(cel) => match cel.get_program(name) {
+                Some(prog) if prog.details().dialect() != self.dialect => Interpreter {
+                    cel: self.cel,
+                    bindings: self.bindings,
+                    dialect: prog.details().dialect(),
+                    locals: Vec::new(),
+                    frames: RefCell::new(Vec::new()),
+                    depth: ScopedCounter::new(),
+                }
+                .run_raw(prog.bytecode(), true),
                 Some(prog) => self.run_raw(prog.bytecode(), true),
                 None => Err(CelError::binding(&name)),
             },
@@ -141,6 +240,14 @@ impl<'a> Interpreter<'a> {
     }
 
     pub fn run_raw(&self, prog: &CelByteCode, resolve: bool) -> CelResult<CelValue> {
+        // Drop any comprehension left open by an error
+        let n_frames = self.frames.borrow().len();
+        let res = self.run_bytecode(prog, resolve);
+        self.frames.borrow_mut().truncate(n_frames);
+        res
+    }
+
+    fn run_bytecode(&self, prog: &CelByteCode, resolve: bool) -> CelResult<CelValue> {
         let mut pc: usize = 0;
         let mut stack = InterpStack::new(self);
 
@@ -161,18 +268,7 @@ impl<'a> Interpreter<'a> {
                 ByteCode::Test => {
                     let v = stack.pop_val()?;
 
-                    if v.is_err() {
-                        stack.push_val(v);
-                    } else if cfg!(feature = "type_prop") {
-                        stack.push_val(v.is_truthy().into());
-                    } else if let CelValue::Bool(b) = v {
-                        stack.push_val(b.into())
-                    } else {
-                        return Err(CelError::invalid_op(&format!(
-                            "TEST invalid on type {:?}",
-                            v.as_type()
-                        )));
-                    }
+                    stack.push_val(self.dialect.test(v)?);
                 }
                 ByteCode::Dup => {
                     let v = stack.pop_val()?;
@@ -180,99 +276,39 @@ impl<'a> Interpreter<'a> {
                     stack.push_val(v.clone());
                     stack.push_val(v);
                 }
-                ByteCode::Or => {
-                    let v2 = stack.pop_val()?;
-                    let v1 = stack.pop_val()?;
-
-                    stack.push_val(v1.or(&v2))
-                }
-                ByteCode::And => {
-                    let v2 = stack.pop_val()?;
-                    let v1 = stack.pop_val()?;
-
-                    stack.push_val(v1.and(v2))
-                }
                 ByteCode::Not => {
                     let v1 = stack.pop_val()?;
 
-                    stack.push_val(!v1);
+                    stack.push_val(self.dialect.not(v1));
                 }
                 ByteCode::Neg => {
                     let v1 = stack.pop_val()?;
 
                     stack.push_val(-v1);
                 }
-                ByteCode::Add => {
-                    let v2 = stack.pop_val()?;
-                    let v1 = stack.pop_val()?;
-
-                    stack.push_val(v1 + v2);
-                }
-                ByteCode::Sub => {
-                    let v2 = stack.pop_val()?;
-                    let v1 = stack.pop_val()?;
-
-                    stack.push_val(v1 - v2);
-                }
-                ByteCode::Mul => {
-                    let v2 = stack.pop_val()?;
-                    let v1 = stack.pop_val()?;
-
-                    stack.push_val(v1 * v2);
-                }
-                ByteCode::Div => {
-                    let v2 = stack.pop_val()?;
-                    let v1 = stack.pop_val()?;
-
-                    stack.push_val(v1 / v2);
-                }
-                ByteCode::Mod => {
-                    let v2 = stack.pop_val()?;
-                    let v1 = stack.pop_val()?;
-
-                    stack.push_val(v1 % v2);
-                }
-                ByteCode::Lt => {
-                    let v2 = stack.pop_val()?;
-                    let v1 = stack.pop_val()?;
-
-                    stack.push_val(v1.lt(v2));
-                }
-                ByteCode::Le => {
-                    let v2 = stack.pop_val()?;
-                    let v1 = stack.pop_val()?;
-
-                    stack.push_val(v1.le(v2));
-                }
-                ByteCode::Eq => {
-                    let v2 = stack.pop_val()?;
-                    let v1 = stack.pop_val()?;
-
-                    stack.push_val(CelValueDyn::eq(&v1, &v2));
-                }
-                ByteCode::Ne => {
-                    let v2 = stack.pop_val()?;
-                    let v1 = stack.pop_val()?;
-
-                    stack.push_val(v1.neq(v2));
-                }
-                ByteCode::Ge => {
-                    let v2 = stack.pop_val()?;
-                    let v1 = stack.pop_val()?;
-
-                    stack.push_val(v1.ge(v2));
-                }
-                ByteCode::Gt => {
+                op @ (ByteCode::Or
+                | ByteCode::And
+                | ByteCode::Add
+                | ByteCode::Sub
+                | ByteCode::Mul
+                | ByteCode::Div
+                | ByteCode::Mod
+                | ByteCode::Lt
+                | ByteCode::Le
+                | ByteCode::Eq
+                | ByteCode::Ne
+                | ByteCode::Ge
+                | ByteCode::Gt) => {
                     let v2 = stack.pop_val()?;
                     let v1 = stack.pop_val()?;
 
-                    stack.push_val(v1.gt(v2));
+                    stack.push_val(self.dialect.binary(op, v1, v2));
                 }
                 ByteCode::In => {
                     let rhs = stack.pop_val()?;
                     let lhs = stack.pop_val()?;
 
-                    stack.push_val(lhs.in_(rhs));
+                    stack.push_val(self.dialect.in_(lhs, rhs));
                 }
                 ByteCode::Jmp(dist) => pc = Self::checked_jump_target(pc, *dist, prog.len())?,
                 ByteCode::JmpCond { when, dist } => {
@@ -296,6 +332,39 @@ impl<'a> Interpreter<'a> {
                         }
                     }
                 }
+                ByteCode::CompBegin { vars, accu } => {
+                    let init = stack.pop_val()?;
+                    let range = stack.pop_val()?;
+
+                    self.frames.borrow_mut().push(CompFrame::new(
+                        vars.clone(),
+                        accu.clone(),
+                        range,
+                        init,
+                    ));
+                }
+                ByteCode::CompNext(dist) => {
+                    if !self.with_frame(|frame| frame.advance())? {
+                        pc = Self::checked_jump_target(pc, *dist, prog.len())?
+                    }
+                }
+                ByteCode::CompAccu => {
+                    let v = stack.pop_val()?;
+                    self.with_frame(|frame| frame.set_accu(v))?;
+                }
+                ByteCode::CompAppend => {
+                    let v = stack.pop_val()?;
+                    self.with_frame(|frame| frame.append(v))?;
+                }
+                ByteCode::CompEnd => {
+                    let result = stack.pop_val()?;
+
+                    if self.frames.borrow_mut().pop().is_none() {
+                        return Err(CelError::runtime("No comprehension to end"));
+                    }
+
+                    stack.push_val(result);
+                }
                 ByteCode::MkList(size) => {
                     let mut v = Vec::new();
 
@@ -325,70 +394,17 @@ impl<'a> Interpreter<'a> {
                     let index = stack.pop_val()?;
                     let obj = stack.pop_val()?;
 
-                    stack.push_val(obj.index(index));
+                    stack.push_val(self.dialect.index(obj, index));
                 }
-                ByteCode::Access => {
+                op @ (ByteCode::Access | ByteCode::AccessMethod) => {
                     let index = stack.pop_noresolve()?;
                     if let CelValue::Ident(ident) = index.as_value()? {
                         let obj = stack.pop()?.into_value()?;
-                        match obj {
-                            CelValue::Map(ref map) => match map.get(ident.as_str()) {
-                                Some(val) => stack.push_val(val.clone()),
-                                None => match self.callable_by_name(ident.as_str()) {
-                                    Ok(callable) => stack.push(CelStackValue::BoundCall {
-                                        callable,
-                                        value: obj,
-                                    }),
-                                    Err(_) => {
-                                        stack.push(
-                                            CelValue::from_err(CelError::attribute(
-                                                "obj",
-                                                ident.as_str(),
-                                            ))
-                                            .into(),
-                                        );
-                                    }
-                                },
-                            },
-                            #[cfg(feature = "protobuf")]
-                            CelValue::Message(msg) => {
-                                let desc = msg.descriptor_dyn();
-
-                                if let Some(field) = desc.field_by_name(ident.as_str()) {
-                                    stack.push_val(
-                                        field.get_singular_field_or_default(msg.as_ref()).into(),
-                                    )
-                                } else {
-                                    return Err(CelError::attribute("msg", ident.as_str()));
-                                }
-                            }
-                            CelValue::Dyn(d) => {
-                                stack.push_val(d.access(ident.as_str()));
-                            }
-                            _ => {
-                                if let Some(bindings) = self.bindings {
-                                    if bindings.get_func(ident.as_str()).is_some()
-                                        || bindings.get_macro(ident.as_str()).is_some()
-                                    {
-                                        stack.push(CelStackValue::BoundCall {
-                                            callable: self.callable_by_name(ident.as_str())?,
-                                            value: obj,
-                                        });
-                                    } else {
-                                        stack.push(
-                                            CelValue::from_err(CelError::attribute(
-                                                "obj",
-                                                ident.as_str(),
-                                            ))
-                                            .into(),
-                                        );
-                                    }
-                                } else {
-                                    return Err(CelError::Runtime(
-                                        "Invalid state: no bindings".to_string(),
-                                    ));
-                                }
-                            }
+
+                        if matches!(op, ByteCode::AccessMethod) {
+                            stack.push(self.access_method(obj, ident)?);
+                        } else {
+                            stack.push_val(self.access_member(obj, ident)?);
                         }
                     } else {
                         let obj_type = stack.pop()?.into_value()?.as_type();
@@ -444,7 +460,12 @@ impl<'a> Interpreter<'a> {
                                         self.get_type_by_name(&func_name)
                                     {
                                         let arg_values = self.resolve_args(args)?;
-                                        stack.push_val(construct_type(type_name, arg_values));
+                                        stack.push_val(self.construct_type(type_name, arg_values));
+                                    } else if let Some((receiver, _)) = func_name.rsplit_once('.') {
+                                        // A method call on a receiver that never resolved
+                                        stack.push_val(CelValue::from_err(CelError::binding(
+                                            receiver.split('.').next().unwrap_or(receiver),
+                                        )));
                                     } else {
                                         stack.push_val(CelValue::from_err(CelError::runtime(
                                             &format!("{} is not callable", func_name),
@@ -453,7 +474,7 @@ impl<'a> Interpreter<'a> {
                                 }
                                 CelValue::Type(type_name) => {
                                     let arg_values = self.resolve_args(args)?;
-                                    stack.push_val(construct_type(&type_name, arg_values));
+                                    stack.push_val(self.construct_type(&type_name, arg_values));
                                 }
                                 other => stack.push_val(
                                     CelValue::from_err(CelError::runtime(&format!(
@@ -466,6 +487,49 @@ impl<'a> Interpreter<'a> {
                         }
                     };
                 }
+                ByteCode::Resolve(ident) => match stack.resolve_prefix(ident) {
+                    Some((val, n)) => {
+                        let mut val = val?;
+                        for member in ident.name().split('.').skip(n) {
+                            val = self.access_member(val, member)?;
+                        }
+                        stack.push_val(val);
+                    }
+                    None => stack.push_val(CelValue::from_ident(ident.name())),
+                },
+                ByteCode::ResolveCall(ident) => {
+                    let (receiver, method) =
+                        ident.name().rsplit_once('.').unwrap_or(("", ident.name()));
+                    let n_idents = ident.name().split('.').count();
+
+                    // A function bound under the full name wins over a method of a prefix
+                    match ident.candidates().find(|(name, n)| {
+                        *n == n_idents && !self.is_local(name) && self.is_callable(name)
+                    }) {
+                        Some((name, _)) => stack.push_val(CelValue::from_ident(name)),
+                        None => match stack.resolve_prefix(ident).filter(|(_, n)| *n < n_idents) {
+                            Some((val, n)) => {
+                                let mut val = val?;
+                                for member in receiver.split('.').skip(n) {
+                                    val = self.access_member(val, member)?;
+                                }
+                                stack.push(self.access_method(val, method)?);
+                            }
+                            None => stack.push_val(CelValue::from_ident(ident.name())),
+                        },
+                    }
+                }
+                ByteCode::FmtValue(spec) => {
+                    let value = match stack.pop_val()? {
+                        CelValue::ByteCode(bc) => self.run_raw(&bc, true)?,
+                        value => value,
+                    };
+
+                    stack.push_val(match spec.format(value) {
+                        Ok(s) => CelValue::String(s),
+                        Err(err) => CelValue::from_err(err),
+                    });
+                }
                 ByteCode::FmtString(nsegments) => {
                     let mut segments = Vec::new();
                     for _ in 0..*nsegments {
@@ -476,6 +540,8 @@ impl<'a> Interpreter<'a> {
                     for seg in segments.into_iter().rev() {
                         if let CelValue::String(s) = seg {
                             working.push_str(&s)
+                        } else if let CelValue::Err(err) = seg {
+                            return Err(err);
                         } else {
                             return Err(CelError::Runtime(
                                 "Expected string from format string specifier".to_string(),
@@ -537,6 +603,102 @@ impl<'a> Interpreter<'a> {
         Ok(arg_values)
     }
 
+    fn is_local(&self, name: &str) -> bool {
+        let root = name.split('.').next().unwrap_or(name);
+        self.locals.iter().any(|l| l == root)
+            || self.frames.borrow().iter().any(|f| f.get(root).is_some())
+    }
+
+    fn with_frame<T>(&self, f: impl FnOnce(&mut CompFrame) -> T) -> CelResult<T> {
+        match self.frames.borrow_mut().last_mut() {
+            Some(frame) => Ok(f(frame)),
+            None => Err(CelError::runtime("No comprehension in progress")),
+        }
+    }
+
+    /// Looks up a comprehension variable, innermost comprehension first.
+    fn get_frame_value(&self, name: &str) -> Option<CelValue> {
+        self.frames
+            .borrow()
+            .iter()
+            .rev()
+            .find_map(|f| f.get(name).cloned())
+    }
+
+    /// Variables of all running comprehensions, outermost first so inner ones shadow.
+    fn frame_locals(&self) -> Vec<(String, CelValue)> {
+        self.frames
+            .borrow()
+            .iter()
+            .flat_map(|f| f.locals().map(|(n, v)| (n.to_owned(), v.clone())))
+            .collect()
+    }
+
+    /// The `ident` member of `obj`.
+    fn access_member(&self, obj: CelValue, ident: &str) -> CelResult<CelValue> {
+        match obj {
+            CelValue::Map(ref map) => Ok(match map.get(ident) {
+                Some(val) => val.clone(),
+                None => CelValue::from_err(CelError::attribute("obj", ident)),
+            }),
+            #[cfg(feature = "protobuf")]
+            CelValue::Message(msg) => {
+                let desc = msg.descriptor_dyn();
+
+                match desc.field_by_name(ident) {
+                    Some(field) => Ok(field.get_singular_field_or_default(msg.as_ref()).into()),
+                    None => Err(CelError::attribute("msg", ident)),
+                }
+            }
+            CelValue::Dyn(ref d) => Ok(d.access(ident)),
+            _ => Ok(CelValue::from_err(CelError::attribute("obj", ident))),
+        }
+    }
+
+    /// The `ident` method of `obj` about to be called, a member of that name or else a
+    /// function bound to `obj`.
+    fn access_method(&self, obj: CelValue, ident: &str) -> CelResult<CelStackValue<'_>> {
+        let member = match obj {
+            CelValue::Map(ref map) => map.get(ident).cloned(),
+            #[cfg(feature = "protobuf")]
+            CelValue::Message(_) => return self.access_member(obj, ident).map(|val| val.into()),
+            CelValue::Dyn(ref d) => Some(d.access(ident)).filter(|val| !val.is_err()),
+            _ if self.bindings.is_none() => {
+                return Err(CelError::Runtime("Invalid state: no bindings".to_string()))
+            }
+            _ => None,
+        };
+
+        match member {
+            Some(val) => Ok(val.into()),
+            None => match self.callable_by_name(ident) {
+                Ok(callable) => Ok(CelStackValue::BoundCall {
+                    callable,
+                    value: obj,
+                }),
+                Err(_) => self.access_member(obj, ident).map(|val| val.into()),
+            },
+        }
+    }
+
+    fn is_callable(&self, name: &str) -> bool {
+        self.get_func_by_name(name).is_some()
+            || self.get_macro_by_name(name).is_some()
+            || self.get_type_by_name(name).is_some()
+    }
+
+    fn construct_type(&self, type_name: &str, args: Vec<CelValue>) -> CelValue {
+        // timestamp() is the current time, which only the bindings know
+        if type_name == "timestamp" && args.is_empty() {
+            return match self.now() {
+                Ok(ts) => ts.into(),
+                Err(err) => CelValue::from_err(err),
+            };
+        }
+
+        self.dialect.construct_type(type_name, args)
+    }
+
     fn get_param_by_name(&self, name: &str) -> Option<&'a CelValue> {
         self.bindings?.get_param(name)
     }
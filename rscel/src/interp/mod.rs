@@ -1,6 +1,8 @@
+mod comprehension;
 mod interp;
 mod types;
 
+pub(crate) use comprehension::iteration_values;
 pub use interp::Interpreter;
 pub use types::*;
 
@@ -55,4 +57,53 @@ mod test {
 
         assert_eq!(result, 2.into());
     }
+
+    #[test]
+    fn test_comprehension_sum() {
+        // [1, 2, 3].reduce(acc, x, acc + x, 0)
+        let prog = CelByteCode::from_vec(vec![
+            ByteCode::Push(CelValue::from_val_slice(&[1.into(), 2.into(), 3.into()])),
+            ByteCode::Push(0.into()),
+            ByteCode::CompBegin {
+                vars: vec!["x".to_owned()],
+                accu: "acc".to_owned(),
+            },
+            ByteCode::CompNext(5),
+            ByteCode::Push(CelValue::from_ident("acc")),
+            ByteCode::Push(CelValue::from_ident("x")),
+            ByteCode::Add,
+            ByteCode::CompAccu,
+            ByteCode::Jmp(-6),
+            ByteCode::Push(CelValue::from_ident("acc")),
+            ByteCode::CompEnd,
+        ]);
+
+        let interp = Interpreter::empty();
+        let result = interp.run_raw(&prog, true).unwrap();
+
+        assert_eq!(result, 6.into());
+    }
+
+    #[test]
+    fn test_comprehension_error_ends_loop() {
+        let prog = CelByteCode::from_vec(vec![
+            ByteCode::Push(CelValue::from_val_slice(&[1.into(), 2.into()])),
+            ByteCode::Push(Vec::<CelValue>::new().into()),
+            ByteCode::CompBegin {
+                vars: vec!["x".to_owned()],
+                accu: "@result".to_owned(),
+            },
+            ByteCode::CompNext(4),
+            ByteCode::Push(CelValue::from_ident("x")),
+            ByteCode::Push(0.into()),
+            ByteCode::Div,
+            ByteCode::CompAppend,
+            ByteCode::Push(CelValue::from_ident("@result")),
+            ByteCode::CompEnd,
+        ]);
+
+        let interp = Interpreter::empty();
+
+        assert!(interp.run_raw(&prog, true).is_err());
+    }
 }
@@ -2,6 +2,6 @@ mod bytecode;
 mod celstackvalue;
 mod rscallable;
 
-pub use bytecode::{ByteCode, JmpWhen};
+pub use bytecode::{ByteCode, JmpWhen, QualifiedIdent};
 pub use celstackvalue::CelStackValue;
 pub use rscallable::RsCallable;
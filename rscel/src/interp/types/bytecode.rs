@@ -2,7 +2,10 @@ use std::fmt;
 
 use serde::{Deserialize, Serialize};
 
-use crate::CelValue;
+use crate::{
+    utils::{qualified_names, FormatSpec},
+    CelValue,
+};
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum JmpWhen {
@@ -19,6 +22,50 @@ impl JmpWhen {
     }
 }
 
+/// A dotted name as written, `a.b.c`, with the names it may be bound as. The whole
+/// name may be bound or only a prefix of it, the remaining idents being members of
+/// the prefix's value.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct QualifiedIdent {
+    name: String,
+    /// Candidate names qualified with the container, longest first, each with the
+    /// number of idents of `name` it spells out
+    candidates: Vec<(String, usize)>,
+}
+
+impl QualifiedIdent {
+    /// Resolves `name` within `container`, see `utils::qualified_names`.
+    pub fn new(container: Option<&str>, name: &str) -> QualifiedIdent {
+        let idents: Vec<&str> = name.split('.').collect();
+        let candidates = (1..=idents.len())
+            .rev()
+            .flat_map(|n| {
+                qualified_names(container, &idents[..n].join("."))
+                    .into_iter()
+                    .map(move |candidate| (candidate, n))
+            })
+            .collect();
+
+        QualifiedIdent {
+            name: name.to_owned(),
+            candidates,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The first ident of the name, `a` in `a.b.c`.
+    pub fn root(&self) -> &str {
+        self.name.split('.').next().unwrap_or(&self.name)
+    }
+
+    pub fn candidates(&self) -> impl Iterator<Item = (&str, usize)> {
+        self.candidates.iter().map(|(name, n)| (name.as_str(), *n))
+    }
+}
+
 #[derive(Clone, PartialEq, Serialize, Deserialize)]
 pub enum ByteCode {
     Push(CelValue),
@@ -42,13 +89,43 @@ pub enum ByteCode {
     Gt,
     In,
     Jmp(i32),
-    JmpCond { when: JmpWhen, dist: i32 },
+    JmpCond {
+        when: JmpWhen,
+        dist: i32,
+    },
     MkList(u32),
     MkDict(u32),
     Index,
     Access,
+    /// Accesses a member that is called right after, a function bound to the object
+    /// when it has no such member.
+    AccessMethod,
     Call(u32),
+    /// Pushes the value of the longest bound candidate of a name, or the name as an
+    /// ident when none is bound.
+    Resolve(QualifiedIdent),
+    /// Pushes the callee of a name followed by a call, a function bound under one of
+    /// its full candidates or else a method of the longest bound prefix.
+    ResolveCall(QualifiedIdent),
+    FmtValue(FormatSpec),
     FmtString(u32),
+    /// Starts a comprehension over the iteration range below the accumulator's initial
+    /// value on the stack. `vars` are the iteration variables and `accu` names the
+    /// accumulator, both resolvable as idents until `CompEnd`.
+    CompBegin {
+        vars: Vec<String>,
+        accu: String,
+    },
+    /// Binds the iteration variables to the next element, or jumps when the range is
+    /// exhausted or the accumulator holds an error.
+    CompNext(i32),
+    /// Replaces the accumulator with the value on the stack.
+    CompAccu,
+    /// Appends the value on the stack to a list accumulator, or merges it into a map one.
+    CompAppend,
+    /// Ends the innermost comprehension, the value on the stack is its result and is
+    /// resolved before the comprehension's variables go out of scope.
+    CompEnd,
 }
 
 impl fmt::Debug for ByteCode {
@@ -82,8 +159,17 @@ impl fmt::Debug for ByteCode {
             MkDict(size) => write!(f, "MKDICT {}", size),
             Index => write!(f, "INDEX"),
             Access => write!(f, "ACCESS"),
+            AccessMethod => write!(f, "ACCESSMETHOD"),
             Call(size) => write!(f, "CALL {}", size),
+            Resolve(ident) => write!(f, "RESOLVE {}", ident.name()),
+            ResolveCall(ident) => write!(f, "RESOLVECALL {}", ident.name()),
+            FmtValue(spec) => write!(f, "FMTVAL {:?}", spec.to_string()),
             FmtString(size) => write!(f, "FMT {}", size),
+            CompBegin { vars, accu } => write!(f, "COMPBEGIN {:?} {}", vars, accu),
+            CompNext(dist) => write!(f, "COMPNEXT {}", dist),
+            CompAccu => write!(f, "COMPACCU"),
+            CompAppend => write!(f, "COMPAPPEND"),
+            CompEnd => write!(f, "COMPEND"),
         }
     }
 }
@@ -53,6 +53,8 @@
 //! ```
 mod compiler;
 mod context;
+#[cfg(feature = "protobuf")]
+pub mod expr_proto;
 mod interp;
 mod program;
 mod types;
@@ -60,13 +62,16 @@ mod types;
 // Export some public interface
 pub mod utils;
 pub use compiler::{
-    ast_node::AstNode, compiler::CelCompiler, grammar::*, source_location::SourceLocation,
+    ast_node::AstNode, ast_tokenizer::AstTokenizer, compiler::CelCompiler,
+    expr_builder::ExprBuilder, grammar::*, source_location::SourceLocation,
     source_range::SourceRange, string_tokenizer::StringTokenizer, tokenizer::Tokenizer,
 };
-pub use context::{BindContext, CelContext, RsCelFunction, RsCelMacro};
-pub use interp::ByteCode;
-pub use program::{Program, ProgramDetails};
-pub use types::{CelError, CelResult, CelValue, CelValueDyn};
+pub use context::{BindContext, CelContext, Clock, RsCelFunction, RsCelMacro};
+pub use interp::{ByteCode, QualifiedIdent};
+pub use program::{Dialect, Program, ProgramDetails};
+pub use types::{
+    CelError, CelOptional, CelQuantity, CelResult, CelValue, CelValueDyn, CelZonedTimestamp,
+};
 
 // Some re-exports to allow a consistent use of serde
 pub use serde;
@@ -0,0 +1,246 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    context::{construct_type, parse_bool_literal},
+    ByteCode, CelError, CelResult, CelValue, CelValueDyn,
+};
+
+/// The flavor of CEL a program is compiled and evaluated with.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Dialect {
+    /// CEL with rscel's extensions: match expressions, f-strings and, when built with the
+    /// `type_prop` and `neg_index` features, truthiness, mixed numeric arithmetic and
+    /// negative list indexes.
+    #[default]
+    Rscel,
+    /// CEL as the spec defines it, whichever features rscel is built with.
+    Strict,
+}
+
+impl Dialect {
+    pub fn is_strict(&self) -> bool {
+        *self == Dialect::Strict
+    }
+
+    /// Evaluates the binary operator `op`, one of the logical, relational or arithmetic
+    /// bytecodes.
+    pub(crate) fn binary(&self, op: &ByteCode, lhs: CelValue, rhs: CelValue) -> CelValue {
+        if self.is_strict() {
+            if let Some(res) = strict_binary(op, &lhs, &rhs) {
+                return res;
+            }
+        }
+
+        match op {
+            ByteCode::Or => lhs.or(&rhs),
+            ByteCode::And => lhs.and(rhs),
+            ByteCode::Add => lhs + rhs,
+            ByteCode::Sub => lhs - rhs,
+            ByteCode::Mul => lhs * rhs,
+            ByteCode::Div => lhs / rhs,
+            ByteCode::Mod => lhs % rhs,
+            ByteCode::Lt => lhs.lt(rhs),
+            ByteCode::Le => lhs.le(rhs),
+            ByteCode::Eq => CelValueDyn::eq(&lhs, &rhs),
+            ByteCode::Ne => lhs.neq(rhs),
+            ByteCode::Ge => lhs.ge(rhs),
+            ByteCode::Gt => lhs.gt(rhs),
+            _ => CelValue::from_err(CelError::internal(&format!(
+                "{:?} is not a binary operator",
+                op
+            ))),
+        }
+    }
+
+    /// Evaluates `lhs in rhs`, comparing list elements the way `==` does.
+    pub(crate) fn in_(&self, lhs: CelValue, rhs: CelValue) -> CelValue {
+        if self.is_strict() && !lhs.is_err() {
+            if let CelValue::List(list) = &rhs {
+                for value in list.iter() {
+                    match strict_eq(&lhs, value) {
+                        CelValue::Bool(false) => {}
+                        res => return res,
+                    }
+                }
+
+                return false.into();
+            }
+        }
+
+        lhs.in_(rhs)
+    }
+
+    pub(crate) fn not(&self, val: CelValue) -> CelValue {
+        match val {
+            CelValue::Bool(_) | CelValue::Err(_) => !val,
+            other if self.is_strict() => CelValue::from_err(CelError::invalid_op(&format!(
+                "Invalid op '!' on {:?}",
+                other.as_type()
+            ))),
+            other => !other,
+        }
+    }
+
+    /// The condition of a jump, strict CEL has no truthiness so anything other than a
+    /// bool is an error.
+    pub(crate) fn test(&self, val: CelValue) -> CelResult<CelValue> {
+        match val {
+            CelValue::Bool(_) | CelValue::Err(_) => Ok(val),
+            other if self.is_strict() => Ok(CelValue::from_err(CelError::invalid_op(&format!(
+                "TEST invalid on type {:?}",
+                other.as_type()
+            )))),
+            other if cfg!(feature = "type_prop") => Ok(other.is_truthy().into()),
+            other => Err(CelError::invalid_op(&format!(
+                "TEST invalid on type {:?}",
+                other.as_type()
+            ))),
+        }
+    }
+
+    pub(crate) fn index(&self, obj: CelValue, index: CelValue) -> CelValue {
+        if self.is_strict() {
+            if let (CelValue::List(_), CelValue::Int(i)) = (&obj, &index) {
+                if *i < 0 {
+                    return CelValue::from_err(CelError::value("Negative index is not allowed"));
+                }
+            }
+        }
+
+        obj.index(index)
+    }
+
+    pub(crate) fn construct_type(&self, type_name: &str, args: Vec<CelValue>) -> CelValue {
+        if self.is_strict() && type_name == "bool" {
+            match &args[..] {
+                [CelValue::Bool(_)] | [CelValue::Err(_)] => {}
+                [CelValue::String(s)] if parse_bool_literal(s).is_some() => {}
+                [CelValue::String(s)] => {
+                    return CelValue::from_err(CelError::Value(format!(
+                        "value '{}' cannot be converted to bool",
+                        s
+                    )))
+                }
+                [other] => {
+                    return CelValue::from_err(CelError::value(&format!(
+                        "{} cannot be converted to bool",
+                        other.as_type()
+                    )))
+                }
+                _ => {}
+            }
+        }
+
+        construct_type(type_name, args)
+    }
+}
+
+/// The result strict CEL gives where it disagrees with rscel, `None` when they agree.
+fn strict_binary(op: &ByteCode, lhs: &CelValue, rhs: &CelValue) -> Option<CelValue> {
+    match op {
+        ByteCode::And => Some(match (lhs, rhs) {
+            (CelValue::Bool(false), _) | (_, CelValue::Bool(false)) => false.into(),
+            (CelValue::Bool(true), CelValue::Bool(true)) => true.into(),
+            (CelValue::Err(_), _) => lhs.clone(),
+            (_, CelValue::Err(_)) => rhs.clone(),
+            _ => no_overload("&&", lhs, rhs),
+        }),
+        ByteCode::Or => Some(match (lhs, rhs) {
+            (CelValue::Bool(true), _) | (_, CelValue::Bool(true)) => true.into(),
+            (CelValue::Bool(false), CelValue::Bool(false)) => false.into(),
+            (CelValue::Err(_), _) => lhs.clone(),
+            (_, CelValue::Err(_)) => rhs.clone(),
+            _ => no_overload("||", lhs, rhs),
+        }),
+        // Arithmetic needs both sides to be the same numeric type, and bools are not
+        // numbers to do arithmetic on even when both sides are bools
+        ByteCode::Add | ByteCode::Sub | ByteCode::Mul | ByteCode::Div | ByteCode::Mod
+            if is_numeric(lhs)
+                && is_numeric(rhs)
+                && (lhs.as_type() != rhs.as_type() || matches!(lhs, CelValue::Bool(_))) =>
+        {
+            Some(no_overload(symbol(op), lhs, rhs))
+        }
+        // Numbers of different types compare by value, but never against a bool
+        ByteCode::Lt | ByteCode::Le | ByteCode::Ge | ByteCode::Gt if bool_and_number(lhs, rhs) => {
+            Some(no_overload(symbol(op), lhs, rhs))
+        }
+        ByteCode::Eq => Some(strict_eq(lhs, rhs)),
+        ByteCode::Ne => Some(match strict_eq(lhs, rhs) {
+            CelValue::Bool(res) => (!res).into(),
+            err => err,
+        }),
+        _ => None,
+    }
+}
+
+/// Equality where a bool never equals a number, including inside lists and maps.
+fn strict_eq(lhs: &CelValue, rhs: &CelValue) -> CelValue {
+    match (lhs, rhs) {
+        (CelValue::List(l), CelValue::List(r)) if l.len() == r.len() => {
+            for (v1, v2) in l.iter().zip(r.iter()) {
+                match strict_eq(v1, v2) {
+                    CelValue::Bool(true) => {}
+                    res => return res,
+                }
+            }
+
+            true.into()
+        }
+        (CelValue::Map(l), CelValue::Map(r)) if l.len() == r.len() => {
+            for (k, v1) in l.iter() {
+                let Some(v2) = r.get(k) else {
+                    return false.into();
+                };
+
+                match strict_eq(v1, v2) {
+                    CelValue::Bool(true) => {}
+                    res => return res,
+                }
+            }
+
+            true.into()
+        }
+        _ if bool_and_number(lhs, rhs) => false.into(),
+        _ => CelValueDyn::eq(lhs, rhs),
+    }
+}
+
+fn is_numeric(val: &CelValue) -> bool {
+    matches!(
+        val,
+        CelValue::Int(_) | CelValue::UInt(_) | CelValue::Float(_) | CelValue::Bool(_)
+    )
+}
+
+fn bool_and_number(lhs: &CelValue, rhs: &CelValue) -> bool {
+    match (lhs, rhs) {
+        (CelValue::Bool(_), CelValue::Bool(_)) => false,
+        (CelValue::Bool(_), other) | (other, CelValue::Bool(_)) => is_numeric(other),
+        _ => false,
+    }
+}
+
+fn symbol(op: &ByteCode) -> &'static str {
+    match op {
+        ByteCode::Add => "+",
+        ByteCode::Sub => "-",
+        ByteCode::Mul => "*",
+        ByteCode::Div => "/",
+        ByteCode::Mod => "%",
+        ByteCode::Lt => "<",
+        ByteCode::Le => "<=",
+        ByteCode::Ge => ">=",
+        ByteCode::Gt => ">",
+        _ => "?",
+    }
+}
+
+fn no_overload(symbol: &str, lhs: &CelValue, rhs: &CelValue) -> CelValue {
+    CelValue::from_err(CelError::invalid_op(&format!(
+        "Invalid op '{}' between {:?} and {:?}",
+        symbol,
+        lhs.as_type(),
+        rhs.as_type()
+    )))
+}
@@ -1,3 +1,4 @@
+mod dialect;
 mod program_details;
 
 use crate::{
@@ -5,6 +6,12 @@ use crate::{
     types::CelByteCode,
     CelCompiler, CelResult, StringTokenizer,
 };
+#[cfg(feature = "protobuf")]
+use crate::{
+    expr_proto::{self, checked::CheckedExpr, syntax::ParsedExpr},
+    CelError,
+};
+pub use dialect::Dialect;
 pub use program_details::ProgramDetails;
 use serde::{Deserialize, Serialize};
 
@@ -60,6 +67,32 @@ impl Program {
     pub fn ast<'a>(&'a self) -> Option<&'a AstNode<Expr>> {
         self.details.ast()
     }
+
+    #[cfg(feature = "protobuf")]
+    pub fn from_parsed_expr(parsed: &ParsedExpr) -> CelResult<Program> {
+        CelCompiler::compile_ast(expr_proto::from_parsed_expr(parsed)?)
+    }
+
+    #[cfg(feature = "protobuf")]
+    pub fn from_checked_expr(checked: &CheckedExpr) -> CelResult<Program> {
+        CelCompiler::compile_ast(expr_proto::from_checked_expr(checked)?)
+    }
+
+    #[cfg(feature = "protobuf")]
+    pub fn to_parsed_expr(&self) -> CelResult<ParsedExpr> {
+        match self.ast() {
+            Some(ast) => expr_proto::to_parsed_expr(ast, self.source()),
+            None => Err(CelError::misc("Program does not contain an ast")),
+        }
+    }
+
+    #[cfg(feature = "protobuf")]
+    pub fn to_checked_expr(&self) -> CelResult<CheckedExpr> {
+        match self.ast() {
+            Some(ast) => expr_proto::to_checked_expr(ast, self.source()),
+            None => Err(CelError::misc("Program does not contain an ast")),
+        }
+    }
 }
 
 impl Clone for Program {
@@ -3,6 +3,8 @@ use crate::{
     utils::IdentFilterIter,
     BindContext,
 };
+
+use super::Dialect;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 use std::collections::HashSet;
@@ -12,8 +14,14 @@ use std::collections::HashSet;
 pub struct ProgramDetails {
     source: Option<String>,
     params: HashSet<String>,
+    #[serde(default)]
+    container: Option<String>,
+    #[serde(default)]
+    dialect: Dialect,
     #[serde(skip_serializing, skip_deserializing)]
     ast: Option<AstNode<Expr>>,
+    #[serde(skip_serializing, skip_deserializing)]
+    comments: Vec<AstNode<String>>,
 }
 
 impl ProgramDetails {
@@ -21,7 +29,10 @@ impl ProgramDetails {
         ProgramDetails {
             source: None,
             params: HashSet::new(),
+            container: None,
+            dialect: Dialect::default(),
             ast: None,
+            comments: Vec::new(),
         }
     }
 
@@ -34,10 +45,32 @@ impl ProgramDetails {
         self.ast = Some(ast);
     }
 
+    pub fn add_comment(&mut self, comment: AstNode<String>) {
+        self.comments.push(comment);
+    }
+
     pub fn add_source(&mut self, source: String) {
         self.source = Some(source);
     }
 
+    pub fn set_container(&mut self, container: Option<String>) {
+        self.container = container;
+    }
+
+    /// The container names in the program were resolved against, `pkg` in `pkg.Msg`.
+    pub fn container<'a>(&'a self) -> Option<&'a str> {
+        self.container.as_deref()
+    }
+
+    pub fn set_dialect(&mut self, dialect: Dialect) {
+        self.dialect = dialect;
+    }
+
+    /// The dialect the program was compiled with and is evaluated in.
+    pub fn dialect(&self) -> Dialect {
+        self.dialect
+    }
+
     pub fn union_from(&mut self, other: ProgramDetails) {
         for param in other.params.iter() {
             self.params.insert(param.to_string());
@@ -52,10 +85,19 @@ impl ProgramDetails {
         self.source.as_deref()
     }
 
+    /// Comments found in the source, including their delimiters.
+    pub fn comments<'a>(&'a self) -> &'a [AstNode<String>] {
+        &self.comments
+    }
+
     pub fn add_param(&mut self, name: &str) {
         self.params.insert(name.to_owned());
     }
 
+    pub fn remove_param(&mut self, name: &str) {
+        self.params.remove(name);
+    }
+
     pub fn params<'a>(&'a self) -> Vec<&'a str> {
         self.params.iter().map(|x| x.as_str()).collect()
     }
@@ -0,0 +1,145 @@
+use std::cell::Cell;
+
+use chrono::{DateTime, Duration, Utc};
+use test_case::test_case;
+
+use crate::{BindContext, CelContext, CelValue, Clock};
+
+fn fixed_time() -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339("2024-02-29T12:30:00Z")
+        .unwrap()
+        .to_utc()
+}
+
+#[test_case("now()", fixed_time().into(); "now")]
+#[test_case("timestamp()", fixed_time().into(); "timestamp")]
+#[test_case("now() == timestamp()", true.into(); "now and timestamp agree")]
+#[test_case("now() - duration('1h')", (fixed_time() - Duration::hours(1)).into(); "an hour ago")]
+#[test_case("now().getDayOfMonth()", 28.into(); "accessor")]
+#[test_case("[1, 2].map(x, now()).all(t, t == now())", true.into(); "inside a macro")]
+fn test_fixed_clock(prog: &str, res: CelValue) {
+    let mut ctx = CelContext::new();
+    let mut exec_ctx = BindContext::new();
+
+    ctx.add_program_str("main", prog).unwrap();
+    exec_ctx.set_clock(Clock::Fixed(fixed_time()));
+
+    assert_eq!(ctx.exec("main", &exec_ctx).unwrap(), res);
+}
+
+#[test]
+fn test_offset_clock() {
+    let mut ctx = CelContext::new();
+    let mut exec_ctx = BindContext::new();
+
+    ctx.add_program_str("main", "now()").unwrap();
+    exec_ctx.set_clock(Clock::Offset(Duration::days(-365)));
+
+    let CelValue::TimeStamp(ts) = ctx.exec("main", &exec_ctx).unwrap() else {
+        panic!("now() should return a timestamp");
+    };
+
+    let delta = (Utc::now() - Duration::days(365)) - ts;
+    assert!(
+        delta.num_seconds().abs() <= 5,
+        "offset delta too large: {delta}"
+    );
+}
+
+#[test]
+fn test_custom_clock() {
+    let mut ctx = CelContext::new();
+    let mut exec_ctx = BindContext::new();
+    let ticks = Cell::new(0);
+
+    ctx.add_program_str("main", "now()").unwrap();
+    exec_ctx.set_clock(Clock::custom(move || {
+        ticks.set(ticks.get() + 1);
+        fixed_time() + Duration::seconds(ticks.get())
+    }));
+
+    assert_eq!(
+        ctx.exec("main", &exec_ctx).unwrap(),
+        (fixed_time() + Duration::seconds(1)).into()
+    );
+    assert_eq!(
+        ctx.exec("main", &exec_ctx).unwrap(),
+        (fixed_time() + Duration::seconds(2)).into()
+    );
+}
+
+#[test]
+fn test_clock_per_evaluation() {
+    let mut ctx = CelContext::new();
+    let mut first = BindContext::new();
+    let mut second = BindContext::new();
+
+    // The time is read when evaluating, never folded into the compiled program
+    ctx.add_program_str("main", "timestamp() + duration('1s')")
+        .unwrap();
+    first.set_clock(Clock::Fixed(fixed_time()));
+    second.set_clock(Clock::Fixed(fixed_time() + Duration::days(1)));
+
+    assert_eq!(
+        ctx.exec("main", &first).unwrap(),
+        (fixed_time() + Duration::seconds(1)).into()
+    );
+    assert_eq!(
+        ctx.exec("main", &second).unwrap(),
+        (fixed_time() + Duration::days(1) + Duration::seconds(1)).into()
+    );
+}
+
+#[test_case("now()"; "now")]
+#[test_case("timestamp()"; "timestamp")]
+#[test_case("now() > timestamp('2020-01-01T00:00:00Z')"; "comparison")]
+fn test_deterministic_without_clock(prog: &str) {
+    let mut ctx = CelContext::new();
+    let mut exec_ctx = BindContext::new();
+
+    ctx.add_program_str("main", prog).unwrap();
+    exec_ctx.set_deterministic(true);
+
+    assert!(ctx.exec("main", &exec_ctx).is_err());
+
+    exec_ctx.set_clock(Clock::Fixed(fixed_time()));
+    assert!(ctx.exec("main", &exec_ctx).is_ok());
+}
+
+#[test_case("now()"; "now")]
+#[test_case("timestamp()"; "timestamp")]
+fn test_deterministic_offset_clock(prog: &str) {
+    let mut ctx = CelContext::new();
+    let mut exec_ctx = BindContext::new();
+
+    ctx.add_program_str("main", prog).unwrap();
+    exec_ctx.set_clock(Clock::Offset(Duration::days(-1)));
+    assert!(ctx.exec("main", &exec_ctx).is_ok());
+
+    exec_ctx.set_deterministic(true);
+    assert!(ctx.exec("main", &exec_ctx).is_err());
+}
+
+#[test_case("timestamp('2024-02-29T12:30:00Z')", fixed_time().into(); "parsed timestamp")]
+#[test_case("duration('1h') + duration('1m')", Duration::minutes(61).into(); "durations")]
+#[test_case("1 + 2", 3.into(); "arithmetic")]
+fn test_deterministic_pure(prog: &str, res: CelValue) {
+    let mut ctx = CelContext::new();
+    let mut exec_ctx = BindContext::new();
+
+    ctx.add_program_str("main", prog).unwrap();
+    exec_ctx.set_deterministic(true);
+
+    assert_eq!(ctx.exec("main", &exec_ctx).unwrap(), res);
+}
+
+#[test]
+fn test_now_arguments() {
+    let mut ctx = CelContext::new();
+    let mut exec_ctx = BindContext::new();
+
+    ctx.add_program_str("main", "now(1)").unwrap();
+    exec_ctx.set_clock(Clock::Fixed(fixed_time()));
+
+    assert!(ctx.exec("main", &exec_ctx).is_err());
+}
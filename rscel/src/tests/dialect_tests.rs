@@ -0,0 +1,130 @@
+use crate::{BindContext, CelCompiler, CelContext, CelValue, Dialect, Program, StringTokenizer};
+use test_case::test_case;
+
+fn compile(source: &str, dialect: Dialect) -> Program {
+    let mut tokenizer = StringTokenizer::with_input(source);
+    CelCompiler::with_tokenizer(&mut tokenizer)
+        .with_dialect(dialect)
+        .compile()
+        .unwrap()
+}
+
+#[test_case("3 + 2.1"; "int plus float")]
+#[test_case("2 + 1u"; "int plus unsigned")]
+#[test_case("true + 1"; "bool plus int")]
+#[test_case("true + true"; "bool plus bool")]
+#[test_case("false * true"; "bool times bool")]
+#[test_case("1 && true"; "int and bool")]
+#[test_case("0 || 1u"; "int or unsigned")]
+#[test_case("!1"; "not int")]
+#[test_case("1 ? 1 : 2"; "int as condition")]
+#[test_case("true < 1"; "bool less than int")]
+#[test_case("[1, 2, 3][-1]"; "negative index")]
+#[test_case("bool('abc')"; "bool of non literal string")]
+#[test_case("bool(1)"; "bool of int")]
+#[test_case("[1, 2].all(x, x)"; "int as predicate")]
+fn test_strict_errors(prog: &str) {
+    let mut ctx = CelContext::new();
+    let exec_ctx = BindContext::new();
+
+    ctx.add_program("main", compile(prog, Dialect::Strict));
+
+    let res = ctx.exec("main", &exec_ctx);
+    assert!(
+        res.is_err(),
+        "expected an error from {} but got {:?}",
+        prog,
+        res
+    );
+}
+
+#[test_case("1 < 1.5", true.into(); "int less than float")]
+#[test_case("2u > 1", true.into(); "unsigned greater than int")]
+#[test_case("1 == 1.0", true.into(); "int equals float")]
+#[test_case("true == 1", false.into(); "bool equals int")]
+#[test_case("true != 1", true.into(); "bool not equals int")]
+#[test_case("true in [1]", false.into(); "bool in int list")]
+#[test_case("1 in [true, 1]", true.into(); "int in mixed list")]
+#[test_case("[true] == [1]", false.into(); "bool list equals int list")]
+#[test_case("[true] != [1]", true.into(); "bool list not equals int list")]
+#[test_case("{'a': true} == {'a': 1}", false.into(); "bool map equals int map")]
+#[test_case("[[1]] == [[1.0]]", true.into(); "nested numeric lists")]
+#[test_case("false && 1", false.into(); "false and int")]
+#[test_case("true || 1", true.into(); "true or int")]
+#[test_case("1 / 0 > 0 || true", true.into(); "error or true")]
+#[test_case("[1, 2, 3][2]", 3.into(); "positive index")]
+#[test_case("bool('true')", true.into(); "bool of literal string")]
+#[test_case("[1, 2].filter(x, x > 1)", CelValue::from_val_slice(&[2.into()]); "filter")]
+fn test_strict_equation(prog: &str, res: CelValue) {
+    let mut ctx = CelContext::new();
+    let exec_ctx = BindContext::new();
+
+    ctx.add_program("main", compile(prog, Dialect::Strict));
+
+    assert_eq!(ctx.exec("main", &exec_ctx).unwrap(), res);
+}
+
+#[test_case("a + b", None; "int plus float")]
+#[test_case("a < b", Some(false.into()); "int less than float")]
+#[test_case("!a", None; "not int")]
+#[test_case("a || true", Some(true.into()); "int or true")]
+#[test_case("a && true", None; "int and true")]
+#[test_case("true in l", Some(false.into()); "bool in int list")]
+#[test_case("[true, false] == l", Some(false.into()); "bool list equals int list")]
+#[test_case("l[-1]", None; "negative index")]
+#[test_case("l.exists(x, x)", None; "int as predicate")]
+fn test_strict_params(prog: &str, res: Option<CelValue>) {
+    let mut ctx = CelContext::new();
+    let mut exec_ctx = BindContext::new();
+
+    ctx.add_program("main", compile(prog, Dialect::Strict));
+    exec_ctx.bind_param("a", 3.into());
+    exec_ctx.bind_param("b", 2.1.into());
+    exec_ctx.bind_param("l", CelValue::from_val_slice(&[1.into(), 2.into()]));
+
+    match res {
+        Some(res) => assert_eq!(ctx.exec("main", &exec_ctx).unwrap(), res),
+        None => assert!(ctx.exec("main", &exec_ctx).is_err()),
+    }
+}
+
+#[test_case("match 1 { case int: 1, case _: 2 }"; "match expression")]
+#[test_case("f'{1}'"; "f-string")]
+#[test_case("5kg"; "unit literal")]
+fn test_strict_syntax(prog: &str) {
+    let mut tokenizer = StringTokenizer::with_input(prog);
+
+    assert!(CelCompiler::with_tokenizer(&mut tokenizer)
+        .with_dialect(Dialect::Strict)
+        .compile()
+        .is_err());
+
+    let mut tokenizer = StringTokenizer::with_input(prog);
+    assert!(CelCompiler::with_tokenizer(&mut tokenizer)
+        .compile()
+        .is_ok());
+}
+
+#[test]
+fn test_dialect_per_program() {
+    let mut ctx = CelContext::new();
+    let exec_ctx = BindContext::new();
+
+    ctx.add_program("rscel", compile("[1, 2, 3][2] + 2", Dialect::Rscel));
+    ctx.add_program("strict", compile("3 + 2.1", Dialect::Strict));
+    assert_eq!(ctx.exec("rscel", &exec_ctx).unwrap(), 5.into());
+    assert!(ctx.exec("strict", &exec_ctx).is_err());
+
+    // Serialized programs remember their dialect
+    let json = serde_json::to_string(&compile("2 + 1u", Dialect::Strict)).unwrap();
+    let prog: Program = serde_json::from_str(&json).unwrap();
+    assert_eq!(prog.details().dialect(), Dialect::Strict);
+
+    ctx.add_program("loaded", prog);
+    assert!(ctx.exec("loaded", &exec_ctx).is_err());
+
+    // Programs serialized before dialects existed are rscel programs
+    let legacy: Program = serde_json::from_str(&json.replace(",\"dialect\":\"Strict\"", ""))
+        .expect("Failed to load program");
+    assert_eq!(legacy.details().dialect(), Dialect::Rscel);
+}
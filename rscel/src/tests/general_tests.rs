@@ -1,6 +1,7 @@
 use crate::{
     compiler::{compiler::CelCompiler, string_tokenizer::StringTokenizer},
-    BindContext, CelContext, CelError, CelValue, Program,
+    BindContext, ByteCode, CelContext, CelError, CelValue, CelZonedTimestamp, Program,
+    QualifiedIdent,
 };
 use chrono::{DateTime, Duration, TimeZone, Utc};
 use serde_json::Value;
@@ -30,6 +31,9 @@ fn test_contains() {
 }
 
 #[test_case("3+3", 6; "add signed")]
+#[test_case("3 // three\n + 4 // four", 7; "line comments")]
+#[test_case("// leading\n6 / 2", 3; "leading comment")]
+#[test_case("'a // b' + f'//{1 / 1}'", "a // b//1"; "comments in strings")]
 #[test_case("4-3", 1; "sub signed")]
 #[test_case("4u + 3u", 7u64; "add unsigned")]
 #[test_case("7 % 2", 1; "test mod")]
@@ -43,6 +47,14 @@ fn test_contains() {
 #[test_case("size([1,2,3,4])", 4u64; "test list size")]
 #[test_case("size('foo')", 3u64; "size string")]
 #[test_case("size(b'foo')", 3u64; "size bytes")]
+#[test_case("'''line 1\nline 2'''", "line 1\nline 2"; "triple single quote")]
+#[test_case("\"\"\"it's \"quoted\" ''\"\"\"", "it's \"quoted\" ''"; "triple double quote")]
+#[test_case("'''two '' quotes'''", "two '' quotes"; "triple with inner quotes")]
+#[test_case("r'''\\d+\n\\w'''", "\\d+\n\\w"; "raw triple")]
+#[test_case("b'''a\nb'''", crate::types::CelBytes::from_vec(vec![97u8, 10u8, 98u8]); "bytes triple")]
+#[test_case("f'''{1 + 1}\n{'x'}'''", "2\nx"; "fstring triple")]
+#[test_case("'' + \"\" + r'' + f''", ""; "empty strings")]
+#[test_case("size(b'') + size(b\"\")", 0u64; "empty bytes")]
 #[test_case("'foo'.size()", 3u64; "string size")]
 #[test_case("b'foo'.size()", 3u64; "bytes size")]
 #[test_case("true || false", true; "or")]
@@ -166,6 +178,16 @@ fn test_contains() {
 #[test_case(r#"f"{3}""#, "3"; "test basic format string")]
 #[test_case(r#"f"{({"foo": 3}).foo)}""#, "3"; "test fstring with map")]
 #[test_case(r#"f"{[1,2,3][2]}""#, "3"; "test fstring with list")]
+#[test_case("f'{3.14159:.2f}'", "3.14"; "fstring float precision")]
+#[test_case("f'[{5:>5}]'", "[    5]"; "fstring align right")]
+#[test_case("f'{255:x}'", "ff"; "fstring hex")]
+#[test_case("f'{1234567:,}'", "1,234,567"; "fstring grouping")]
+#[test_case("f'{timestamp(\"2023-04-20T12:00:00Z\"):%Y-%m-%d}'", "2023-04-20"; "fstring strftime")]
+#[test_case("f'{true ? 1 : 2}'", "1"; "fstring ternary")]
+#[test_case("f'{true ? 1.5 : 2.0:.1f}'", "1.5"; "fstring ternary with spec")]
+#[test_case("f'{ {\"a\": 1}[\"a\"]:03}'", "001"; "fstring map with spec")]
+#[test_case("f'{\"a:b\"}'", "a:b"; "fstring colon in string")]
+#[test_case("f'{true} {null} {[1, 2]}'", "true null [1, 2]"; "fstring auto string")]
 #[test_case("timestamp('2024-07-30 12:00:00+00:00') - timestamp('2024-07-30 11:55:00+00:00') == duration('5m')", true; "test timestamp sub 1")]
 #[test_case("timestamp('2024-07-30 11:55:00+00:00') - timestamp('2024-07-30 12:00:00+00:00')", Duration::new(-300, 0).unwrap(); "test timestamp sub 2")]
 #[test_case("timestamp('2023-12-25T12:00:00Z').getDayOfMonth()", 24; "getDayOfMonth")]
@@ -174,6 +196,100 @@ fn test_contains() {
 #[test_case("uint(1u)", 1u64; "identity -- uint")]
 #[test_case("double(5.5)", 5.5; "identity -- double")]
 #[test_case("string('hello')", "hello"; "identity -- string")]
+#[test_case("math.greatest(-7, 5)", 5; "math greatest")]
+#[test_case("math.greatest(1u, 1)", 1u64; "math greatest keeps first")]
+#[test_case("math.greatest(5.4, 10, 3u, -5.0, 18446744073709551615u)", u64::MAX; "math greatest mixed")]
+#[test_case("math.greatest([5.4, 10.5, 3u, -5.0, 3.5])", 10.5; "math greatest list")]
+#[test_case("math.greatest(-3, 3u)", 3u64; "math greatest int uint")]
+#[test_case("math.least(-9223372036854775807 - 1, -1, -5)", i64::MIN; "math least")]
+#[test_case("math.least([dyn(5.4), dyn(10u), dyn(3u), dyn(1u), dyn(3.5)])", 1u64; "math least list")]
+#[test_case("math.least(1.797693e308, 1, 5)", 1; "math least mixed")]
+#[test_case("math.bitAnd(3, 5) == 1 && math.bitOr(3u, 5u) == 7u && math.bitXor(3, 5) == 6", true; "math bit ops")]
+#[test_case("math.bitNot(1)", -2; "math bitNot int")]
+#[test_case("math.bitNot(0u)", u64::MAX; "math bitNot uint")]
+#[test_case("math.bitShiftLeft(1, 2)", 4; "math bitShiftLeft")]
+#[test_case("math.bitShiftLeft(1u, 200)", 0u64; "math bitShiftLeft overflow")]
+#[test_case("math.bitShiftRight(-1024, 3)", 2305843009213693824i64; "math bitShiftRight logical")]
+#[test_case("math.isNaN(double('NaN')) && math.isInf(1.0 / 0.0) && math.isFinite(1.2)", true; "math classify")]
+#[test_case("math.isFinite(double('NaN'))", false; "math isFinite nan")]
+#[test_case("math.abs(-1)", 1; "math abs")]
+#[test_case("math.sqrt(49.0)", 7.0; "math sqrt")]
+#[test_case("[math.ceil(1.2), math.floor(-1.2)]", vec![CelValue::from(2.0), CelValue::from(-2.0)]; "math ceil floor")]
+#[test_case("[math.round(1.5), math.round(-1.5)]", vec![CelValue::from(2.0), CelValue::from(-2.0)]; "math round")]
+#[test_case("math.trunc(-1.7)", -1.0; "math trunc")]
+#[test_case("[math.sign(-5), math.sign(0)]", vec![CelValue::from(-1), CelValue::from(0)]; "math sign int")]
+#[test_case("math.sign(5u)", 1u64; "math sign uint")]
+#[test_case("math.sign(-0.5)", -1.0; "math sign double")]
+#[test_case("base64.encode(b'hello')", "aGVsbG8="; "base64 encode")]
+#[test_case("base64.decode('aGVsbG8=') == b'hello'", true; "base64 decode")]
+#[test_case("base64.decode('aGVsbG8') == b'hello'", true; "base64 decode without padding")]
+#[test_case("base64.decode(base64.encode(b'Hello World!'))", crate::types::CelBytes::from_vec(b"Hello World!".to_vec()); "base64 round trip")]
+#[test_case("hex.encode(b'\\x01\\xab') + hex.encode('Hi')", "01ab4869"; "hex encode")]
+#[test_case("hex.decode('01AB') == b'\\x01\\xab'", true; "hex decode")]
+#[test_case("url.encode('a b&c=d/é')", "a%20b%26c%3Dd%2F%C3%A9"; "url encode")]
+#[test_case("url.decode('a%20b%26c%3Dd%2F%C3%A9')", "a b&c=d/é"; "url decode")]
+#[test_case("sets.contains([], [])", true; "sets contains empty")]
+#[test_case("sets.contains([1, 2, 3], [3, 1])", true; "sets contains")]
+#[test_case("sets.contains([1, 2], [2, 2, 4])", false; "sets contains missing")]
+#[test_case("sets.contains([1, 2u, 3.0], [1u, 2.0, 3])", true; "sets contains numeric")]
+#[test_case("sets.contains([[1], {'a': 2}], [[1.0], {'a': 2u}])", true; "sets contains nested")]
+#[test_case("sets.contains(['a', b'b', null], [null, 'a'])", true; "sets contains mixed")]
+#[test_case("sets.contains([0.0], [-0.0])", true; "sets contains negative zero")]
+#[test_case("sets.contains([double('NaN')], [double('NaN')])", false; "sets contains nan")]
+#[test_case("sets.equivalent([1, 1, 2], [2u, 1.0])", true; "sets equivalent")]
+#[test_case("sets.equivalent([1, 2], [1, 2, 3])", false; "sets not equivalent")]
+#[test_case("sets.intersects([1, 2], [5, 2.0])", true; "sets intersects")]
+#[test_case("sets.intersects([1, 2], [])", false; "sets intersects empty")]
+#[test_case("sets.intersects([timestamp(0)], [timestamp(0), 'a'])", true; "sets intersects timestamp")]
+#[test_case("[1, 2, 3, 4].slice(1, 3)", vec![CelValue::from(2), CelValue::from(3)]; "lists slice")]
+#[test_case("[1, 2].slice(2, 2)", Vec::<CelValue>::new(); "lists slice empty")]
+#[test_case("[1, [2, [3, [4]]]].flatten()", vec![CelValue::from(1), CelValue::from(2), CelValue::from(vec![CelValue::from(3), CelValue::from(vec![CelValue::from(4)])])]; "lists flatten")]
+#[test_case("[1, [2, [3, [4]]]].flatten(2) == [1, 2, 3, [4]]", true; "lists flatten depth")]
+#[test_case("[[1], [2]].flatten(0) == [[1], [2]]", true; "lists flatten zero")]
+#[test_case("[1, 2u, 1.0, 'a', 'a', [1], [1u]].distinct() == [1, 2u, 'a', [1]]", true; "lists distinct")]
+#[test_case("lists.range(4)", vec![CelValue::from(0), CelValue::from(1), CelValue::from(2), CelValue::from(3)]; "lists range")]
+#[test_case("lists.range(0)", Vec::<CelValue>::new(); "lists range empty")]
+#[test_case("[3, 1, 2].reverse()", vec![CelValue::from(2), CelValue::from(1), CelValue::from(3)]; "lists reverse")]
+#[test_case("[{'n': 'b', 'a': 2}, {'n': 'a', 'a': 1}, {'n': 'c', 'a': 1}].sortBy(e, e.a).map(e, e.n)", vec![CelValue::from("a"), CelValue::from("c"), CelValue::from("b")]; "lists sortBy")]
+#[test_case("['ccc', 'a', 'bb'].sortBy(s, -int(s.size()))", vec![CelValue::from("ccc"), CelValue::from("bb"), CelValue::from("a")]; "lists sortBy expression")]
+#[test_case("[1, 2, 3].first().value()", 1; "lists first")]
+#[test_case("[1, 2, 3].last().value()", 3; "lists last")]
+#[test_case("[].first().hasValue()", false; "lists first empty")]
+#[test_case("[].last().orValue(7)", 7; "lists last empty")]
+#[test_case("optional.of(1) == optional.of(1u)", true; "optional equality")]
+#[test_case("optional.none() == optional.of(1)", false; "optional none equality")]
+#[test_case("has({'a': 1}.value)", false; "has map key named like a function")]
+#[test_case("{'a': 1, 'b': 2}.all(k, v, v > 0 && k != 'c')", true; "two var all map")]
+#[test_case("[1, 2, 3].all(i, v, v == i + 1)", true; "two var all list")]
+#[test_case("{'a': 1, 'b': -2}.exists(k, v, v < 0 && k == 'b')", true; "two var exists")]
+#[test_case("[5, 5].exists_one(i, v, v == 5 && i == 1)", true; "two var exists_one")]
+#[test_case("[5, 5].existsOne(i, v, v == 5)", false; "two var existsOne many")]
+#[test_case("{'a': 1, 'b': 2}.all(k, k.size() == 1)", true; "all map keys")]
+#[test_case("[1, 2, 3].transformList(i, v, v * i)", vec![CelValue::from(0), CelValue::from(2), CelValue::from(6)]; "transformList")]
+#[test_case("[1, 2, 3].transformList(i, v, i > 0, v * 10)", vec![CelValue::from(20), CelValue::from(30)]; "transformList filter")]
+#[test_case("{'a': 1, 'b': 2}.transformList(k, v, k + string(v)).sort()", vec![CelValue::from("a1"), CelValue::from("b2")]; "transformList map")]
+#[test_case("{'a': 1, 'b': 2}.transformMap(k, v, v * 2) == {'a': 2, 'b': 4}", true; "transformMap")]
+#[test_case("{'a': 1, 'b': 2}.transformMap(k, v, v > 1, k) == {'b': 'b'}", true; "transformMap filter")]
+#[test_case("{'a': 1, 'b': 2}.transformMapEntry(k, v, {string(v): k}) == {'1': 'a', '2': 'b'}", true; "transformMapEntry")]
+#[test_case("[[1, 2], [3]].map(l, l.map(x, x * int(l.size())))", vec![CelValue::from_val_slice(&[2.into(), 4.into()]), CelValue::from_val_slice(&[3.into()])]; "nested comprehension")]
+#[test_case("[1, 2].map(x, [x].exists(x, x == 2))", vec![CelValue::from(false), CelValue::from(true)]; "nested comprehension shadowing")]
+#[test_case("[[2, 1], [3]].map(l, l.sortBy(x, x * int(l.size())))", vec![CelValue::from_val_slice(&[1.into(), 2.into()]), CelValue::from_val_slice(&[3.into()])]; "runtime macro in comprehension")]
+#[test_case("[0, 1].exists(x, x == 0 || 1 / x == 1)", true; "exists short circuits")]
+#[test_case("[1, 0].all(x, x == 1)", false; "all stops at false")]
+#[test_case("{'a': 1}.map(k, k + 'b')", vec![CelValue::from("ab")]; "map over map keys")]
+#[test_case("[1, 2, 3].filter(x, x != 2).reduce(acc, x, acc * x, 1)", 3; "filter then reduce")]
+#[test_case("-9223372036854775808", i64::MIN; "int min literal")]
+#[test_case("-9223372036854775807 - 1", i64::MIN; "int min by subtraction")]
+#[test_case("9223372036854775807 + 0", i64::MAX; "int max")]
+#[test_case("18446744073709551615u", u64::MAX; "uint max literal")]
+#[test_case("int(-9223372036854775808.0)", i64::MIN; "int of min double")]
+#[test_case("uint(-0.5)", 0u64; "uint of small negative double")]
+#[test_case("-7 % 3", -1; "negative remainder")]
+#[test_case("cel.bind(msg, 'hello', msg + msg + msg)", "hellohellohello"; "bind")]
+#[test_case("cel.bind(t1, true, cel.bind(t2, true, t1 && t2))", true; "bind nested")]
+#[test_case("cel.bind(valid, [1, 2, 3], [3, 4, 5].exists(e, e in valid))", true; "bind in comprehension")]
+#[test_case("cel.bind(x, 1, cel.bind(x, x + 1, x))", 2; "bind shadows bind")]
+#[test_case("[1, 2].map(x, cel.bind(y, x * 10, y + x))", vec![CelValue::from(11), CelValue::from(22)]; "bind per element")]
 #[test_case("bytes(bytes('abc'))", crate::types::CelBytes::from_vec(vec![97u8, 98u8, 99u8]); "identity -- bytes 1")]
 #[test_case("bytes(b'abc')", crate::types::CelBytes::from_vec(vec![97u8, 98u8, 99u8]); "identity -- bytes 2")]
 #[test_case("duration(duration('100s')) == duration('100s')", true; "identity -- duration")]
@@ -204,9 +320,106 @@ fn test_contains() {
 #[test_case("[1, 2, 3, 4].all(x, x < 5) && [1, 2, 3, 4].exists(x, x == 3)", true; "all and exists")]
 #[test_case("coalesce(null, null, 'hello', null) == 'hello'", true; "coalesce with multiple nulls")]
 #[test_case("duration('3h').getHours()", 3; "duration.getHours")]
-#[test_case("duration('1s234ms').getMilliseconds()", 234; "duration.getMilliseconds")]
+#[test_case("duration('1s234ms').getMilliseconds()", 1234; "duration.getMilliseconds")]
+#[test_case("duration('123.321456789s').getMilliseconds()", 123321; "duration.getMilliseconds fractional")]
+#[test_case("duration('-1.5s').getMilliseconds()", -1500; "duration.getMilliseconds negative")]
 #[test_case("duration('1h30m').getMinutes()", 90; "duration.getMinutes")]
+#[test_case("timestamp('2009-02-13T23:31:30.123456789Z').getMilliseconds()", 123; "timestamp.getMilliseconds nanos")]
+#[test_case("string(timestamp('2009-02-13T23:31:30.123456789Z'))", "2009-02-13T23:31:30.123456789Z"; "timestamp string nanos")]
+#[test_case("string(timestamp('2009-02-13T23:31:30Z'))", "2009-02-13T23:31:30Z"; "timestamp string whole seconds")]
+#[test_case("string(duration('1.000000001s'))", "1.000000001s"; "duration string nanos")]
+#[test_case("string(duration('-1.5s'))", "-1.5s"; "duration string negative")]
+#[test_case("duration('1h2m3.5s') == duration('3723500ms')", true; "duration compound units")]
+#[test_case("timestamp('2009-02-13T23:31:30.000000001Z') - timestamp('2009-02-13T23:31:30Z') == duration('1ns')", true; "timestamp nanosecond difference")]
+#[test_case("string(timestamp('9999-12-31T23:59:59.999999999Z'))", "9999-12-31T23:59:59.999999999Z"; "timestamp max")]
+#[test_case("duration(-315576000000) < duration('0s')", true; "duration min")]
+#[test_case("timestamp('2024-03-10T23:30:00Z').format('%Y-%m-%d %H:%M')", "2024-03-10 23:30"; "timestamp format")]
+#[test_case("timestamp('2024-03-10T23:30:00Z').format('%Y-%m-%d %H:%M', 'Europe/Paris')", "2024-03-11 00:30"; "timestamp format tz")]
+#[test_case("timestamp('03/11/2024', '%m/%d/%Y') == timestamp('2024-03-11T00:00:00Z')", true; "timestamp parse date pattern")]
+#[test_case("timestamp('03/11/2024 08:15', '%m/%d/%Y %H:%M', 'America/New_York') == timestamp('2024-03-11T12:15:00Z')", true; "timestamp parse pattern tz")]
+#[test_case("timestamp('2024-03-11 08:15 +0200', '%Y-%m-%d %H:%M %z') == timestamp('2024-03-11T06:15:00Z')", true; "timestamp parse pattern offset")]
+#[test_case("timestamp('2024-03-10T23:30:45Z').truncate('day') == timestamp('2024-03-10T00:00:00Z')", true; "truncate day")]
+#[test_case("timestamp('2024-03-10T23:30:45Z').truncate('day', 'Europe/Paris') == timestamp('2024-03-10T23:00:00Z')", true; "truncate day tz")]
+#[test_case("timestamp('2024-03-10T23:30:45Z').truncate('hour') == timestamp('2024-03-10T23:00:00Z')", true; "truncate hour")]
+#[test_case("timestamp('2024-03-10T23:30:45Z').truncate('month') == timestamp('2024-03-01T00:00:00Z')", true; "truncate month")]
+#[test_case("timestamp('2024-03-10T23:30:45Z').truncate('year') == timestamp('2024-01-01T00:00:00Z')", true; "truncate year")]
+#[test_case("timestamp('2024-03-10T23:30:45Z').startOfWeek() == timestamp('2024-03-04T00:00:00Z')", true; "start of week")]
+#[test_case("timestamp('2024-03-10T23:30:45Z').startOfWeek('Europe/Paris') == timestamp('2024-03-10T23:00:00Z')", true; "start of week tz")]
+#[test_case("timestamp('2024-01-31T12:00:00Z').addMonths(1) == timestamp('2024-02-29T12:00:00Z')", true; "add months clamps")]
+#[test_case("timestamp('2024-03-31T12:00:00Z').addMonths(-13) == timestamp('2023-02-28T12:00:00Z')", true; "add negative months")]
+#[test_case("timestamp('2024-01-31T23:30:00Z').addMonths(1, 'Europe/Paris') == timestamp('2024-02-29T23:30:00Z')", true; "add months tz")]
+#[test_case("timestamp('2024-02-10T07:30:00Z').addMonths(1, 'America/New_York') == timestamp('2024-03-10T07:30:00Z')", true; "add months into dst gap")]
+#[test_case("timestamp('2024-09-08T12:00:00Z').truncate('day', 'America/Santiago') == timestamp('2024-09-08T04:00:00Z')", true; "truncate day dst gap")]
+#[test_case("timestamp('2021-03-24T12:00:00Z').startOfWeek('Asia/Tehran') == timestamp('2021-03-21T20:30:00Z')", true; "start of week dst gap")]
+#[test_case("timestamp('2009-02-13T23:31:30.5Z').toEpochSeconds()", 1234567890; "to epoch seconds")]
+#[test_case("duration('P1DT2H') == duration('26h')", true; "duration iso8601")]
+#[test_case("duration('-PT1.5S') == duration('-1.5s')", true; "duration iso8601 negative")]
+#[test_case("duration('P1DT2H3M4.5S').toISO8601()", "P1DT2H3M4.5S"; "duration to iso8601")]
+#[test_case("duration('-90m').toISO8601()", "-PT1H30M"; "duration to iso8601 negative")]
+#[test_case("duration('0s').toISO8601()", "PT0S"; "duration to iso8601 zero")]
+#[test_case("string(duration('1h'))", "3600s"; "duration string canonical")]
+#[test_case("duration('P1DT2H3M4.5S').getDaysPart()", 1; "duration days part")]
+#[test_case("duration('P1DT2H3M4.5S').getHoursPart()", 2; "duration hours part")]
+#[test_case("duration('P1DT2H3M4.5S').getMinutesPart()", 3; "duration minutes part")]
+#[test_case("duration('P1DT2H3M4.5S').getSecondsPart()", 4; "duration seconds part")]
+#[test_case("duration('P1DT2H3M4.5S').getMillisecondsPart()", 500; "duration milliseconds part")]
+#[test_case("duration('1.000000123s').getNanosecondsPart()", 123; "duration nanoseconds part")]
+#[test_case("duration('-90m').getHoursPart()", -1; "duration hours part negative")]
+#[test_case("duration('P1DT2H').getHours()", 26; "duration total hours")]
 #[test_case("duration('1m30s').getSeconds()", 90; "duration.getSeconds")]
+#[test_case("string(timestamp('2024-03-10T06:30:00Z').inZone('America/New_York'))", "2024-03-10T01:30:00-05:00"; "in zone string")]
+#[test_case("string(timestamp('2024-03-10T07:30:00Z').inZone('America/New_York'))", "2024-03-10T03:30:00-04:00"; "in zone string after dst")]
+#[test_case("timestamp('2024-03-10T06:30:00Z').inZone('America/New_York').getHours()", 1; "in zone getter")]
+#[test_case("timestamp('2024-03-10T06:30:00Z').inZone('America/New_York').getHours('UTC')", 6; "in zone getter explicit tz")]
+#[test_case("timestamp('2024-03-10T06:30:00Z').inZone('America/New_York').getDayOfMonth()", 9; "in zone day of month")]
+#[test_case("timestamp('2024-03-10T06:30:00Z').inZone('America/New_York').format('%Y-%m-%d %H:%M %Z')", "2024-03-10 01:30 EST"; "in zone format")]
+#[test_case("timestamp('2024-03-10T06:30:00Z').inZone('America/New_York').getZone()", "America/New_York"; "in zone get zone")]
+#[test_case("timestamp('2024-03-10T06:30:00Z').getZone()", "UTC"; "timestamp get zone")]
+#[test_case("timestamp('2024-03-10T06:30:00Z').inZone('Asia/Tokyo').inZone('Europe/Paris').getHours()", 7; "in zone rezone")]
+#[test_case("timestamp('2024-03-10T06:30:00Z').inZone('Asia/Tokyo').toUTC() == timestamp('2024-03-10T06:30:00Z')", true; "in zone to utc")]
+#[test_case("string(timestamp('2024-03-10T06:30:00Z').inZone('Asia/Tokyo').toUTC())", "2024-03-10T06:30:00Z"; "in zone to utc string")]
+#[test_case("timestamp('2024-03-10T06:30:00Z').inZone('Asia/Tokyo') == timestamp('2024-03-10T06:30:00Z').inZone('America/New_York')", true; "in zone equality on instant")]
+#[test_case("timestamp('2024-03-10T06:30:00Z') == timestamp('2024-03-10T06:30:00Z').inZone('Asia/Tokyo')", true; "in zone equality with timestamp")]
+#[test_case("timestamp('2024-03-10T06:30:00Z').inZone('Asia/Tokyo') < timestamp('2024-03-10T07:00:00Z').inZone('America/New_York')", true; "in zone ordering")]
+#[test_case("timestamp('2024-03-10T06:30:00Z') >= timestamp('2024-03-10T07:00:00Z').inZone('Asia/Tokyo')", false; "in zone ordering with timestamp")]
+#[test_case("string(timestamp('2024-03-10T06:30:00Z').inZone('America/New_York') + duration('1h'))", "2024-03-10T03:30:00-04:00"; "in zone add duration")]
+#[test_case("timestamp('2024-03-10T06:30:00Z').inZone('America/New_York') - timestamp('2024-03-10T05:30:00Z')", Duration::hours(1); "in zone minus timestamp")]
+#[test_case("string(timestamp('2024-03-10T06:30:00Z').inZone('America/New_York').truncate('day'))", "2024-03-10T00:00:00-05:00"; "in zone truncate")]
+#[test_case("string(timestamp('2024-01-31T23:30:00Z').inZone('Europe/Paris').addMonths(1))", "2024-03-01T00:30:00+01:00"; "in zone add months")]
+#[test_case("timestamp('2009-02-13T23:31:30Z').inZone('Asia/Tokyo').toEpochSeconds()", 1234567890; "in zone epoch seconds")]
+#[test_case("type(timestamp('2024-03-10T06:30:00Z').inZone('UTC')) == type(timestamp('2024-03-10T06:30:00Z'))", false; "in zone type")]
+#[test_case("sets.contains([timestamp('2024-03-10T06:30:00Z')], [timestamp('2024-03-10T06:30:00Z').inZone('Europe/Paris')])", true; "in zone sets contains")]
+#[test_case("[timestamp('2024-03-10T06:30:00Z'), timestamp('2024-03-10T06:30:00Z').inZone('Europe/Paris')].distinct().size()", 1u64; "in zone distinct")]
+#[test_case("timestamp('2024-03-10T06:30:00Z').inZone('Europe/Paris') in [timestamp('2024-03-10T06:30:00Z')]", true; "in zone in list")]
+#[test_case("timestamp('2024-03-10T06:30:00Z') in [timestamp('2024-03-10T06:30:00Z').inZone('Europe/Paris')]", true; "in list of zoned")]
+#[test_case("f'{timestamp(\"2024-03-10T06:30:00Z\").inZone(\"Europe/Paris\"):%H:%M}'", "07:30"; "in zone f-string strftime")]
+#[test_case("'%s'.format([timestamp('2024-03-10T06:30:00Z').inZone('Europe/Paris')])", "2024-03-10T07:30:00+01:00"; "in zone string format")]
+#[test_case("'%s'.format([[timestamp('2024-03-10T06:30:00Z').inZone('Europe/Paris')]])", "[timestamp(\"2024-03-10T07:30:00+01:00\").inZone(\"Europe/Paris\")]"; "in zone string format nested")]
+#[test_case("timestamp(timestamp('2024-03-10T06:30:00Z').inZone('Europe/Paris')) == timestamp('2024-03-10T06:30:00Z')", true; "timestamp of zoned")]
+#[test_case("type(timestamp(timestamp('2024-03-10T06:30:00Z').inZone('Europe/Paris'))) == timestamp", true; "timestamp of zoned is timestamp")]
+#[test_case("timestamp('2024-03-09T12:00:00Z').isWeekend()", true; "is weekend saturday")]
+#[test_case("timestamp('2024-03-11T02:00:00Z').isWeekend()", false; "is weekend monday")]
+#[test_case("timestamp('2024-03-11T02:00:00Z').isWeekend('America/New_York')", true; "is weekend tz")]
+#[test_case("timestamp('2024-03-11T02:00:00Z').inZone('America/New_York').isWeekend()", true; "is weekend zoned")]
+#[test_case("businessDaysBetween(timestamp('2024-03-04T09:00:00Z'), timestamp('2024-03-18T09:00:00Z'))", 10; "business days two weeks")]
+#[test_case("businessDaysBetween(timestamp('2024-03-04T09:00:00Z'), timestamp('2024-03-09T09:00:00Z'))", 5; "business days to saturday")]
+#[test_case("businessDaysBetween(timestamp('2024-03-09T09:00:00Z'), timestamp('2024-03-11T09:00:00Z'))", 0; "business days over weekend")]
+#[test_case("businessDaysBetween(timestamp('2024-03-18T09:00:00Z'), timestamp('2024-03-04T09:00:00Z'))", -10; "business days reversed")]
+#[test_case("businessDaysBetween(timestamp('2024-03-04T09:00:00Z'), timestamp('2024-03-18T09:00:00Z'), ['2024-03-05', '2024-03-09', '2024-03-05', '2024-03-18'])", 9; "business days holidays")]
+#[test_case("businessDaysBetween(timestamp('2024-03-04T09:00:00Z'), timestamp('2024-03-18T09:00:00Z'), [timestamp('2024-03-06T00:00:00Z')])", 9; "business days timestamp holidays")]
+#[test_case("businessDaysBetween(timestamp('2024-03-04T03:00:00Z'), timestamp('2024-03-05T03:00:00Z'))", 1; "business days utc")]
+#[test_case("businessDaysBetween(timestamp('2024-03-04T03:00:00Z'), timestamp('2024-03-05T03:00:00Z'), 'America/New_York')", 0; "business days tz")]
+#[test_case("businessDaysBetween(timestamp('2024-03-04T03:00:00Z'), timestamp('2024-03-07T03:00:00Z'), 'America/New_York', ['2024-03-05'])", 1; "business days tz holidays")]
+#[test_case("businessDaysBetween(timestamp('2024-03-04T03:00:00Z').inZone('America/New_York'), timestamp('2024-03-05T03:00:00Z'))", 0; "business days zoned")]
+#[test_case("timestamp('2024-03-04T10:00:00Z').nextDayOfWeek(1) == timestamp('2024-03-11T00:00:00Z')", true; "next day of week same day")]
+#[test_case("timestamp('2024-03-04T10:00:00Z').nextDayOfWeek(5) == timestamp('2024-03-08T00:00:00Z')", true; "next day of week friday")]
+#[test_case("timestamp('2024-03-04T10:00:00Z').nextDayOfWeek(0, 'Europe/Paris') == timestamp('2024-03-09T23:00:00Z')", true; "next day of week tz")]
+#[test_case("timestamp('2024-02-10T10:00:00Z').endOfMonth() == timestamp('2024-02-29T23:59:59.999999999Z')", true; "end of month")]
+#[test_case("timestamp('2024-09-07T12:00:00Z').nextDayOfWeek(0, 'America/Santiago') == timestamp('2024-09-08T04:00:00Z')", true; "next day of week dst gap")]
+#[test_case("timestamp('2023-09-15T12:00:00Z').endOfMonth('America/Asuncion') == timestamp('2023-10-01T03:59:59.999999999Z')", true; "end of month dst gap")]
+#[test_case("timestamp('2024-01-31T23:30:00Z').endOfMonth('Europe/Paris') == timestamp('2024-02-29T22:59:59.999999999Z')", true; "end of month tz")]
+#[test_case("timestamp('2024-02-10T10:00:00Z').endOfMonth() + duration('1ns') == timestamp('2024-03-01T00:00:00Z')", true; "next first of month")]
+#[test_case("string(timestamp('2024-01-31T23:30:00Z').inZone('Europe/Paris').endOfMonth())", "2024-02-29T23:59:59.999999999+01:00"; "end of month zoned")]
 #[test_case("match 'foo' {case int: false, case _: true}", true; "match else")]
 #[test_case("match 3 { case int: true, case _: false}", true; "match int" )]
 #[test_case("match 2.0 { case float: true, case _: flase}", true; "match float")]
@@ -229,6 +442,36 @@ fn test_contains() {
 #[test_case("'12131415'.rsplit('1')", vec!["5", "4", "3", "2", ""]; "string rsplit")]
 #[test_case("'12131415'.split('1')", vec!["", "2", "3", "4", "5"]; "string split")]
 #[test_case("'123456'.splitAt(3)", vec!["123", "456"]; "string splitAt")]
+#[test_case("'%s has %d items, %.2f%%'.format(['cart', 3, 12.345])", "cart has 3 items, 12.35%"; "string format")]
+#[test_case("'%f %.3e %e'.format([2.0, 1052.032911275, -0.5])", "2.000000 1.052e+03 -5.000000e-01"; "string format floats")]
+#[test_case("'%x %X %o %b %b'.format([255, 'Hi', 8u, 5, true])", "ff 4869 10 101 1"; "string format radix")]
+#[test_case("'tacocat'.charAt(3)", "o"; "string charAt")]
+#[test_case("'tacocat'.charAt(7)", ""; "string charAt end")]
+#[test_case("'©αT'.charAt(1)", "α"; "string charAt unicode")]
+#[test_case("'tacocat'.indexOf('ac')", 1; "string indexOf")]
+#[test_case("'tacocat'.indexOf('none')", -1; "string indexOf no match")]
+#[test_case("'ta©o©αT'.indexOf('©αT', 3)", 4; "string indexOf unicode start")]
+#[test_case("'tacocat'.lastIndexOf('')", 7; "string lastIndexOf empty")]
+#[test_case("'bananananana'.lastIndexOf('nana', 7)", 6; "string lastIndexOf start")]
+#[test_case("'ta©o©αT'.lastIndexOf('©', 3)", 2; "string lastIndexOf unicode")]
+#[test_case("'TacoCÆt Xii'.lowerAscii()", "tacocÆt xii"; "string lowerAscii")]
+#[test_case("'tacoCαt'.upperAscii()", "TACOCαT"; "string upperAscii")]
+#[test_case("'ta©o©αT'.substring(2, 6)", "©o©α"; "string substring")]
+#[test_case("'tacocat'.substring(4)", "cat"; "string substring start")]
+#[test_case("['x', 'y'].join('-')", "x-y"; "list join")]
+#[test_case("[].join()", ""; "list join empty")]
+#[test_case("strings.quote('bell\\a \\\\ \"q\"\\n')", "\"bell\\a \\\\ \\\"q\\\"\\n\""; "strings quote")]
+#[test_case("'ta©o'.reverse()", "o©at"; "string reverse")]
+#[test_case("'{0} days {0} hours'.replace('{0}', '2', 1)", "2 days {0} hours"; "string replace count")]
+#[test_case("'hello world events!'.split(' ', 0)", Vec::<&str>::new(); "string split zero limit")]
+#[test_case("'hello world events!'.split(' ', 2)", vec!["hello", "world events!"]; "string split limit")]
+#[test_case("'o©o©o©o'.split('©', -1)", vec!["o", "o", "o", "o"]; "string split negative limit")]
+#[test_case("'%s'.format([double('NaN')])", "NaN"; "string format nan")]
+#[test_case("'%s %s %s'.format([null, b'xyz', 1.5])", "null xyz 1.5"; "string format scalars")]
+#[test_case("'%s'.format([['abc', 3.14, null, [9, 8], timestamp('2023-02-03T23:31:20Z')]])", "[\"abc\", 3.14, null, [9, 8], timestamp(\"2023-02-03T23:31:20Z\")]"; "string format list")]
+#[test_case("'%s'.format([{'key2': duration('2h'), 'key1': b'xyz', 'key3': true}])", "{\"key1\": b\"xyz\", \"key2\": duration(\"7200s\"), \"key3\": true}"; "string format map")]
+#[test_case("'%s'.format([['a\\x07\\u00e9\\n\"', b'\\xff\\x00\"']])", "[\"a\\a\u{e9}\\n\\\"\", b\"\\xff\\x00\\\"\"]"; "string format list escapes")]
+#[test_case("'%s'.format([{'\\t': 1}])", "{\"\\t\": 1}"; "string format map key escapes")]
 #[test_case("'12345LF'.trimEndMatches('LF')", "12345"; "string trimEndMatches")]
 #[test_case("'LF12345'.trimStartMatches('LF')", "12345"; "string trimStartMatches")]
 #[test_case("zip([1, 2, 3], ['a', 'b', 'c'])",
@@ -250,6 +493,178 @@ fn test_equation(prog: &str, res: impl Into<CelValue>) {
     assert_eq!(eval_res, res.into());
 }
 
+#[test_case("f'{1:.2d}'"; "precision with int")]
+#[test_case("f'{1:abc}'"; "garbage spec")]
+#[test_case("f'{1:%Q}'"; "bad strftime")]
+fn test_bad_format_spec(prog: &str) {
+    let mut ctx = CelContext::new();
+
+    assert!(matches!(
+        Program::from_source(prog).map(|p| ctx.add_program("main", p)),
+        Err(CelError::Syntax(_))
+    ));
+}
+
+#[test_case("sets.contains([1], 1)"; "sets contains non list")]
+#[test_case("[1, 2].slice(-1, 1)"; "slice negative")]
+#[test_case("[1, 2].slice(2, 1)"; "slice inverted")]
+#[test_case("[1, 2].slice(0, 3)"; "slice past end")]
+#[test_case("[1].flatten(-1)"; "flatten negative depth")]
+#[test_case("lists.range(-1)"; "range negative")]
+#[test_case("[1, 'a'].sortBy(x, x)"; "sortBy mixed keys")]
+#[test_case("[1.0, 0.0 / 0.0, 2.0].sortBy(x, x)"; "sortBy nan key")]
+#[test_case("[].first().value()"; "value of none")]
+#[test_case("{'a': 1, 'b': 1}.transformMapEntry(k, v, {'x': v})"; "transformMapEntry duplicate key")]
+#[test_case("[1].transformMap(i, v, v)"; "transformMap on list")]
+fn test_bad_list_ops(prog: &str) {
+    let mut ctx = CelContext::new();
+    let exec_ctx = BindContext::new();
+
+    ctx.add_program_str("main", prog).unwrap();
+
+    assert!(ctx.exec("main", &exec_ctx).is_err());
+}
+
+#[test_case("[1].all(a, b, c, a)"; "all too many variables")]
+#[test_case("[1, 0].filter(x, 1 / x > 0)"; "filter error")]
+#[test_case("[1, 0].exists_one(x, 1 / x > 0)"; "exists_one error")]
+#[test_case("cel.bind(1, 2, 3)"; "bind non ident")]
+#[test_case("cel.bind(x, 1)"; "bind missing expr")]
+fn test_bad_macros(prog: &str) {
+    let mut ctx = CelContext::new();
+    let exec_ctx = BindContext::new();
+
+    ctx.add_program_str("main", prog).unwrap();
+
+    assert!(ctx.exec("main", &exec_ctx).is_err());
+}
+
+#[test_case("9223372036854775807 + 1"; "int overflow add")]
+#[test_case("-9223372036854775808 - 1"; "int overflow sub")]
+#[test_case("-9223372036854775808 + (-1)"; "int overflow add negative")]
+#[test_case("1 - (-9223372036854775807)"; "int overflow sub positive")]
+#[test_case("-(-9223372036854775808)"; "int min negate")]
+#[test_case("--9223372036854775808"; "int min literal double neg")]
+#[test_case("(-9223372036854775808) * -1"; "int min negate mul")]
+#[test_case("(-9223372036854775808) / -1"; "int min negate div")]
+#[test_case("(-9223372036854775808) % -1"; "int min rem")]
+#[test_case("5000000000 * 5000000000"; "int overflow mul")]
+#[test_case("18446744073709551615u + 1u"; "uint overflow add")]
+#[test_case("0u - 1u"; "uint overflow sub")]
+#[test_case("5000000000u * 5000000000u"; "uint overflow mul")]
+#[test_case("1 % 0"; "int rem zero")]
+#[test_case("1u % 0u"; "uint rem zero")]
+#[test_case("int(18446744073709551615u)"; "int of large uint")]
+#[test_case("int(1e19)"; "int of large double")]
+#[test_case("int(0.0 / 0.0)"; "int of nan")]
+#[test_case("uint(-1)"; "uint of negative int")]
+#[test_case("uint(-1.0)"; "uint of negative double")]
+#[test_case("math.abs(-9223372036854775808)"; "abs of int min")]
+#[test_case("pow(10, 19)"; "pow overflow")]
+#[test_case("pow(2, -1)"; "pow negative exponent")]
+#[test_case("'tacocat'.lastIndexOf('a', -1)"; "lastIndexOf negative")]
+fn test_bad_arithmetic(prog: &str) {
+    let mut ctx = CelContext::new();
+    let exec_ctx = BindContext::new();
+
+    ctx.add_program_str("main", prog).unwrap();
+
+    assert!(ctx.exec("main", &exec_ctx).is_err());
+}
+
+#[test_case("math.greatest([])"; "greatest empty")]
+#[test_case("math.least(1, 'a')"; "least string")]
+#[test_case("math.bitShiftRight(1, -1)"; "shift negative offset")]
+#[test_case("math.bitAnd(1, 1u)"; "bitAnd mixed")]
+fn test_bad_math_ext(prog: &str) {
+    let mut ctx = CelContext::new();
+    let exec_ctx = BindContext::new();
+
+    ctx.add_program_str("main", prog).unwrap();
+
+    assert!(ctx.exec("main", &exec_ctx).is_err());
+}
+
+#[test_case("timestamp('9999-12-31T23:59:59Z') + duration('1s')"; "timestamp overflow")]
+#[test_case("timestamp('0001-01-01T00:00:00Z') - duration('1s')"; "timestamp underflow")]
+#[test_case("duration('315576000000s') + duration('1s')"; "duration overflow")]
+#[test_case("timestamp('0000-01-01T00:00:00Z')"; "timestamp before year 1")]
+#[test_case("timestamp(253402300800)"; "timestamp after year 9999")]
+#[test_case("duration('315576000001s')"; "duration out of range")]
+#[test_case("duration(-315576000001)"; "negative duration out of range")]
+#[test_case("duration('1.2.3s')"; "duration bad fraction")]
+#[test_case("timestamp('2024-03-10T23:30:00Z').format('%Q')"; "timestamp format bad pattern")]
+#[test_case("timestamp('2024-03-10T23:30:00Z').format('%Y', 'Mars/Olympus')"; "timestamp format bad tz")]
+#[test_case("timestamp('11/2024', '%m/%d/%Y')"; "timestamp parse mismatch")]
+#[test_case("timestamp('2024-03-10T23:30:00Z').truncate('fortnight')"; "truncate bad unit")]
+#[test_case("timestamp('9999-12-01T00:00:00Z').addMonths(1)"; "add months overflow")]
+#[test_case("duration('P1M')"; "duration iso8601 months")]
+#[test_case("duration('P1DT')"; "duration iso8601 empty time")]
+#[test_case("timestamp('2024-03-10T06:30:00Z').inZone('Mars/Olympus')"; "in zone bad tz")]
+#[test_case("duration('1h').inZone('UTC')"; "in zone duration")]
+#[test_case("timestamp('2024-03-10T06:30:00Z').inZone('UTC') + 1"; "in zone plus int")]
+#[test_case("timestamp('2024-03-04T10:00:00Z').nextDayOfWeek(7)"; "next day of week out of range")]
+#[test_case("businessDaysBetween(timestamp('2024-03-04T09:00:00Z'), timestamp('2024-03-18T09:00:00Z'), ['03/05/2024'])"; "business days bad holiday")]
+#[test_case("businessDaysBetween(timestamp('2024-03-04T09:00:00Z'), timestamp('2024-03-18T09:00:00Z'), [1])"; "business days int holiday")]
+#[test_case("timestamp('2024-03-04T09:00:00Z').isWeekend('Mars/Olympus')"; "is weekend bad tz")]
+fn test_bad_time(prog: &str) {
+    let mut ctx = CelContext::new();
+    let exec_ctx = BindContext::new();
+
+    ctx.add_program_str("main", prog).unwrap();
+
+    assert!(ctx.exec("main", &exec_ctx).is_err());
+}
+
+#[test_case("base64.decode('a!')"; "base64 invalid")]
+#[test_case("hex.decode('abc')"; "hex odd length")]
+#[test_case("url.decode('%ff')"; "url invalid utf8")]
+fn test_bad_encoding(prog: &str) {
+    let mut ctx = CelContext::new();
+    let exec_ctx = BindContext::new();
+
+    ctx.add_program_str("main", prog).unwrap();
+
+    assert!(ctx.exec("main", &exec_ctx).is_err());
+}
+
+#[test_case("'tacocat'.charAt(30)"; "charAt out of range")]
+#[test_case("'tacocat'.indexOf('a', 30)"; "indexOf out of range")]
+#[test_case("'tacocat'.substring(4, 3)"; "substring inverted range")]
+#[test_case("'tacocat'.substring(1, 50)"; "substring end out of range")]
+#[test_case("[1, 2].join()"; "join non strings")]
+fn test_bad_string_ext(prog: &str) {
+    let mut ctx = CelContext::new();
+    let exec_ctx = BindContext::new();
+
+    ctx.add_program_str("main", prog).unwrap();
+
+    assert!(ctx.exec("main", &exec_ctx).is_err());
+}
+
+#[test_case("'%d'.format([1.5])"; "decimal double")]
+#[test_case("'%s %s'.format([1])"; "missing arg")]
+#[test_case("'%z'.format([1])"; "unknown clause")]
+#[test_case("'%.2d'.format([1])"; "precision on decimal")]
+fn test_bad_string_format(prog: &str) {
+    let mut ctx = CelContext::new();
+    let exec_ctx = BindContext::new();
+
+    ctx.add_program_str("main", prog).unwrap();
+
+    assert!(ctx.exec("main", &exec_ctx).is_err());
+}
+
+#[test]
+fn test_format_spec_type_mismatch() {
+    let mut ctx = CelContext::new();
+    let exec_ctx = BindContext::new();
+
+    ctx.add_program_str("main", "f'{\"abc\":d}'").unwrap();
+
+    assert!(ctx.exec("main", &exec_ctx).is_err());
+}
+
 #[test]
 fn test_timestamp() {
     let mut ctx = CelContext::new();
@@ -365,6 +780,83 @@ fn test_serialization() {
     assert_eq!(cel.exec("main", &bindings).unwrap(), 18.into());
 }
 
+#[test]
+fn test_time_serialization() {
+    let source = "[timestamp('2009-02-13T23:31:30.123456789Z'), duration('-1.000000001s')]";
+    let json_str = {
+        let mut tokenizer = StringTokenizer::with_input(source);
+        let prog = CelCompiler::with_tokenizer(&mut tokenizer)
+            .compile()
+            .unwrap();
+        serde_json::to_string(&prog).unwrap()
+    };
+
+    let prog: Program = serde_json::from_str(&json_str).unwrap();
+
+    let mut cel = CelContext::new();
+    cel.add_program("main", prog);
+    cel.add_program_str("expected", source).unwrap();
+    let bindings = BindContext::new();
+
+    assert_eq!(
+        cel.exec("main", &bindings).unwrap(),
+        cel.exec("expected", &bindings).unwrap()
+    );
+
+    let value: CelValue = serde_json::from_str(r#"{"Duration":[-1,-500000000]}"#).unwrap();
+    assert_eq!(value, Duration::milliseconds(-1500).into());
+    assert!(serde_json::from_str::<CelValue>(r#"{"TimeStamp":[253402300800,0]}"#).is_err());
+
+    // Programs serialized before nanosecond precision store milliseconds
+    let source = "[timestamp('2009-02-13T23:31:30.123Z'), duration('-1.5s')]";
+    let json_str = {
+        let mut tokenizer = StringTokenizer::with_input(source);
+        let prog = CelCompiler::with_tokenizer(&mut tokenizer)
+            .compile()
+            .unwrap();
+        serde_json::to_string(&prog).unwrap()
+    };
+    assert!(json_str.contains("[1234567890,123000000]") && json_str.contains("[-1,-500000000]"));
+    let legacy_str = json_str
+        .replace("[1234567890,123000000]", "1234567890123")
+        .replace("[-1,-500000000]", "-1500");
+
+    cel.add_program("legacy", serde_json::from_str(&legacy_str).unwrap());
+    cel.add_program_str("legacy_expected", source).unwrap();
+    assert_eq!(
+        cel.exec("legacy", &bindings).unwrap(),
+        cel.exec("legacy_expected", &bindings).unwrap()
+    );
+
+    let value: CelValue = serde_json::from_str(r#"{"TimeStamp":1234567890123}"#).unwrap();
+    assert_eq!(
+        value,
+        CelValue::from_timestamp(DateTime::from_timestamp_millis(1_234_567_890_123).unwrap())
+    );
+    let value: CelValue = serde_json::from_str(r#"{"Duration":-1500}"#).unwrap();
+    assert_eq!(value, Duration::milliseconds(-1500).into());
+}
+
+#[test]
+fn test_zoned_serialization() {
+    let source = "timestamp('2024-03-10T06:30:00Z').inZone('America/New_York')";
+    let json_str = {
+        let mut tokenizer = StringTokenizer::with_input(source);
+        let prog = CelCompiler::with_tokenizer(&mut tokenizer)
+            .compile()
+            .unwrap();
+        serde_json::to_string(&prog).unwrap()
+    };
+
+    let mut cel = CelContext::new();
+    cel.add_program("main", serde_json::from_str(&json_str).unwrap());
+    let bindings = BindContext::new();
+
+    let res = cel.exec("main", &bindings).unwrap();
+    let zoned = CelZonedTimestamp::from_cel_value(&res).expect("expected a zoned timestamp");
+    assert_eq!(zoned.to_string(), "2024-03-10T01:30:00-05:00");
+}
+
 #[test]
 fn test_nested() {
     let mut ctx = CelContext::new();
@@ -378,6 +870,126 @@ fn test_nested() {
     assert_eq!(ctx.exec("bar", &exec_ctx).unwrap(), 30.into());
 }
 
+#[test]
+fn test_qualified_binding() {
+    let mut ctx = CelContext::new();
+    let mut exec_ctx = BindContext::new();
+
+    ctx.add_program_str("main", "x.y").unwrap();
+    ctx.add_program_str("deep", "a.b.c + 1").unwrap();
+    ctx.add_program_str("has", "has(a.b.d)").unwrap();
+
+    exec_ctx.bind_param("x.y", true.into());
+    exec_ctx.bind_param("a.b.c", 2.into());
+
+    assert_eq!(ctx.exec("main", &exec_ctx).unwrap(), true.into());
+    assert_eq!(ctx.exec("deep", &exec_ctx).unwrap(), 3.into());
+    assert_eq!(ctx.exec("has", &exec_ctx).unwrap(), false.into());
+
+    // The qualified name wins over a member of its prefix
+    let mut x = HashMap::new();
+    x.insert("y".to_owned(), CelValue::from(false));
+    exec_ctx.bind_param("x", x.into());
+    assert_eq!(ctx.exec("main", &exec_ctx).unwrap(), true.into());
+}
+
+#[test]
+fn test_qualified_name_bytecode() {
+    let mut tokenizer = StringTokenizer::with_input("a.b.c + 1");
+    let prog = CelCompiler::with_tokenizer(&mut tokenizer)
+        .with_container("x")
+        .compile()
+        .unwrap();
+
+    // The candidates are worked out once when compiling
+    assert_eq!(
+        prog.bytecode().as_slice()[0],
+        ByteCode::Resolve(QualifiedIdent::new(Some("x"), "a.b.c"))
+    );
+    assert!(!prog
+        .bytecode()
+        .iter()
+        .any(|code_point| matches!(code_point, ByteCode::Access)));
+
+    // Longer names are tried first, then the more qualified ones
+    assert_eq!(
+        QualifiedIdent::new(Some("x"), "a.b")
+            .candidates()
+            .collect::<Vec<_>>(),
+        vec![("x.a.b", 2), ("a.b", 2), ("x.a", 1), ("a", 1)]
+    );
+}
+
+#[test]
+fn test_container() {
+    let compile = |source: &str, container: &str| {
+        let mut tokenizer = StringTokenizer::with_input(source);
+        CelCompiler::with_tokenizer(&mut tokenizer)
+            .with_container(container)
+            .compile()
+            .unwrap()
+    };
+
+    let mut ctx = CelContext::new();
+    let mut exec_ctx = BindContext::new();
+
+    ctx.add_program("main", compile("y", "x"));
+    ctx.add_program("nested", compile("y", "x.z"));
+    ctx.add_program("func", compile("greatest(1, 3, 2)", "math"));
+    ctx.add_program("shadow", compile("[1, 2].map(y, y * 2)", "x"));
+
+    exec_ctx.bind_param("x.y", true.into());
+    exec_ctx.bind_param("y", "false".into());
+
+    assert_eq!(ctx.exec("main", &exec_ctx).unwrap(), true.into());
+    assert_eq!(ctx.exec("nested", &exec_ctx).unwrap(), true.into());
+    assert_eq!(ctx.exec("func", &exec_ctx).unwrap(), 3.into());
+    assert_eq!(
+        ctx.exec("shadow", &exec_ctx).unwrap(),
+        vec![CelValue::from(2), CelValue::from(4)].into()
+    );
+
+    let prog: Program =
+        serde_json::from_str(&serde_json::to_string(ctx.get_program("main").unwrap()).unwrap())
+            .unwrap();
+    assert_eq!(prog.details().container(), Some("x"));
+}
+
+#[test]
+fn test_qualified_function() {
+    fn answer(_this: CelValue, _args: Vec<CelValue>) -> CelValue {
+        42.into()
+    }
+
+    let mut ctx = CelContext::new();
+    let mut exec_ctx = BindContext::new();
+
+    ctx.add_program_str("main", "pkg.answer()").unwrap();
+    ctx.add_program_str("unbound", "pkg.missing()").unwrap();
+
+    exec_ctx.bind_func("pkg.answer", &answer);
+
+    assert_eq!(ctx.exec("main", &exec_ctx).unwrap(), 42.into());
+    assert!(matches!(
+        ctx.exec("unbound", &exec_ctx),
+        Err(CelError::Binding { .. })
+    ));
+
+    let mut tokenizer = StringTokenizer::with_input("answer()");
+    ctx.add_program(
+        "contained",
+        CelCompiler::with_tokenizer(&mut tokenizer)
+            .with_container("pkg")
+            .compile()
+            .unwrap(),
+    );
+    assert_eq!(ctx.exec("contained", &exec_ctx).unwrap(), 42.into());
+
+    // The qualified function wins over a method of a variable named like its prefix
+    exec_ctx.bind_param("pkg", HashMap::<String, CelValue>::new().into());
+    assert_eq!(ctx.exec("main", &exec_ctx).unwrap(), 42.into());
+}
+
 #[test]
 fn test_call_depth_failure() {
     let mut ctx = CelContext::new();
@@ -567,3 +1179,100 @@ fn test_keywords_as_access_idents() {
         _ => panic!(),
     }
 }
+
+#[test]
+fn test_missing_key_named_like_function() {
+    let mut ctx = CelContext::new();
+
+    ctx.add_program_str("attr", "m.first").unwrap();
+    ctx.add_program_str("call", "m.first()").unwrap();
+    ctx.add_program_str("call_args", "m.contains('a')").unwrap();
+    // The same on a map that isn't named by an ident
+    ctx.add_program_str("expr_attr", "(m).first").unwrap();
+    ctx.add_program_str("expr_call", "(m).first()").unwrap();
+
+    let mut exec = BindContext::new();
+    let mut map = HashMap::new();
+    map.insert("a".to_string(), 1.into());
+    exec.bind_param("m", map.into());
+
+    for prog in ["attr", "expr_attr"] {
+        match ctx.exec(prog, &exec) {
+            Err(CelError::Attribute { field, .. }) => assert_eq!(field, "first"),
+            other => panic!("Expected attribute error, got {:?}", other),
+        }
+    }
+    // Method calls, with or without arguments, still reach the functions
+    for prog in ["call", "call_args", "expr_call"] {
+        match ctx.exec(prog, &exec) {
+            Err(CelError::Argument(_)) => {}
+            other => panic!("Expected argument error, got {:?}", other),
+        }
+    }
+}
+
+#[test]
+fn test_program_comments() {
+    let prog = Program::from_source("foo // the foo\n  + bar // the bar").unwrap();
+
+    let comments: Vec<_> = prog
+        .details()
+        .comments()
+        .iter()
+        .map(|c| c.node().as_str())
+        .collect();
+    assert_eq!(comments, vec!["// the foo", "// the bar"]);
+    assert_eq!(prog.details().comments()[1].start().line(), 1);
+}
+
+#[test]
+fn test_bind_scope() {
+    let mut ctx = CelContext::new();
+    let mut exec = BindContext::new();
+
+    ctx.add_program_str("main", "[cel.bind(x, x + 1, x * 2), x]")
+        .unwrap();
+    exec.bind_param("x", 3.into());
+
+    assert_eq!(
+        ctx.exec("main", &exec).unwrap(),
+        CelValue::from_val_slice(&[8.into(), 3.into()])
+    );
+}
+
+#[test]
+fn test_bind_params() {
+    let prog = Program::from_source(
+        "cel.bind(items, request.items.filter(i, i > limit), items.size() > 1 && items[0] > 2)",
+    )
+    .unwrap();
+
+    let mut params = prog.params();
+    params.sort();
+    assert_eq!(params, vec!["limit", "request"]);
+}
+
+#[test]
+fn test_overflow_error() {
+    let mut ctx = CelContext::new();
+    let exec = BindContext::new();
+
+    ctx.add_program_str("main", "9223372036854775807 + 1")
+        .unwrap();
+
+    assert!(matches!(
+        ctx.exec("main", &exec),
+        Err(CelError::Overflow(_))
+    ));
+}
+
+#[test_case("9223372036854775808"; "int literal")]
+#[test_case("18446744073709551616"; "past uint")]
+#[test_case("0x8000000000000000"; "hex literal")]
+#[test_case("-(9223372036854775808)"; "parenthesized")]
+fn test_int_literal_out_of_range(prog: &str) {
+    assert!(matches!(
+        Program::from_source(prog),
+        Err(CelError::Syntax(_))
+    ));
+}
@@ -1,3 +1,5 @@
+mod clock_tests;
+mod dialect_tests;
 mod general_tests;
 mod neg_index_tests;
 mod type_prop_tests;
@@ -1,4 +1,6 @@
-use crate::{BindContext, CelContext, CelError, CelValue};
+use test_case::test_case;
+
+use crate::{BindContext, CelContext, CelError, CelResult, CelValue};
 
 const EPSILON: f64 = 1e-6;
 
@@ -59,6 +61,74 @@ fn converts_temperature_units() {
     assert_close(kelvin, 0.0);
 }
 
+#[test_case("uomConvert(1, 'mi', 'km')", 1.609_344; "length")]
+#[test_case("uomConvert(1, 'nmi', 'ft')", 6_076.115_485_564_304; "nautical miles")]
+#[test_case("uomConvert(1, 'acre', 'ft2')", 43_560.0; "area")]
+#[test_case("uomConvert(1.5, 'h', 'min')", 90.0; "time")]
+#[test_case("uomConvert(2, 'weeks', 'd')", 14.0; "weeks")]
+#[test_case("uomConvert(1, 'GiB', 'MB')", 1_073.741_824; "data size")]
+#[test_case("uomConvert(1, 'B', 'bit')", 8.0; "bits")]
+#[test_case("uomConvert(1, 'bar', 'kPa')", 100.0; "pressure")]
+#[test_case("uomConvert(1, 'psi', 'Pa')", 6_894.757_889_515_779; "psi")]
+#[test_case("uomConvert(1, 'kWh', 'J')", 3_600_000.0; "energy")]
+#[test_case("uomConvert(1, 'BTU', 'J')", 1_055.056; "btu")]
+#[test_case("uomConvert(1, 'hp', 'W')", 745.699_9; "power")]
+#[test_case("uomConvert(3, 'GHz', 'MHz')", 3_000.0; "frequency")]
+#[test_case("uomConvert(60, 'rpm', 'Hz')", 1.0; "rpm")]
+fn converts_more_units(expr: &str, expected: f64) {
+    assert_close(eval_float(expr), expected);
+}
+
+#[test_case("quantity(1, 'MW') == 1000kW"; "megawatt")]
+#[test_case("1MW == quantity(1, 'megawatt')"; "megawatt literal")]
+#[test_case("quantity(1, 'Megawatts') == 1000kW"; "megawatt name")]
+#[test_case("quantity(8, 'bit') == quantity(1, 'B')"; "bits and bytes")]
+#[test_case("quantity(1, 'Bytes') == 8bit"; "byte name")]
+#[test_case("quantity(1, 'KB') == quantity(1, 'kB')"; "kilobyte")]
+#[test_case("quantity(1, 'Mbit') == quantity(1, 'megabits')"; "megabit")]
+#[test_case("quantity(1, 'KG') == 1kg"; "case insensitive symbol")]
+fn parses_unit_case(expr: &str) {
+    assert_eq!(eval(expr).unwrap(), true.into());
+}
+
+#[test_case("quantity(1, 'mW')"; "milliwatt")]
+#[test_case("quantity(1, 'b')"; "lower case byte")]
+#[test_case("quantity(1, 'mb')"; "lower case megabyte")]
+#[test_case("quantity(1, 'mPa')"; "millipascal")]
+#[test_case("uomConvert(1, 'Mb', 'B')"; "megabit symbol")]
+fn rejects_ambiguous_units(expr: &str) {
+    match eval(expr) {
+        Err(CelError::Argument(msg)) => assert!(msg.contains("Unsupported unit"), "{msg}"),
+        other => panic!("Expected argument error, got {:?}", other),
+    }
+}
+
+#[test]
+fn rejects_ambiguous_unit_literals() {
+    assert!(matches!(eval("1mW"), Err(CelError::Syntax(_))));
+    assert!(matches!(eval("1mb"), Err(CelError::Syntax(_))));
+}
+
+#[test]
+fn lists_units() {
+    assert_eq!(
+        eval("uomUnits().length").unwrap(),
+        eval("['m', 'km', 'cm', 'mm', 'in', 'ft', 'yd', 'mi', 'nmi']").unwrap()
+    );
+    assert_eq!(
+        eval(
+            "['mass', 'volume', 'speed', 'temperature', 'length', 'area', 'time', 'data', \
+             'pressure', 'energy', 'power', 'frequency'].all(c, c in uomUnits())"
+        )
+        .unwrap(),
+        true.into()
+    );
+    assert_eq!(
+        eval("uomUnits().all(c, uomUnits()[c].all(u, quantity(1, u).unit() == u))").unwrap(),
+        true.into()
+    );
+}
+
 #[test]
 fn rejects_incompatible_units() {
     let mut ctx = CelContext::new();
@@ -90,3 +160,172 @@ fn rejects_unknown_units() {
         other => panic!("Expected argument error, got {:?}", other),
     }
 }
+
+fn eval(expr: &str) -> CelResult<CelValue> {
+    let mut ctx = CelContext::new();
+    let exec_ctx = BindContext::new();
+
+    ctx.add_program_str("main", expr)?;
+    ctx.exec("main", &exec_ctx)
+}
+
+#[test]
+fn builds_quantities() {
+    assert_eq!(eval("5kg == quantity(5, 'kg')").unwrap(), true.into());
+    assert_eq!(eval("string(5.5kg)").unwrap(), "5.5 kg".into());
+    assert_eq!(eval("2.5e1C.unit()").unwrap(), "°C".into());
+    assert_eq!(eval("quantity(3u, 'MPH').unit()").unwrap(), "mph".into());
+    assert_close(eval_float("7lb.magnitude()"), 7.0);
+}
+
+#[test]
+fn adds_quantities_in_left_unit() {
+    assert_eq!(eval("string(5kg + 200g)").unwrap(), "5.2 kg".into());
+    assert_close(eval_float("(5kg + 200g).magnitude()"), 5.2);
+    assert_close(eval_float("(200g + 5kg).magnitude()"), 5200.0);
+    assert_close(eval_float("(1gal - 1l).magnitude()"), 1.0 - 1.0 / 3.785_412);
+}
+
+#[test]
+fn converts_quantities() {
+    assert_close(
+        eval_float("5kg.to('lb').magnitude()"),
+        11.023_112_380_189_792,
+    );
+    assert_eq!(eval("5kg.to('lb').unit()").unwrap(), "lb".into());
+    assert_close(eval_float("quantity(0, 'c').to('f').magnitude()"), 32.0);
+}
+
+#[test]
+fn compares_quantities() {
+    assert_eq!(eval("1kg > 900g").unwrap(), true.into());
+    assert_eq!(eval("1kg == 1000g").unwrap(), true.into());
+    assert_eq!(eval("1kg != 1l").unwrap(), true.into());
+    assert_eq!(eval("1kg == 1").unwrap(), false.into());
+    assert_eq!(eval("string(max(2kg, 3lb, 1000g))").unwrap(), "2 kg".into());
+}
+
+#[test_case("1l == quantity(1000, 'ml')"; "liters and milliliters")]
+#[test_case("1lb == 16oz"; "pounds and ounces")]
+#[test_case("100C == 212F"; "celsius and fahrenheit")]
+#[test_case("1l >= 1000ml && 1l <= 1000ml"; "ordering")]
+#[test_case("(0C).to('F') == 32F"; "converted")]
+#[test_case("5kg in [5000g]"; "in list")]
+#[test_case("sets.contains([1lb, 2lb], [16oz])"; "sets contains")]
+#[test_case("[1l, 1000ml, 1gal].distinct().size() == 2u"; "distinct")]
+fn equates_converted_quantities(expr: &str) {
+    assert_eq!(eval(expr).unwrap(), true.into());
+}
+
+#[test]
+fn keeps_conversion_precision() {
+    assert_eq!(
+        eval("5kg.to('lb').magnitude()").unwrap(),
+        (5.0 / 0.453_592_4).into()
+    );
+    assert_close(eval_float("(0C).to('F').magnitude()"), 32.0);
+    assert_close(eval_float("1l.to('ml').magnitude()"), 1000.0);
+}
+
+#[test]
+fn subtracts_temperatures() {
+    assert_eq!(eval("(30C - 10C).unit()").unwrap(), "K".into());
+    assert_close(eval_float("(30C - 10C).magnitude()"), 20.0);
+    assert_close(eval_float("(212F - 100C).magnitude()"), 0.0);
+    assert_close(eval_float("(300K - 10K).magnitude()"), 290.0);
+    assert_close(eval_float("(2 * 20K).magnitude()"), 40.0);
+}
+
+#[test]
+fn scales_quantities() {
+    assert_close(eval_float("(2kg * 3).magnitude()"), 6.0);
+    assert_close(eval_float("(3 * 2kg).magnitude()"), 6.0);
+    assert_close(eval_float("(10kg / 4).magnitude()"), 2.5);
+    assert_close(eval_float("(-5kg).magnitude()"), -5.0);
+}
+
+#[test]
+fn combines_dimensions() {
+    assert_eq!(eval("(60mph * duration('2h')).unit()").unwrap(), "m".into());
+    assert_close(
+        eval_float("(60mph * duration('2h')).magnitude()"),
+        193_121.28,
+    );
+    assert_close(eval_float("60mph / 30mph"), 2.0);
+    assert_eq!(eval("(1000kg / 1m3).unit()").unwrap(), "kg/m^3".into());
+    assert_eq!(eval("(1m3 / 1kg).unit()").unwrap(), "m^3/kg".into());
+    assert_eq!(eval("(2kg * 10mph).unit()").unwrap(), "kg*m/s".into());
+    assert_eq!(
+        eval("(2kg * 10mph / duration('1s')).unit()").unwrap(),
+        "kg*m/s^2".into()
+    );
+    assert_eq!(
+        eval("(1 / 1kg / duration('2s')).unit()").unwrap(),
+        "1/(kg*s)".into()
+    );
+}
+
+#[test]
+fn names_derived_units() {
+    assert_eq!(eval("string(5km + 300m)").unwrap(), "5.3 km".into());
+    assert_eq!(eval("string(10m * 20m)").unwrap(), "200 m2".into());
+    assert_eq!(eval("string(1 / 2s)").unwrap(), "0.5 Hz".into());
+    assert_eq!(eval("(1GB / duration('8s')).unit()").unwrap(), "B/s".into());
+    assert_close(
+        eval_float("(2kW * duration('3h')).to('kWh').magnitude()"),
+        6.0,
+    );
+    assert_close(eval_float("(3ft).to('in').magnitude()"), 36.0);
+    assert_close(eval_float("(1mi / 1h).to('mph').magnitude()"), 1.0);
+}
+
+#[test]
+fn keeps_in_after_numbers() {
+    assert_eq!(eval("1in [1, 2]").unwrap(), true.into());
+    assert_eq!(eval("quantity(1, 'in') == 1inch").unwrap(), true.into());
+}
+
+#[test_case("5kg + 2l"; "add across dimensions")]
+#[test_case("5kg - 1"; "subtract number")]
+#[test_case("5kg < 2mph"; "compare across dimensions")]
+#[test_case("5kg * 'a'"; "multiply string")]
+fn rejects_mismatched_dimensions(expr: &str) {
+    match eval(expr) {
+        Err(CelError::InvalidOp(msg)) => assert!(msg.contains("kg"), "{msg}"),
+        other => panic!("Expected invalid op error, got {:?}", other),
+    }
+}
+
+#[test_case("0C + 0F"; "add temperatures")]
+#[test_case("20C + 5K"; "add kelvin")]
+#[test_case("20C - 5K"; "subtract kelvin")]
+#[test_case("2 * 20C"; "scale")]
+#[test_case("20F / 2"; "divide")]
+#[test_case("20C * 1kg"; "multiply quantity")]
+fn rejects_absolute_temperature_arithmetic(expr: &str) {
+    match eval(expr) {
+        Err(CelError::InvalidOp(msg)) => assert!(msg.contains("°"), "{msg}"),
+        other => panic!("Expected invalid op error, got {:?}", other),
+    }
+}
+
+#[test_case("5kg / 0"; "by zero")]
+#[test_case("5kg / 0.0"; "by float zero")]
+#[test_case("5kg / 0kph"; "by zero quantity")]
+#[test_case("1 / 0l"; "number by zero quantity")]
+fn rejects_quantity_division_by_zero(expr: &str) {
+    match eval(expr) {
+        Err(CelError::DivideByZero) => {}
+        other => panic!("Expected divide by zero error, got {:?}", other),
+    }
+}
+
+#[test_case("5kg.to('mph')", "Cannot convert"; "convert across dimensions")]
+#[test_case("quantity(5, 'parsec')", "Unsupported unit"; "unknown unit")]
+#[test_case("quantity('5', 'kg')", "expects a number"; "string value")]
+fn rejects_quantity_arguments(expr: &str, message: &str) {
+    match eval(expr) {
+        Err(CelError::Argument(msg)) => assert!(msg.contains(message), "{msg}"),
+        other => panic!("Expected argument error, got {:?}", other),
+    }
+}
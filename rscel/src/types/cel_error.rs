@@ -15,6 +15,7 @@ pub enum CelError {
     Binding { symbol: String },
     Attribute { parent: String, field: String },
     DivideByZero,
+    Overflow(String),
 
     Internal(String),
 }
@@ -49,6 +50,11 @@ impl CelError {
         CelError::Runtime(msg.to_owned())
     }
 
+    /// Integer arithmetic or a conversion whose result is out of range.
+    pub fn overflow(msg: &str) -> CelError {
+        CelError::Overflow(msg.to_owned())
+    }
+
     pub fn binding(sym_name: &str) -> CelError {
         CelError::Binding {
             symbol: sym_name.to_owned(),
@@ -75,6 +81,7 @@ impl CelError {
             Binding { .. } => "BINDING",
             Attribute { .. } => "ATTRIBUTE",
             DivideByZero => "DIVIDE BY ZERO",
+            Overflow(..) => "OVERFLOW",
 
             Internal(..) => "INTERNAL",
         }
@@ -106,6 +113,7 @@ impl fmt::Display for CelError {
                 write!(f, "Field {} does not exist on {}", field, parent)
             }
             DivideByZero => write!(f, "Divide by zero error"),
+            Overflow(msg) => write!(f, "Overflow: {}", msg),
         }
     }
 }
@@ -0,0 +1,73 @@
+use std::{any::Any, fmt, sync::Arc};
+
+use crate::{CelError, CelValue, CelValueDyn};
+
+/// An optional value, `optional.of(x)` or `optional.none()`. Optionals are carried
+/// as `CelValue::Dyn` values and inspected with `hasValue()`, `value()` and `orValue()`.
+#[derive(Debug, Clone)]
+pub struct CelOptional(Option<CelValue>);
+
+impl CelOptional {
+    pub fn new(value: Option<CelValue>) -> CelOptional {
+        CelOptional(value)
+    }
+
+    pub fn value(&self) -> Option<&CelValue> {
+        self.0.as_ref()
+    }
+
+    /// Returns the optional wrapped by `value`, if it is one.
+    pub fn from_cel_value(value: &CelValue) -> Option<&CelOptional> {
+        match value {
+            CelValue::Dyn(d) => d.any_ref().downcast_ref::<CelOptional>(),
+            _ => None,
+        }
+    }
+}
+
+impl From<CelOptional> for CelValue {
+    fn from(value: CelOptional) -> Self {
+        CelValue::from_dyn(Arc::new(value))
+    }
+}
+
+impl fmt::Display for CelOptional {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.0 {
+            Some(v) => write!(f, "optional.of({})", v),
+            None => write!(f, "optional.none()"),
+        }
+    }
+}
+
+impl CelValueDyn for CelOptional {
+    fn as_type(&self) -> CelValue {
+        CelValue::Type("optional_type".to_owned())
+    }
+
+    fn access(&self, key: &str) -> CelValue {
+        CelValue::from_err(CelError::attribute("optional", key))
+    }
+
+    fn eq(&self, rhs: &CelValue) -> CelValue {
+        match CelOptional::from_cel_value(rhs) {
+            Some(CelOptional(Some(r))) => match &self.0 {
+                Some(l) => CelValueDyn::eq(l, r),
+                None => CelValue::false_(),
+            },
+            Some(CelOptional(None)) => CelValue::from_bool(self.0.is_none()),
+            None => CelValue::from_err(CelError::invalid_op(&format!(
+                "Invalid op == between optional_type and {}",
+                rhs.as_type()
+            ))),
+        }
+    }
+
+    fn is_truthy(&self) -> bool {
+        self.0.is_some()
+    }
+
+    fn any_ref<'a>(&'a self) -> &'a dyn Any {
+        self
+    }
+}
@@ -0,0 +1,294 @@
+use std::{any::Any, cmp::Ordering, fmt, sync::Arc};
+
+use crate::{
+    context::default_funcs::uom::{Dimension, TemperatureUnit, Unit},
+    CelError, CelResult, CelValue, CelValueDyn,
+};
+
+/// Conversions through SI units are inexact in the last bits of a double, quantities
+/// within this relative difference of each other are equal.
+const RELATIVE_EPSILON: f64 = 1e-12;
+
+/// A number with a unit, `quantity(5, 'kg')` or `5kg`. Quantities are carried as
+/// `CelValue::Dyn` values; adding, subtracting and comparing them requires the same
+/// dimension, while multiplying and dividing combines their dimensions. Degrees
+/// Celsius and Fahrenheit are absolute temperatures, they can only be subtracted from
+/// each other, giving the difference in kelvin.
+#[derive(Debug, Clone, Copy)]
+pub struct CelQuantity {
+    value: f64,
+    unit: Unit,
+}
+
+impl CelQuantity {
+    pub(crate) fn new(value: f64, unit: Unit) -> CelQuantity {
+        CelQuantity { value, unit }
+    }
+
+    /// A quantity of `value` in the unit named `unit`, like `"lb"`.
+    pub fn parse(value: f64, unit: &str) -> CelResult<CelQuantity> {
+        Ok(CelQuantity::new(value, parse_unit(unit)?))
+    }
+
+    /// The value in the quantity's unit.
+    pub fn magnitude(&self) -> f64 {
+        self.value
+    }
+
+    /// The symbol of the quantity's unit.
+    pub fn symbol(&self) -> String {
+        self.unit.symbol()
+    }
+
+    /// Returns the quantity wrapped by `value`, if it is one.
+    pub fn from_cel_value(value: &CelValue) -> Option<&CelQuantity> {
+        match value {
+            CelValue::Dyn(d) => d.any_ref().downcast_ref::<CelQuantity>(),
+            _ => None,
+        }
+    }
+
+    /// The same quantity in the unit named `unit`, which must measure the same dimension.
+    pub fn to(&self, unit: &str) -> CelResult<CelQuantity> {
+        let unit = parse_unit(unit)?;
+
+        if unit.dimension() != self.unit.dimension() {
+            return Err(CelError::argument(&format!(
+                "Cannot convert units '{}' -> '{}'.",
+                self.unit.symbol(),
+                unit.symbol()
+            )));
+        }
+
+        Ok(CelQuantity::new(unit.from_si(self.si()), unit))
+    }
+
+    fn si(&self) -> f64 {
+        self.unit.to_si(self.value)
+    }
+
+    /// A quantity from a value in the SI unit of `dimension`, a float when dimensionless.
+    fn from_si(si: f64, dimension: Dimension) -> CelValue {
+        if dimension == Dimension::NONE {
+            return CelValue::from_float(si);
+        }
+
+        let unit = Unit::si(dimension);
+        CelQuantity::new(unit.from_si(si), unit).into()
+    }
+
+    /// `lhs + rhs` when either is a quantity. The result is in the unit of `lhs`.
+    pub(crate) fn add(lhs: &CelValue, rhs: &CelValue) -> Option<CelValue> {
+        CelQuantity::same_dimension(lhs, rhs, "+", |l, r| l + r)
+    }
+
+    /// `lhs - rhs` when either is a quantity. The result is in the unit of `lhs`, or in
+    /// kelvin for the difference of two absolute temperatures.
+    pub(crate) fn sub(lhs: &CelValue, rhs: &CelValue) -> Option<CelValue> {
+        if let (Some(l), Some(r)) = (
+            CelQuantity::from_cel_value(lhs),
+            CelQuantity::from_cel_value(rhs),
+        ) {
+            if l.unit.has_offset() && r.unit.has_offset() {
+                let kelvin = Unit::Temperature(TemperatureUnit::Kelvin);
+                return Some(CelQuantity::new(l.si() - r.si(), kelvin).into());
+            }
+        }
+
+        CelQuantity::same_dimension(lhs, rhs, "-", |l, r| l - r)
+    }
+
+    fn same_dimension(
+        lhs: &CelValue,
+        rhs: &CelValue,
+        op: &str,
+        func: fn(f64, f64) -> f64,
+    ) -> Option<CelValue> {
+        let res = match (
+            CelQuantity::from_cel_value(lhs),
+            CelQuantity::from_cel_value(rhs),
+        ) {
+            (None, None) => return None,
+            _ if has_offset(lhs) || has_offset(rhs) => invalid_op(op, lhs, rhs),
+            (Some(l), Some(r)) if l.unit.dimension() == r.unit.dimension() => {
+                let r = l.unit.from_si(r.si());
+                CelQuantity::new(func(l.value, r), l.unit).into()
+            }
+            _ => invalid_op(op, lhs, rhs),
+        };
+
+        Some(res)
+    }
+
+    /// `lhs * rhs` when either is a quantity. Numbers scale a quantity, other quantities
+    /// and durations multiply its dimension.
+    pub(crate) fn mul(lhs: &CelValue, rhs: &CelValue) -> Option<CelValue> {
+        let scaled = match (
+            CelQuantity::from_cel_value(lhs),
+            CelQuantity::from_cel_value(rhs),
+        ) {
+            (None, None) => return None,
+            _ if has_offset(lhs) || has_offset(rhs) => return Some(invalid_op("*", lhs, rhs)),
+            (Some(q), None) => number(rhs).map(|n| (q, n)),
+            (None, Some(q)) => number(lhs).map(|n| (q, n)),
+            (Some(_), Some(_)) => None,
+        };
+
+        let res = match (scaled, measure(lhs), measure(rhs)) {
+            (Some((q, n)), _, _) => CelQuantity::new(q.value * n, q.unit).into(),
+            (None, Some((l, l_dim)), Some((r, r_dim))) => {
+                CelQuantity::from_si(l * r, l_dim.mul(r_dim))
+            }
+            _ => invalid_op("*", lhs, rhs),
+        };
+
+        Some(res)
+    }
+
+    /// `lhs / rhs` when either is a quantity. Numbers scale a quantity, other quantities
+    /// and durations divide its dimension.
+    pub(crate) fn div(lhs: &CelValue, rhs: &CelValue) -> Option<CelValue> {
+        let scaled = match (
+            CelQuantity::from_cel_value(lhs),
+            CelQuantity::from_cel_value(rhs),
+        ) {
+            (None, None) => return None,
+            _ if has_offset(lhs) || has_offset(rhs) => return Some(invalid_op("/", lhs, rhs)),
+            (q, _) => q.zip(number(rhs)),
+        };
+
+        let res = match (scaled, measure(lhs), measure(rhs)) {
+            (Some((_, n)), _, _) | (None, _, Some((n, _))) if n == 0.0 => {
+                CelValue::from_err(CelError::DivideByZero)
+            }
+            (Some((q, n)), _, _) => CelQuantity::new(q.value / n, q.unit).into(),
+            (None, Some((l, l_dim)), Some((r, r_dim))) => {
+                CelQuantity::from_si(l / r, l_dim.div(r_dim))
+            }
+            _ => invalid_op("/", lhs, rhs),
+        };
+
+        Some(res)
+    }
+
+    /// `-value` when it is a quantity.
+    pub(crate) fn neg(value: &CelValue) -> Option<CelValue> {
+        CelQuantity::from_cel_value(value).map(|q| CelQuantity::new(-q.value, q.unit).into())
+    }
+
+    /// Orders `lhs` and `rhs` when either is a quantity.
+    pub(crate) fn ord(lhs: &CelValue, rhs: &CelValue) -> Option<CelResult<Option<Ordering>>> {
+        let res = match (
+            CelQuantity::from_cel_value(lhs),
+            CelQuantity::from_cel_value(rhs),
+        ) {
+            (None, None) => return None,
+            (Some(l), Some(r)) if l.unit.dimension() == r.unit.dimension() => {
+                if approx_eq(l.si(), r.si()) {
+                    Ok(Some(Ordering::Equal))
+                } else {
+                    Ok(l.si().partial_cmp(&r.si()))
+                }
+            }
+            _ => Err(CelError::invalid_op(&format!(
+                "Invalid op 'ord' between {} and {}",
+                describe(lhs),
+                describe(rhs)
+            ))),
+        };
+
+        Some(res)
+    }
+}
+
+fn parse_unit(unit: &str) -> CelResult<Unit> {
+    Unit::from_str(unit).ok_or_else(|| CelError::argument(&format!("Unsupported unit '{}'.", unit)))
+}
+
+/// Whether `value` is a temperature in degrees Celsius or Fahrenheit, whose zero is not
+/// the zero of kelvin.
+fn has_offset(value: &CelValue) -> bool {
+    CelQuantity::from_cel_value(value).is_some_and(|q| q.unit.has_offset())
+}
+
+fn approx_eq(lhs: f64, rhs: f64) -> bool {
+    lhs == rhs || (lhs - rhs).abs() <= RELATIVE_EPSILON * lhs.abs().max(rhs.abs())
+}
+
+fn number(value: &CelValue) -> Option<f64> {
+    match value {
+        CelValue::Int(i) => Some(*i as f64),
+        CelValue::UInt(u) => Some(*u as f64),
+        CelValue::Float(f) => Some(*f),
+        _ => None,
+    }
+}
+
+/// The SI value and dimension of a quantity, duration or number.
+fn measure(value: &CelValue) -> Option<(f64, Dimension)> {
+    match (CelQuantity::from_cel_value(value), value) {
+        (Some(q), _) => Some((q.si(), q.unit.dimension())),
+        (None, CelValue::Duration(d)) => Some((
+            d.num_seconds() as f64 + d.subsec_nanos() as f64 / 1e9,
+            Dimension::TIME,
+        )),
+        (None, other) => number(other).map(|n| (n, Dimension::NONE)),
+    }
+}
+
+/// The unit of a quantity, the type of anything else.
+fn describe(value: &CelValue) -> String {
+    match CelQuantity::from_cel_value(value) {
+        Some(q) => q.unit.symbol(),
+        None => value.as_type().to_string(),
+    }
+}
+
+fn invalid_op(op: &str, lhs: &CelValue, rhs: &CelValue) -> CelValue {
+    CelValue::from_err(CelError::invalid_op(&format!(
+        "Invalid op '{}' between {} and {}",
+        op,
+        describe(lhs),
+        describe(rhs)
+    )))
+}
+
+impl From<CelQuantity> for CelValue {
+    fn from(value: CelQuantity) -> Self {
+        CelValue::from_dyn(Arc::new(value))
+    }
+}
+
+/// The value and the unit's symbol, like `5.5 kg`.
+impl fmt::Display for CelQuantity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.value, self.unit.symbol())
+    }
+}
+
+impl CelValueDyn for CelQuantity {
+    fn as_type(&self) -> CelValue {
+        CelValue::Type("quantity".to_owned())
+    }
+
+    fn access(&self, key: &str) -> CelValue {
+        CelValue::from_err(CelError::attribute("quantity", key))
+    }
+
+    fn eq(&self, rhs: &CelValue) -> CelValue {
+        match CelQuantity::from_cel_value(rhs) {
+            Some(r) if r.unit.dimension() == self.unit.dimension() => {
+                CelValue::from_bool(approx_eq(self.si(), r.si()))
+            }
+            _ => CelValue::false_(),
+        }
+    }
+
+    fn is_truthy(&self) -> bool {
+        self.value != 0.0
+    }
+
+    fn any_ref<'a>(&'a self) -> &'a dyn Any {
+        self
+    }
+}
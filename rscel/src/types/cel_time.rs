@@ -0,0 +1,318 @@
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+use serde_with::{DeserializeAs, SerializeAs};
+
+/// Timestamps are limited to the years 0001 through 9999 and durations to roughly
+/// 10,000 years either way, the ranges of the protobuf well known types.
+pub(crate) const MIN_TIMESTAMP_SECS: i64 = -62_135_596_800;
+pub(crate) const MAX_TIMESTAMP_SECS: i64 = 253_402_300_799;
+pub(crate) const MAX_DURATION_SECS: i64 = 315_576_000_000;
+
+const NANOS_PER_SEC: i128 = 1_000_000_000;
+const MINUTE: i128 = 60 * NANOS_PER_SEC;
+const HOUR: i128 = 60 * MINUTE;
+const DAY: i128 = 24 * HOUR;
+
+pub(crate) fn timestamp_in_range(ts: &DateTime<Utc>) -> bool {
+    (MIN_TIMESTAMP_SECS..=MAX_TIMESTAMP_SECS).contains(&ts.timestamp())
+}
+
+pub(crate) fn duration_in_range(d: &Duration) -> bool {
+    d.num_seconds().abs() <= MAX_DURATION_SECS
+}
+
+/// RFC 3339 in UTC with as many fractional digits as the nanoseconds need, like
+/// `2009-02-13T23:31:30.123456789Z`.
+pub(crate) fn format_timestamp(ts: &DateTime<Utc>) -> String {
+    ts.to_rfc3339_opts(SecondsFormat::AutoSi, true)
+}
+
+/// Seconds with as many fractional digits as the nanoseconds need, like `-1.5s`.
+pub(crate) fn format_duration(d: &Duration) -> String {
+    let nanos = duration_nanos(d);
+    let sign = if nanos < 0 { "-" } else { "" };
+    let secs = (nanos / NANOS_PER_SEC).unsigned_abs();
+    let frac = (nanos % NANOS_PER_SEC).unsigned_abs();
+
+    if frac == 0 {
+        format!("{}{}s", sign, secs)
+    } else {
+        let digits = format!("{:09}", frac);
+        format!("{}{}.{}s", sign, secs, digits.trim_end_matches('0'))
+    }
+}
+
+/// Parses a CEL duration, an optionally signed sequence of decimal numbers each with a
+/// unit of `h`, `m`, `s`, `ms`, `us` or `ns` such as `1h2m3.5s`, exactly to the
+/// nanosecond. Digits past nanoseconds are truncated.
+pub(crate) fn parse_duration(s: &str) -> Option<Duration> {
+    let (negative, rest) = split_sign(s);
+
+    if rest.is_empty() {
+        return None;
+    }
+
+    let total = sum_components(rest, |unit| match unit {
+        "h" => Some(HOUR),
+        "m" => Some(MINUTE),
+        "s" => Some(NANOS_PER_SEC),
+        "ms" => Some(1_000_000),
+        "us" | "µs" => Some(1_000),
+        "ns" => Some(1),
+        _ => None,
+    })?;
+
+    nanos_duration(if negative { -total } else { total })
+}
+
+/// Parses an ISO-8601 duration such as `P1DT2H` or `-PT1.5S`. Weeks and days are
+/// taken as 7 and 1 times 24 hours, years and months have no fixed length and aren't
+/// accepted.
+pub(crate) fn parse_iso8601_duration(s: &str) -> Option<Duration> {
+    let (negative, rest) = split_sign(s);
+    let rest = rest.strip_prefix('P')?;
+
+    let (date, time) = match rest.split_once('T') {
+        Some((_, "")) => return None,
+        Some((date, time)) => (date, time),
+        None => (rest, ""),
+    };
+
+    if date.is_empty() && time.is_empty() {
+        return None;
+    }
+
+    let date_total = sum_components(date, |unit| match unit {
+        "W" => Some(7 * DAY),
+        "D" => Some(DAY),
+        _ => None,
+    })?;
+    let time_total = sum_components(time, |unit| match unit {
+        "H" => Some(HOUR),
+        "M" => Some(MINUTE),
+        "S" => Some(NANOS_PER_SEC),
+        _ => None,
+    })?;
+    let total = date_total.checked_add(time_total)?;
+
+    nanos_duration(if negative { -total } else { total })
+}
+
+/// ISO-8601 with days of 24 hours as the largest unit, like `P1DT2H3M4.5S`.
+pub(crate) fn format_iso8601_duration(d: &Duration) -> String {
+    let nanos = duration_nanos(d);
+    let abs = nanos.unsigned_abs();
+
+    let days = abs / DAY as u128;
+    let hours = abs % DAY as u128 / HOUR as u128;
+    let minutes = abs % HOUR as u128 / MINUTE as u128;
+    let secs = abs % MINUTE as u128 / NANOS_PER_SEC as u128;
+    let frac = abs % NANOS_PER_SEC as u128;
+
+    let mut res = String::from(if nanos < 0 { "-P" } else { "P" });
+    if days > 0 {
+        res.push_str(&format!("{}D", days));
+    }
+    if hours > 0 || minutes > 0 || secs > 0 || frac > 0 || days == 0 {
+        res.push('T');
+    }
+    if hours > 0 {
+        res.push_str(&format!("{}H", hours));
+    }
+    if minutes > 0 {
+        res.push_str(&format!("{}M", minutes));
+    }
+    if frac > 0 {
+        let digits = format!("{:09}", frac);
+        res.push_str(&format!("{}.{}S", secs, digits.trim_end_matches('0')));
+    } else if secs > 0 || abs == 0 {
+        res.push_str(&format!("{}S", secs));
+    }
+
+    res
+}
+
+fn split_sign(s: &str) -> (bool, &str) {
+    match s.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, s.strip_prefix('+').unwrap_or(s)),
+    }
+}
+
+/// Sums a sequence of decimal numbers each followed by a unit, in nanoseconds.
+fn sum_components(mut rest: &str, unit_nanos: impl Fn(&str) -> Option<i128>) -> Option<i128> {
+    let mut total: i128 = 0;
+
+    while !rest.is_empty() {
+        let number_len = rest
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(rest.len());
+        let (number, tail) = rest.split_at(number_len);
+        let unit_len = tail
+            .find(|c: char| c.is_ascii_digit() || c == '.')
+            .unwrap_or(tail.len());
+        let (unit, tail) = tail.split_at(unit_len);
+
+        let unit_nanos = unit_nanos(unit)?;
+
+        let (whole, frac) = number.split_once('.').unwrap_or((number, ""));
+        if (whole.is_empty() && frac.is_empty()) || frac.contains('.') {
+            return None;
+        }
+
+        if !whole.is_empty() {
+            total = total.checked_add(whole.parse::<i128>().ok()?.checked_mul(unit_nanos)?)?;
+        }
+
+        // 18 digits is past a nanosecond of any unit and keeps the product in range
+        let frac = &frac[..frac.len().min(18)];
+        if !frac.is_empty() {
+            let scale = 10i128.pow(frac.len() as u32);
+            total = total.checked_add(frac.parse::<i128>().ok()? * unit_nanos / scale)?;
+        }
+
+        rest = tail;
+    }
+
+    Some(total)
+}
+
+fn duration_nanos(d: &Duration) -> i128 {
+    d.num_seconds() as i128 * NANOS_PER_SEC + d.subsec_nanos() as i128
+}
+
+fn nanos_duration(nanos: i128) -> Option<Duration> {
+    let secs = i64::try_from(nanos.div_euclid(NANOS_PER_SEC)).ok()?;
+    Duration::new(secs, nanos.rem_euclid(NANOS_PER_SEC) as u32)
+}
+
+/// Serializes a timestamp as seconds since the epoch and nanoseconds. Milliseconds
+/// since the epoch, as timestamps were serialized before keeping nanoseconds, are
+/// still accepted.
+pub(crate) struct TimestampNanos;
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum TimestampRepr {
+    Nanos(i64, u32),
+    Millis(i64),
+}
+
+impl SerializeAs<DateTime<Utc>> for TimestampNanos {
+    fn serialize_as<S: Serializer>(ts: &DateTime<Utc>, serializer: S) -> Result<S::Ok, S::Error> {
+        (ts.timestamp(), ts.timestamp_subsec_nanos()).serialize(serializer)
+    }
+}
+
+impl<'de> DeserializeAs<'de, DateTime<Utc>> for TimestampNanos {
+    fn deserialize_as<D: Deserializer<'de>>(deserializer: D) -> Result<DateTime<Utc>, D::Error> {
+        let ts = match TimestampRepr::deserialize(deserializer)? {
+            TimestampRepr::Nanos(secs, nanos) => DateTime::from_timestamp(secs, nanos),
+            TimestampRepr::Millis(millis) => DateTime::from_timestamp_millis(millis),
+        };
+
+        ts.filter(timestamp_in_range)
+            .ok_or_else(|| D::Error::custom("timestamp out of range"))
+    }
+}
+
+/// Serializes a duration as seconds and nanoseconds, both carrying its sign.
+/// Milliseconds, as durations were serialized before keeping nanoseconds, are still
+/// accepted.
+pub(crate) struct DurationNanos;
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum DurationRepr {
+    Nanos(i64, i32),
+    Millis(i64),
+}
+
+impl SerializeAs<Duration> for DurationNanos {
+    fn serialize_as<S: Serializer>(d: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        (d.num_seconds(), d.subsec_nanos()).serialize(serializer)
+    }
+}
+
+impl<'de> DeserializeAs<'de, Duration> for DurationNanos {
+    fn deserialize_as<D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        let nanos = match DurationRepr::deserialize(deserializer)? {
+            DurationRepr::Nanos(secs, nanos) => secs as i128 * NANOS_PER_SEC + nanos as i128,
+            DurationRepr::Millis(millis) => millis as i128 * 1_000_000,
+        };
+
+        nanos_duration(nanos)
+            .filter(duration_in_range)
+            .ok_or_else(|| D::Error::custom("duration out of range"))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::Duration;
+    use test_case::test_case;
+
+    use super::{format_duration, format_iso8601_duration, parse_duration, parse_iso8601_duration};
+
+    #[test_case("1.5s", 1, 500_000_000)]
+    #[test_case("-1.5s", -2, 500_000_000)]
+    #[test_case("1h2m3.004s", 3_723, 4_000_000)]
+    #[test_case("123.321456789s", 123, 321_456_789)]
+    #[test_case("1.0000000019s", 1, 1)]
+    #[test_case(".5ms", 0, 500_000)]
+    #[test_case("1us1ns", 0, 1_001)]
+    #[test_case("315576000000s", 315_576_000_000, 0)]
+    fn test_parse_duration(s: &str, secs: i64, nanos: u32) {
+        assert_eq!(parse_duration(s), Duration::new(secs, nanos));
+    }
+
+    #[test_case("")]
+    #[test_case("-")]
+    #[test_case("1")]
+    #[test_case("1.2.3s")]
+    #[test_case("1d")]
+    #[test_case(".s")]
+    fn test_parse_duration_invalid(s: &str) {
+        assert_eq!(parse_duration(s), None);
+    }
+
+    #[test_case("1.5s")]
+    #[test_case("-0.5s")]
+    #[test_case("3723s")]
+    #[test_case("123.321456789s")]
+    #[test_case("-315576000000.000000001s")]
+    fn test_format_duration(s: &str) {
+        assert_eq!(format_duration(&parse_duration(s).unwrap()), s);
+    }
+
+    #[test_case("P1DT2H", 93_600, 0)]
+    #[test_case("PT1.5S", 1, 500_000_000)]
+    #[test_case("-PT5M", -300, 0)]
+    #[test_case("P2W", 1_209_600, 0)]
+    #[test_case("P1D", 86_400, 0)]
+    #[test_case("PT0S", 0, 0)]
+    fn test_parse_iso8601_duration(s: &str, secs: i64, nanos: u32) {
+        assert_eq!(parse_iso8601_duration(s), Duration::new(secs, nanos));
+    }
+
+    #[test_case("P"; "empty")]
+    #[test_case("P1DT"; "empty time")]
+    #[test_case("P1Y"; "years")]
+    #[test_case("P1M"; "months")]
+    #[test_case("1D"; "missing designator")]
+    fn test_parse_iso8601_duration_invalid(s: &str) {
+        assert_eq!(parse_iso8601_duration(s), None);
+    }
+
+    #[test_case("P1DT2H3M4.5S")]
+    #[test_case("-PT0.000000001S")]
+    #[test_case("P1D")]
+    #[test_case("PT1H")]
+    #[test_case("PT0S")]
+    fn test_format_iso8601_duration(s: &str) {
+        assert_eq!(
+            format_iso8601_duration(&parse_iso8601_duration(s).unwrap()),
+            s
+        );
+    }
+}
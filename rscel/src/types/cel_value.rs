@@ -1,6 +1,6 @@
-use chrono::{offset::Utc, serde::ts_milliseconds, DateTime, Duration, FixedOffset};
+use chrono::{offset::Utc, DateTime, Duration, FixedOffset};
 use serde::{Deserialize, Serialize};
-use serde_with::{serde_as, DeserializeAs, DurationMilliSeconds, SerializeAs};
+use serde_with::{serde_as, DeserializeAs, SerializeAs};
 use std::{
     any::Any,
     cmp::Ordering,
@@ -21,7 +21,11 @@ use protobuf::{
 
 use crate::{interp::ByteCode, CelError, CelResult, CelValueDyn};
 
-use super::{cel_byte_code::CelByteCode, CelBytes};
+use super::{
+    cel_byte_code::CelByteCode,
+    cel_time::{duration_in_range, timestamp_in_range, DurationNanos, TimestampNanos},
+    CelBytes, CelQuantity, CelZonedTimestamp,
+};
 
 pub type CelTimeStamp = DateTime<Utc>;
 pub type CelValueVec = Vec<CelValue>;
@@ -33,8 +37,8 @@ pub type CelValueMap = HashMap<String, CelValue>;
 /// interpreter
 // Only the enum values that are also part of the language are serializable, aka int
 // because int literals can exist. If you can't represent it as part of the language
-// it doesn't need to be serialized. The time types will be serialized to
-// milliseconds resolution.
+// it doesn't need to be serialized. The time types are serialized to nanosecond
+// resolution.
 #[serde_as]
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub enum CelValue {
@@ -49,11 +53,14 @@ pub enum CelValue {
     Null,
     Ident(String),
     Type(String),
-    #[serde(with = "ts_milliseconds")]
+    #[serde(
+        serialize_with = "TimestampNanos::serialize_as",
+        deserialize_with = "TimestampNanos::deserialize_as"
+    )]
     TimeStamp(CelTimeStamp),
     #[serde(
-        serialize_with = "DurationMilliSeconds::<i64>::serialize_as",
-        deserialize_with = "DurationMilliSeconds::<i64>::deserialize_as"
+        serialize_with = "DurationNanos::serialize_as",
+        deserialize_with = "DurationNanos::deserialize_as"
     )]
     Duration(Duration),
     ByteCode(CelByteCode),
@@ -372,10 +379,17 @@ impl CelValue {
     }
 
     pub fn ord(self, rhs_value: CelValue) -> CelResult<Option<Ordering>> {
+        if let Some(res) = CelQuantity::ord(&self, &rhs_value) {
+            return res;
+        }
+
         let type1 = self.as_type();
         let type2 = rhs_value.as_type();
 
-        let (lhs, rhs) = CelValue::type_prop(self, rhs_value);
+        let (lhs, rhs) = CelValue::type_prop(
+            CelZonedTimestamp::unzone(self),
+            CelZonedTimestamp::unzone(rhs_value),
+        );
 
         match (lhs, rhs) {
             (CelValue::Int(l), CelValue::Int(r)) => Ok(l.partial_cmp(&r)),
@@ -479,7 +493,7 @@ impl CelValue {
             match rhs {
                 CelValue::List(l) => {
                     for value in l.iter() {
-                        if lhs == *value {
+                        if CelValueDyn::eq(&lhs, value).is_true() {
                             return true.into();
                         }
                     }
@@ -704,7 +718,10 @@ impl CelValueDyn for CelValue {
                     rhs_val
                 };
 
-                let (lhs, rhs) = CelValue::type_prop(lhs_val, rhs);
+                let (lhs, rhs) = CelValue::type_prop(
+                    CelZonedTimestamp::unzone(lhs_val),
+                    CelZonedTimestamp::unzone(rhs),
+                );
 
                 match (lhs, rhs) {
                     (CelValue::Int(l), CelValue::Int(r)) => CelValue::from_bool(l == r),
@@ -1224,11 +1241,49 @@ impl PartialEq for CelValue {
     }
 }
 
+fn checked_int<T: Into<CelValue>>(
+    res: Option<T>,
+    lhs: impl fmt::Display,
+    op: &str,
+    rhs: impl fmt::Display,
+) -> CelValue {
+    match res {
+        Some(val) => val.into(),
+        None => CelValue::from_err(CelError::overflow(&format!("{} {} {}", lhs, op, rhs))),
+    }
+}
+
+fn checked_timestamp(res: Option<DateTime<Utc>>) -> CelValue {
+    match res {
+        Some(ts) if timestamp_in_range(&ts) => CelValue::from_timestamp(ts),
+        _ => CelValue::from_err(CelError::overflow("timestamp out of range")),
+    }
+}
+
+fn checked_duration(res: Option<Duration>) -> CelValue {
+    match res {
+        Some(d) if duration_in_range(&d) => CelValue::from_duration(d),
+        _ => CelValue::from_err(CelError::overflow("duration out of range")),
+    }
+}
+
 impl Add for CelValue {
     type Output = CelValue;
 
     fn add(self, rhs_val: Self) -> Self::Output {
+        // Zoned timestamps keep their zone through arithmetic on their instant
+        if let Some(zone) = CelZonedTimestamp::zone_of(&self, &rhs_val) {
+            return CelZonedTimestamp::rezone(
+                CelZonedTimestamp::unzone(self) + CelZonedTimestamp::unzone(rhs_val),
+                zone,
+            );
+        }
+
         self.error_prop_or(rhs_val, |lhs_val, rhs_val| {
+            if let Some(res) = CelQuantity::add(&lhs_val, &rhs_val) {
+                return res;
+            }
+
             let type1 = lhs_val.as_type();
             let type2 = rhs_val.as_type();
 
@@ -1241,12 +1296,12 @@ impl Add for CelValue {
             match lhs {
                 CelValue::Int(val1) => {
                     if let CelValue::Int(val2) = rhs {
-                        return CelValue::from(val1 + val2);
+                        return checked_int(val1.checked_add(val2), val1, "+", val2);
                     }
                 }
                 CelValue::UInt(val1) => {
                     if let CelValue::UInt(val2) = rhs {
-                        return CelValue::from(val1 + val2);
+                        return checked_int(val1.checked_add(val2), val1, "+", val2);
                     }
                 }
                 CelValue::Float(val1) => {
@@ -1277,12 +1332,12 @@ impl Add for CelValue {
                 }
                 CelValue::TimeStamp(v1) => {
                     if let CelValue::Duration(v2) = rhs {
-                        return CelValue::from_timestamp(v1 + v2);
+                        return checked_timestamp(v1.checked_add_signed(v2));
                     }
                 }
                 CelValue::Duration(v1) => match rhs {
-                    CelValue::TimeStamp(v2) => return CelValue::from_timestamp(v2 + v1),
-                    CelValue::Duration(v2) => return CelValue::Duration(v1 + v2),
+                    CelValue::TimeStamp(v2) => return checked_timestamp(v2.checked_add_signed(v1)),
+                    CelValue::Duration(v2) => return checked_duration(v1.checked_add(&v2)),
                     _ => {}
                 },
                 _ => {}
@@ -1300,7 +1355,19 @@ impl Sub for CelValue {
     type Output = CelValue;
 
     fn sub(self, rhs_val: Self) -> Self::Output {
+        // Zoned timestamps keep their zone through arithmetic on their instant
+        if let Some(zone) = CelZonedTimestamp::zone_of(&self, &rhs_val) {
+            return CelZonedTimestamp::rezone(
+                CelZonedTimestamp::unzone(self) - CelZonedTimestamp::unzone(rhs_val),
+                zone,
+            );
+        }
+
         self.error_prop_or(rhs_val, |lhs_val, rhs_val| {
+            if let Some(res) = CelQuantity::sub(&lhs_val, &rhs_val) {
+                return res;
+            }
+
             let type1 = lhs_val.as_type();
             let type2 = rhs_val.as_type();
 
@@ -1313,12 +1380,12 @@ impl Sub for CelValue {
             match lhs {
                 CelValue::Int(val1) => {
                     if let CelValue::Int(val2) = rhs {
-                        return CelValue::from(val1 - val2);
+                        return checked_int(val1.checked_sub(val2), val1, "-", val2);
                     }
                 }
                 CelValue::UInt(val1) => {
                     if let CelValue::UInt(val2) = rhs {
-                        return CelValue::from(val1 - val2);
+                        return checked_int(val1.checked_sub(val2), val1, "-", val2);
                     }
                 }
                 CelValue::Float(val1) => {
@@ -1327,13 +1394,15 @@ impl Sub for CelValue {
                     }
                 }
                 CelValue::TimeStamp(v1) => match rhs {
-                    CelValue::Duration(v2) => return CelValue::from_timestamp(v1 - v2),
-                    CelValue::TimeStamp(v2) => return CelValue::from_duration(v1 - v2),
+                    CelValue::Duration(v2) => return checked_timestamp(v1.checked_sub_signed(v2)),
+                    CelValue::TimeStamp(v2) => {
+                        return checked_duration(Some(v1.signed_duration_since(v2)))
+                    }
                     _ => {}
                 },
                 CelValue::Duration(v1) => match rhs {
-                    CelValue::TimeStamp(v2) => return CelValue::from_timestamp(v2 - v1),
-                    CelValue::Duration(v2) => return CelValue::from_duration(v1 - v2),
+                    CelValue::TimeStamp(v2) => return checked_timestamp(v2.checked_sub_signed(v1)),
+                    CelValue::Duration(v2) => return checked_duration(v1.checked_sub(&v2)),
                     _ => {}
                 },
                 _ => {}
@@ -1352,6 +1421,10 @@ impl Mul for CelValue {
 
     fn mul(self, rhs_val: Self) -> Self::Output {
         self.error_prop_or(rhs_val, |lhs_val, rhs_val| {
+            if let Some(res) = CelQuantity::mul(&lhs_val, &rhs_val) {
+                return res;
+            }
+
             let type1 = lhs_val.as_type();
             let type2 = rhs_val.as_type();
 
@@ -1364,12 +1437,12 @@ impl Mul for CelValue {
             match lhs {
                 CelValue::Int(val1) => {
                     if let CelValue::Int(val2) = rhs {
-                        return CelValue::from(val1 * val2);
+                        return checked_int(val1.checked_mul(val2), val1, "*", val2);
                     }
                 }
                 CelValue::UInt(val1) => {
                     if let CelValue::UInt(val2) = rhs {
-                        return CelValue::from(val1 * val2);
+                        return checked_int(val1.checked_mul(val2), val1, "*", val2);
                     }
                 }
                 CelValue::Float(val1) => {
@@ -1393,6 +1466,10 @@ impl Div for CelValue {
 
     fn div(self, rhs_val: Self) -> Self::Output {
         self.error_prop_or(rhs_val, |lhs_val, rhs_val| {
+            if let Some(res) = CelQuantity::div(&lhs_val, &rhs_val) {
+                return res;
+            }
+
             let type1 = lhs_val.as_type();
             let type2 = rhs_val.as_type();
 
@@ -1409,7 +1486,7 @@ impl Div for CelValue {
                             return CelValue::from_err(CelError::DivideByZero);
                         }
 
-                        return CelValue::from(val1 / val2);
+                        return checked_int(val1.checked_div(val2), val1, "/", val2);
                     }
                 }
                 CelValue::UInt(val1) => {
@@ -1418,7 +1495,7 @@ impl Div for CelValue {
                             return CelValue::from_err(CelError::DivideByZero);
                         }
 
-                        return CelValue::from(val1 / val2);
+                        return checked_int(val1.checked_div(val2), val1, "/", val2);
                     }
                 }
                 CelValue::Float(val1) => {
@@ -1454,12 +1531,20 @@ impl Rem for CelValue {
             match lhs {
                 CelValue::Int(val1) => {
                     if let CelValue::Int(val2) = rhs {
-                        return CelValue::from(val1 % val2);
+                        if val2 == 0 {
+                            return CelValue::from_err(CelError::DivideByZero);
+                        }
+
+                        return checked_int(val1.checked_rem(val2), val1, "%", val2);
                     }
                 }
                 CelValue::UInt(val1) => {
                     if let CelValue::UInt(val2) = rhs {
-                        return CelValue::from(val1 % val2);
+                        if val2 == 0 {
+                            return CelValue::from_err(CelError::DivideByZero);
+                        }
+
+                        return checked_int(val1.checked_rem(val2), val1, "%", val2);
                     }
                 }
                 _ => {}
@@ -1481,11 +1566,18 @@ impl Neg for CelValue {
             return self.clone();
         }
 
+        if let Some(res) = CelQuantity::neg(&self) {
+            return res;
+        }
+
         let type1 = self.as_type();
 
         match self {
             CelValue::Int(val1) => {
-                return CelValue::from(-val1);
+                return match val1.checked_neg() {
+                    Some(val) => CelValue::from(val),
+                    None => CelValue::from_err(CelError::overflow(&format!("-({})", val1))),
+                };
             }
             CelValue::Float(val1) => {
                 return CelValue::from(-val1);
@@ -0,0 +1,102 @@
+use std::{any::Any, fmt, sync::Arc};
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use chrono_tz::Tz;
+
+use crate::{CelError, CelValue, CelValueDyn};
+
+/// A timestamp seen in a timezone, `ts.inZone('America/New_York')`. Zoned timestamps
+/// are carried as `CelValue::Dyn` values; they equal, order and subtract like the
+/// instant they refer to, and time functions called on them use their zone.
+#[derive(Debug, Clone, Copy)]
+pub struct CelZonedTimestamp {
+    instant: DateTime<Utc>,
+    zone: Tz,
+}
+
+impl CelZonedTimestamp {
+    pub fn new(instant: DateTime<Utc>, zone: Tz) -> CelZonedTimestamp {
+        CelZonedTimestamp { instant, zone }
+    }
+
+    pub fn instant(&self) -> DateTime<Utc> {
+        self.instant
+    }
+
+    pub fn zone(&self) -> Tz {
+        self.zone
+    }
+
+    pub fn local(&self) -> DateTime<Tz> {
+        self.instant.with_timezone(&self.zone)
+    }
+
+    /// Returns the zoned timestamp wrapped by `value`, if it is one.
+    pub fn from_cel_value(value: &CelValue) -> Option<&CelZonedTimestamp> {
+        match value {
+            CelValue::Dyn(d) => d.any_ref().downcast_ref::<CelZonedTimestamp>(),
+            _ => None,
+        }
+    }
+
+    /// The instant of a zoned timestamp as a plain timestamp, other values unchanged.
+    pub(crate) fn unzone(value: CelValue) -> CelValue {
+        match CelZonedTimestamp::from_cel_value(&value) {
+            Some(zoned) => CelValue::from_timestamp(zoned.instant),
+            None => value,
+        }
+    }
+
+    /// The zone of whichever operand is zoned, the left one when both are.
+    pub(crate) fn zone_of(lhs: &CelValue, rhs: &CelValue) -> Option<Tz> {
+        CelZonedTimestamp::from_cel_value(lhs)
+            .or_else(|| CelZonedTimestamp::from_cel_value(rhs))
+            .map(|zoned| zoned.zone)
+    }
+
+    /// Puts a timestamp into `zone`, other values unchanged.
+    pub(crate) fn rezone(value: CelValue, zone: Tz) -> CelValue {
+        match value {
+            CelValue::TimeStamp(instant) => CelZonedTimestamp::new(instant, zone).into(),
+            other => other,
+        }
+    }
+}
+
+impl From<CelZonedTimestamp> for CelValue {
+    fn from(value: CelZonedTimestamp) -> Self {
+        CelValue::from_dyn(Arc::new(value))
+    }
+}
+
+/// RFC 3339 with the zone's offset, like `2024-03-10T01:30:00-05:00`.
+impl fmt::Display for CelZonedTimestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.local().to_rfc3339_opts(SecondsFormat::AutoSi, false))
+    }
+}
+
+impl CelValueDyn for CelZonedTimestamp {
+    fn as_type(&self) -> CelValue {
+        CelValue::Type("zoned_timestamp".to_owned())
+    }
+
+    fn access(&self, key: &str) -> CelValue {
+        CelValue::from_err(CelError::attribute("zoned_timestamp", key))
+    }
+
+    fn eq(&self, rhs: &CelValue) -> CelValue {
+        match CelZonedTimestamp::unzone(rhs.clone()) {
+            CelValue::TimeStamp(r) => CelValue::from_bool(self.instant == r),
+            _ => CelValue::false_(),
+        }
+    }
+
+    fn is_truthy(&self) -> bool {
+        true
+    }
+
+    fn any_ref<'a>(&'a self) -> &'a dyn Any {
+        self
+    }
+}
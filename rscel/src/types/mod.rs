@@ -1,11 +1,18 @@
 pub mod cel_byte_code;
 pub mod cel_bytes;
 pub mod cel_error;
+pub mod cel_optional;
+pub mod cel_quantity;
+pub(crate) mod cel_time;
 pub mod cel_value;
 pub mod cel_value_dyn;
+pub mod cel_zoned_timestamp;
 
 pub use cel_byte_code::CelByteCode;
 pub use cel_bytes::CelBytes;
 pub use cel_error::{CelError, CelResult};
+pub use cel_optional::CelOptional;
+pub use cel_quantity::CelQuantity;
 pub use cel_value::CelValue;
 pub use cel_value_dyn::CelValueDyn;
+pub use cel_zoned_timestamp::CelZonedTimestamp;
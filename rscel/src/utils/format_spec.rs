@@ -0,0 +1,547 @@
+use std::fmt;
+
+use chrono::format::{Item, StrftimeItems};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    context::construct_type, types::CelZonedTimestamp, CelError, CelResult, CelValue, CelValueDyn,
+};
+
+/// A python style format spec as used in format string placeholders,
+/// `[[fill]align][sign][#][0][width][grouping][.precision][type]`. Specs
+/// containing strftime directives are used to format timestamps.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum FormatSpec {
+    Standard(StandardFormat),
+    Strftime(String),
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StandardFormat {
+    fill: Option<char>,
+    align: Option<char>,
+    sign: Option<char>,
+    alternate: bool,
+    zero: bool,
+    width: Option<usize>,
+    grouping: Option<char>,
+    precision: Option<usize>,
+    ty: Option<char>,
+}
+
+impl Default for FormatSpec {
+    fn default() -> Self {
+        FormatSpec::Standard(StandardFormat::default())
+    }
+}
+
+impl FormatSpec {
+    /// Parses and validates a format spec.
+    pub fn parse(spec: &str) -> CelResult<FormatSpec> {
+        match StandardFormat::parse(spec) {
+            Some(standard) => {
+                standard.validate()?;
+                Ok(FormatSpec::Standard(standard))
+            }
+            None if spec.contains('%') => {
+                if StrftimeItems::new(spec).any(|item| matches!(item, Item::Error)) {
+                    Err(CelError::value(&format!(
+                        "Invalid strftime format specifier '{}'",
+                        spec
+                    )))
+                } else {
+                    Ok(FormatSpec::Strftime(spec.to_owned()))
+                }
+            }
+            None => Err(CelError::value(&format!(
+                "Invalid format specifier '{}'",
+                spec
+            ))),
+        }
+    }
+
+    /// Formats a value according to this spec.
+    pub fn format(&self, value: CelValue) -> CelResult<String> {
+        match self {
+            FormatSpec::Standard(standard) => standard.format(value),
+            FormatSpec::Strftime(pattern) => match value {
+                CelValue::TimeStamp(ts) => Ok(ts.format(pattern).to_string()),
+                CelValue::Err(err) => Err(err),
+                other => match CelZonedTimestamp::from_cel_value(&other) {
+                    Some(zoned) => Ok(zoned.local().format(pattern).to_string()),
+                    None => Err(CelError::value(&format!(
+                        "strftime format specifier requires a timestamp, got {}",
+                        other.as_type()
+                    ))),
+                },
+            },
+        }
+    }
+}
+
+impl fmt::Display for FormatSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FormatSpec::Standard(standard) => {
+                if let Some(fill) = standard.fill {
+                    write!(f, "{}", fill)?;
+                }
+                for c in [standard.align, standard.sign].into_iter().flatten() {
+                    write!(f, "{}", c)?;
+                }
+                if standard.alternate {
+                    write!(f, "#")?;
+                }
+                if standard.zero {
+                    write!(f, "0")?;
+                }
+                if let Some(width) = standard.width {
+                    write!(f, "{}", width)?;
+                }
+                if let Some(grouping) = standard.grouping {
+                    write!(f, "{}", grouping)?;
+                }
+                if let Some(precision) = standard.precision {
+                    write!(f, ".{}", precision)?;
+                }
+                if let Some(ty) = standard.ty {
+                    write!(f, "{}", ty)?;
+                }
+                Ok(())
+            }
+            FormatSpec::Strftime(pattern) => write!(f, "{}", pattern),
+        }
+    }
+}
+
+impl StandardFormat {
+    fn parse(spec: &str) -> Option<StandardFormat> {
+        let chars: Vec<char> = spec.chars().collect();
+        let mut res = StandardFormat::default();
+        let mut i = 0;
+
+        let is_align = |c: Option<&char>| matches!(c, Some('<' | '>' | '^' | '='));
+        if is_align(chars.get(1)) {
+            res.fill = Some(chars[0]);
+            res.align = Some(chars[1]);
+            i = 2;
+        } else if is_align(chars.first()) {
+            res.align = Some(chars[0]);
+            i = 1;
+        }
+
+        if let Some(c @ ('+' | '-' | ' ')) = chars.get(i) {
+            res.sign = Some(*c);
+            i += 1;
+        }
+
+        if let Some('#') = chars.get(i) {
+            res.alternate = true;
+            i += 1;
+        }
+
+        if let Some('0') = chars.get(i) {
+            res.zero = true;
+            i += 1;
+        }
+
+        res.width = parse_number(&chars, &mut i);
+
+        if let Some(c @ (',' | '_')) = chars.get(i) {
+            res.grouping = Some(*c);
+            i += 1;
+        }
+
+        if let Some('.') = chars.get(i) {
+            i += 1;
+            res.precision = Some(parse_number(&chars, &mut i)?);
+        }
+
+        if let Some(
+            c @ ('s' | 'd' | 'b' | 'o' | 'x' | 'X' | 'e' | 'E' | 'f' | 'F' | 'g' | 'G' | '%'),
+        ) = chars.get(i)
+        {
+            res.ty = Some(*c);
+            i += 1;
+        }
+
+        if i == chars.len() {
+            Some(res)
+        } else {
+            None
+        }
+    }
+
+    fn validate(&self) -> CelResult<()> {
+        match self.ty {
+            Some(ty @ ('d' | 'b' | 'o' | 'x' | 'X')) => {
+                if self.precision.is_some() {
+                    return Err(CelError::value(
+                        "Precision not allowed in integer format specifier",
+                    ));
+                }
+                if self.grouping == Some(',') && ty != 'd' {
+                    return Err(CelError::value(&format!(
+                        "Cannot specify ',' with '{}'",
+                        ty
+                    )));
+                }
+            }
+            Some('s') => {
+                if self.sign.is_some() {
+                    return Err(CelError::value(
+                        "Sign not allowed in string format specifier",
+                    ));
+                }
+                if self.alternate {
+                    return Err(CelError::value(
+                        "Alternate form (#) not allowed in string format specifier",
+                    ));
+                }
+                if self.grouping.is_some() {
+                    return Err(CelError::value(
+                        "Grouping not allowed in string format specifier",
+                    ));
+                }
+                if self.align == Some('=') {
+                    return Err(CelError::value(
+                        "'=' alignment not allowed in string format specifier",
+                    ));
+                }
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn format(&self, value: CelValue) -> CelResult<String> {
+        match (value, self.ty) {
+            (CelValue::Err(err), _) => Err(err),
+            (value, None | Some('s')) if self.ty.is_some() || !is_number(&value) => {
+                let text = display(value)?;
+                let text = match self.precision {
+                    Some(precision) => text.chars().take(precision).collect(),
+                    None => text,
+                };
+                Ok(self.pad("", &text, false))
+            }
+            (CelValue::Int(i), Some('d' | 'b' | 'o' | 'x' | 'X') | None) => {
+                Ok(self.format_int(i < 0, i.unsigned_abs()))
+            }
+            (CelValue::UInt(u), Some('d' | 'b' | 'o' | 'x' | 'X') | None) => {
+                Ok(self.format_int(false, u))
+            }
+            (CelValue::Int(i), Some(ty)) => Ok(self.format_float(i as f64, ty)),
+            (CelValue::UInt(u), Some(ty)) => Ok(self.format_float(u as f64, ty)),
+            (CelValue::Float(f), None) if self.precision.is_none() => {
+                let text = f.abs().to_string();
+                let sign = self.sign_str(f.is_sign_negative() && !f.is_nan());
+                Ok(self.pad(sign, &self.group(text), true))
+            }
+            (CelValue::Float(f), None) => Ok(self.format_float(f, 'g')),
+            (CelValue::Float(f), Some(ty @ ('e' | 'E' | 'f' | 'F' | 'g' | 'G' | '%'))) => {
+                Ok(self.format_float(f, ty))
+            }
+            (value, ty) => Err(CelError::value(&format!(
+                "Unknown format code '{}' for {}",
+                ty.unwrap_or('s'),
+                value.as_type()
+            ))),
+        }
+    }
+
+    fn format_int(&self, negative: bool, magnitude: u64) -> String {
+        let (digits, prefix, group_size) = match self.ty {
+            Some('b') => (format!("{:b}", magnitude), "0b", 4),
+            Some('o') => (format!("{:o}", magnitude), "0o", 4),
+            Some('x') => (format!("{:x}", magnitude), "0x", 4),
+            Some('X') => (format!("{:X}", magnitude), "0X", 4),
+            _ => (magnitude.to_string(), "", 3),
+        };
+
+        let digits = match self.grouping {
+            Some(sep) => group_digits(&digits, sep, group_size),
+            None => digits,
+        };
+
+        let mut sign = self.sign_str(negative).to_owned();
+        if self.alternate {
+            sign.push_str(prefix);
+        }
+
+        self.pad(&sign, &digits, true)
+    }
+
+    fn format_float(&self, val: f64, ty: char) -> String {
+        let precision = self.precision.unwrap_or(6);
+        let upper = matches!(ty, 'E' | 'F' | 'G');
+        let abs = val.abs();
+
+        let body = if val.is_nan() {
+            "nan".to_owned()
+        } else if val.is_infinite() {
+            "inf".to_owned()
+        } else {
+            match ty {
+                'e' | 'E' => scientific(abs, precision),
+                '%' => format!("{:.*}%", precision, abs * 100.0),
+                'g' | 'G' => self.general(abs, precision),
+                _ => {
+                    let mut text = format!("{:.*}", precision, abs);
+                    if self.alternate && precision == 0 {
+                        text.push('.');
+                    }
+                    text
+                }
+            }
+        };
+
+        let body = if upper { body.to_uppercase() } else { body };
+        let sign = self.sign_str(val.is_sign_negative() && !val.is_nan());
+
+        self.pad(sign, &self.group(body), true)
+    }
+
+    /// Python's general format, fixed or scientific notation depending on
+    /// the exponent with insignificant trailing zeros removed.
+    fn general(&self, abs: f64, precision: usize) -> String {
+        let precision = precision.max(1);
+        let exp = if abs == 0.0 {
+            0
+        } else {
+            let sci = format!("{:.*e}", precision - 1, abs);
+            sci[sci.find('e').unwrap_or(0) + 1..].parse().unwrap_or(0)
+        };
+
+        let text = if exp >= -4 && exp < precision as i32 {
+            format!("{:.*}", (precision as i32 - 1 - exp) as usize, abs)
+        } else {
+            scientific(abs, precision - 1)
+        };
+
+        if self.alternate {
+            return text;
+        }
+
+        let (mantissa, exponent) = match text.find('e') {
+            Some(pos) => text.split_at(pos),
+            None => (text.as_str(), ""),
+        };
+
+        let mantissa = if mantissa.contains('.') {
+            mantissa.trim_end_matches('0').trim_end_matches('.')
+        } else {
+            mantissa
+        };
+
+        format!("{}{}", mantissa, exponent)
+    }
+
+    /// Applies grouping to the integer part of a formatted float.
+    fn group(&self, text: String) -> String {
+        let sep = match self.grouping {
+            Some(sep) => sep,
+            None => return text,
+        };
+
+        let end = text
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(text.len());
+        let (int_part, rest) = text.split_at(end);
+
+        format!("{}{}", group_digits(int_part, sep, 3), rest)
+    }
+
+    fn sign_str(&self, negative: bool) -> &'static str {
+        match (negative, self.sign) {
+            (true, _) => "-",
+            (false, Some('+')) => "+",
+            (false, Some(' ')) => " ",
+            _ => "",
+        }
+    }
+
+    fn pad(&self, sign: &str, body: &str, numeric: bool) -> String {
+        let len = sign.chars().count() + body.chars().count();
+        let width = self.width.unwrap_or(0);
+
+        if len >= width {
+            return format!("{}{}", sign, body);
+        }
+
+        let (fill, align) = match (self.fill, self.align) {
+            (Some(fill), Some(align)) => (fill, align),
+            (None, Some(align)) => (if self.zero { '0' } else { ' ' }, align),
+            (_, None) if self.zero => ('0', '='),
+            (_, None) => (' ', if numeric { '>' } else { '<' }),
+        };
+
+        let padding = width - len;
+        let fill_str = |n: usize| fill.to_string().repeat(n);
+
+        match align {
+            '<' => format!("{}{}{}", sign, body, fill_str(padding)),
+            '^' => format!(
+                "{}{}{}{}",
+                fill_str(padding / 2),
+                sign,
+                body,
+                fill_str(padding - padding / 2)
+            ),
+            '=' => format!("{}{}{}", sign, fill_str(padding), body),
+            _ => format!("{}{}{}", fill_str(padding), sign, body),
+        }
+    }
+}
+
+fn parse_number(chars: &[char], i: &mut usize) -> Option<usize> {
+    let start = *i;
+    while chars.get(*i).is_some_and(|c| c.is_ascii_digit()) {
+        *i += 1;
+    }
+
+    chars[start..*i].iter().collect::<String>().parse().ok()
+}
+
+fn group_digits(digits: &str, sep: char, size: usize) -> String {
+    let len = digits.chars().count();
+    let mut res = String::new();
+
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (len - i).is_multiple_of(size) {
+            res.push(sep);
+        }
+        res.push(c);
+    }
+
+    res
+}
+
+/// Scientific notation with python's sign and two digit minimum exponent.
+pub(crate) fn scientific(abs: f64, precision: usize) -> String {
+    let text = format!("{:.*e}", precision, abs);
+    let (mantissa, exp) = text.split_at(text.find('e').unwrap_or(text.len()));
+    let exp: i32 = exp.get(1..).and_then(|e| e.parse().ok()).unwrap_or(0);
+
+    format!(
+        "{}e{}{:02}",
+        mantissa,
+        if exp < 0 { '-' } else { '+' },
+        exp.abs()
+    )
+}
+
+fn is_number(value: &CelValue) -> bool {
+    matches!(
+        value,
+        CelValue::Int(_) | CelValue::UInt(_) | CelValue::Float(_)
+    )
+}
+
+/// Converts a value to its string form for a format string. Values `string()`
+/// can not convert are rendered as CEL literals.
+fn display(value: CelValue) -> CelResult<String> {
+    match value {
+        CelValue::String(s) => Ok(s),
+        CelValue::Bool(b) => Ok(b.to_string()),
+        CelValue::Null => Ok("null".to_owned()),
+        CelValue::Type(t) => Ok(t),
+        CelValue::List(list) => Ok(format!(
+            "[{}]",
+            list.into_iter()
+                .map(literal)
+                .collect::<CelResult<Vec<_>>>()?
+                .join(", ")
+        )),
+        CelValue::Map(map) => {
+            let mut entries = map.into_iter().collect::<Vec<_>>();
+            entries.sort_by(|a, b| a.0.cmp(&b.0));
+
+            Ok(format!(
+                "{{{}}}",
+                entries
+                    .into_iter()
+                    .map(|(k, v)| Ok(format!("{:?}: {}", k, literal(v)?)))
+                    .collect::<CelResult<Vec<_>>>()?
+                    .join(", ")
+            ))
+        }
+        other => match construct_type("string", vec![other]).into_result()? {
+            CelValue::String(s) => Ok(s),
+            other => Ok(other.to_string()),
+        },
+    }
+}
+
+fn literal(value: CelValue) -> CelResult<String> {
+    match value {
+        CelValue::String(s) => Ok(format!("{:?}", s)),
+        other => display(other),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use test_case::test_case;
+
+    use super::FormatSpec;
+    use crate::CelValue;
+
+    #[test_case(1.23456.into(), ".2f", "1.23")]
+    #[test_case(42.into(), ">5", "   42")]
+    #[test_case(42.into(), "<5", "42   ")]
+    #[test_case(42.into(), "*^6", "**42**")]
+    #[test_case((-42).into(), "06", "-00042")]
+    #[test_case(42.into(), "+", "+42")]
+    #[test_case(255.into(), "x", "ff")]
+    #[test_case(255.into(), "#X", "0XFF")]
+    #[test_case(255u64.into(), "#010b", "0b11111111")]
+    #[test_case(5.into(), "o", "5")]
+    #[test_case(1234567.into(), ",", "1,234,567")]
+    #[test_case(1234567.5.into(), "_.1f", "1_234_567.5")]
+    #[test_case(12345.678.into(), ".3e", "1.235e+04")]
+    #[test_case(0.0001.into(), "E", "1.000000E-04")]
+    #[test_case(0.25.into(), ".1%", "25.0%")]
+    #[test_case(1234.5.into(), "g", "1234.5")]
+    #[test_case(1e20.into(), "g", "1e+20")]
+    #[test_case(0.00001.into(), ".3g", "1e-05")]
+    #[test_case(2.5.into(), "", "2.5")]
+    #[test_case(f64::NAN.into(), "f", "nan")]
+    #[test_case(f64::NEG_INFINITY.into(), "F", "-INF")]
+    #[test_case(3.into(), ".2f", "3.00")]
+    #[test_case("hello".into(), ".3", "hel")]
+    #[test_case("hi".into(), ">4", "  hi")]
+    #[test_case("hi".into(), "4", "hi  ")]
+    #[test_case(true.into(), "", "true")]
+    #[test_case(CelValue::Null, "", "null")]
+    #[test_case(CelValue::from_val_slice(&[1.into(), "a".into()]), "", "[1, \"a\"]")]
+    fn test_format(value: CelValue, spec: &str, expected: &str) {
+        let spec = FormatSpec::parse(spec).unwrap();
+        assert_eq!(spec.format(value).unwrap(), expected);
+    }
+
+    #[test_case(".2d"; "precision with int")]
+    #[test_case("+s"; "sign with string")]
+    #[test_case(",x"; "comma with hex")]
+    #[test_case(".f"; "missing precision")]
+    #[test_case("abc"; "garbage")]
+    #[test_case("%Q"; "bad strftime")]
+    fn test_invalid(spec: &str) {
+        assert!(FormatSpec::parse(spec).is_err());
+    }
+
+    #[test_case("d", "abc".into())]
+    #[test_case("x", 1.5.into())]
+    #[test_case("%Y", 1.into())]
+    fn test_type_mismatch(spec: &str, value: CelValue) {
+        assert!(FormatSpec::parse(spec).unwrap().format(value).is_err());
+    }
+
+    #[test_case(".2f"; "precision")]
+    #[test_case("*^+#010_.3e"; "everything")]
+    #[test_case("%Y-%m-%d"; "strftime")]
+    fn test_display(spec: &str) {
+        assert_eq!(FormatSpec::parse(spec).unwrap().to_string(), spec);
+    }
+}
@@ -1,7 +1,12 @@
 mod eval_utils;
+mod format_spec;
 mod ident_filter;
+mod qualified_names;
 mod scoped_counter;
 
 pub use eval_utils::eval_ident;
+pub(crate) use format_spec::scientific;
+pub use format_spec::FormatSpec;
 pub use ident_filter::IdentFilterIter;
+pub use qualified_names::qualified_names;
 pub use scoped_counter::ScopedCounter;
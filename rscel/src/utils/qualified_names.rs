@@ -0,0 +1,41 @@
+/// Candidate names for `name` resolved within `container`, most qualified first.
+///
+/// With a container of `a.b` the name `c` resolves against `a.b.c`, `a.c` and `c`,
+/// in that order.
+pub fn qualified_names(container: Option<&str>, name: &str) -> Vec<String> {
+    let mut names = Vec::new();
+
+    if let Some(container) = container.filter(|c| !c.is_empty()) {
+        let mut scope = container;
+        loop {
+            names.push(format!("{}.{}", scope, name));
+
+            match scope.rfind('.') {
+                Some(i) => scope = &scope[..i],
+                None => break,
+            }
+        }
+    }
+
+    names.push(name.to_owned());
+    names
+}
+
+#[cfg(test)]
+mod test {
+    use super::qualified_names;
+
+    #[test]
+    fn no_container() {
+        assert_eq!(qualified_names(None, "x.y"), vec!["x.y"]);
+        assert_eq!(qualified_names(Some(""), "y"), vec!["y"]);
+    }
+
+    #[test]
+    fn nested_container() {
+        assert_eq!(
+            qualified_names(Some("pkg.sub"), "Msg"),
+            vec!["pkg.sub.Msg", "pkg.Msg", "Msg"]
+        );
+    }
+}
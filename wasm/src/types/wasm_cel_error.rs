@@ -78,6 +78,10 @@ impl WasmCelError {
             CelError::DivideByZero => {
                 js_sys::Reflect::set(&val, &"type".into(), &"divide by zero".into()).unwrap();
             }
+            CelError::Overflow(msg) => {
+                js_sys::Reflect::set(&val, &"type".into(), &"overflow".into()).unwrap();
+                js_sys::Reflect::set(&val, &"msg".into(), &msg.into()).unwrap();
+            }
         };
 
         val.into()